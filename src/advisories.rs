@@ -0,0 +1,50 @@
+use std::collections::BTreeMap;
+
+use comfy_table::{Cell, Color, Table};
+
+use crate::render;
+use crate::scan::OrgReport;
+
+pub const SEVERITIES: [&str; 5] = ["critical", "high", "medium", "low", "unknown"];
+
+pub fn table(report: &OrgReport) -> Table {
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Repository").fg(Color::Green),
+        Cell::new("Advisory").fg(Color::Green),
+        Cell::new("Severity").fg(Color::Green),
+        Cell::new("Published").fg(Color::Green),
+        Cell::new("Summary").fg(Color::Green),
+    ]);
+    for (repo, _) in &report.rows {
+        for advisory in repo.advisories.iter().flatten() {
+            table.add_row(vec![
+                Cell::new(&repo.name),
+                Cell::new(&advisory.ghsa_id),
+                Cell::new(&advisory.severity),
+                Cell::new(advisory.published_at.format("%Y-%m-%d")),
+                Cell::new(&advisory.summary),
+            ]);
+        }
+    }
+    table
+}
+
+pub fn yearly_table(by_year: &BTreeMap<String, BTreeMap<String, u32>>) -> Table {
+    let mut table = render::new_table();
+    let mut header = vec![Cell::new("Year").fg(Color::Green)];
+    header.extend(SEVERITIES.iter().map(|s| Cell::new(s).fg(Color::Green)));
+    header.push(Cell::new("Total").fg(Color::Green));
+    table.set_header(header);
+    for (year, severities) in by_year.iter().rev() {
+        let mut row = vec![Cell::new(year)];
+        row.extend(
+            SEVERITIES
+                .iter()
+                .map(|s| Cell::new(severities.get(*s).copied().unwrap_or(0))),
+        );
+        row.push(Cell::new(severities.values().sum::<u32>()));
+        table.add_row(row);
+    }
+    table
+}
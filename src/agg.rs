@@ -0,0 +1,154 @@
+use chrono::{TimeZone, Utc};
+use clap::ValueEnum;
+
+use crate::columns::Column;
+use crate::format::Style;
+use crate::stats::RepoStats;
+
+pub trait Aggregation {
+    fn label(&self) -> &'static str;
+    // Called with at least one value, sorted ascending
+    fn apply(&self, sorted: &[f64]) -> f64;
+}
+
+struct Sum;
+struct Min;
+struct Max;
+struct Avg;
+struct Median;
+
+impl Aggregation for Sum {
+    fn label(&self) -> &'static str {
+        "Sum"
+    }
+
+    fn apply(&self, sorted: &[f64]) -> f64 {
+        sorted.iter().sum()
+    }
+}
+
+impl Aggregation for Min {
+    fn label(&self) -> &'static str {
+        "Min"
+    }
+
+    fn apply(&self, sorted: &[f64]) -> f64 {
+        sorted[0]
+    }
+}
+
+impl Aggregation for Max {
+    fn label(&self) -> &'static str {
+        "Max"
+    }
+
+    fn apply(&self, sorted: &[f64]) -> f64 {
+        sorted[sorted.len() - 1]
+    }
+}
+
+impl Aggregation for Avg {
+    fn label(&self) -> &'static str {
+        "Avg"
+    }
+
+    fn apply(&self, sorted: &[f64]) -> f64 {
+        sorted.iter().sum::<f64>() / sorted.len() as f64
+    }
+}
+
+impl Aggregation for Median {
+    fn label(&self) -> &'static str {
+        "Median"
+    }
+
+    fn apply(&self, sorted: &[f64]) -> f64 {
+        let n = sorted.len();
+        if n % 2 == 0 {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+        } else {
+            sorted[n / 2]
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Agg {
+    Sum,
+    Min,
+    Max,
+    Avg,
+    Median,
+}
+
+impl Agg {
+    fn aggregation(&self) -> &'static dyn Aggregation {
+        match self {
+            Agg::Sum => &Sum,
+            Agg::Min => &Min,
+            Agg::Max => &Max,
+            Agg::Avg => &Avg,
+            Agg::Median => &Median,
+        }
+    }
+}
+
+pub fn parse(specs: &[String]) -> Result<Vec<(Column, Agg)>, String> {
+    specs
+        .iter()
+        .map(|spec| {
+            let (column, agg) = spec
+                .split_once('=')
+                .ok_or(format!("Expected column=aggregation in --agg {spec}"))?;
+            let column = Column::from_str(column.trim(), true)
+                .map_err(|_| format!("Unknown column {column} in --agg"))?;
+            let agg = Agg::from_str(agg.trim(), true).map_err(|_| {
+                format!("Unknown aggregation {agg}, expected sum, min, max, avg or median")
+            })?;
+            Ok((column, agg))
+        })
+        .collect()
+}
+
+pub fn find(aggregations: &[(Column, Agg)], column: Column) -> Option<Agg> {
+    aggregations
+        .iter()
+        .find(|(c, _)| *c == column)
+        .map(|(_, agg)| *agg)
+}
+
+fn format(column: Column, value: f64, style: &Style) -> String {
+    match column {
+        Column::UpdatedAt | Column::PushedAt | Column::Created => {
+            match Utc.timestamp_opt(value.round() as i64, 0).single() {
+                Some(at) => style.time(at),
+                None => "".to_string(),
+            }
+        }
+        Column::Size => style.size(value.round() as u64),
+        _ => style.units.decimal(value),
+    }
+}
+
+// Aggregates the column's raw per-repository values, skipping repositories where it was not collected
+pub fn value(column: Column, agg: Agg, repos: &[&RepoStats], style: &Style) -> String {
+    let mut values: Vec<f64> = repos
+        .iter()
+        .map(|repo| column.sort_key(repo))
+        .filter(|value| *value >= 0)
+        .map(|value| value as f64)
+        .collect();
+    if values.is_empty() {
+        return "".to_string();
+    }
+    values.sort_by(f64::total_cmp);
+    format(column, agg.aggregation().apply(&values), style)
+}
+
+pub fn summary(column: Column, agg: Agg, repos: &[&RepoStats], style: &Style) -> String {
+    format!(
+        "{}: {}",
+        agg.aggregation().label(),
+        value(column, agg, repos, style)
+    )
+}
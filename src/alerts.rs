@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use comfy_table::{Cell, Color, Table};
+use serde::{Deserialize, Serialize};
+
+use crate::history::{self, Snapshot};
+use crate::render;
+use crate::stats::SumStats;
+use crate::watch;
+
+pub const STATE_PATH: &str = "out/alert_state.json";
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    Stars,
+    Forks,
+    Followers,
+    OpenIssues,
+    OpenPrs,
+    Size,
+    Health,
+    SecurityAlerts,
+    FailingCi,
+}
+
+impl Metric {
+    fn value(&self, stats: &SumStats) -> Option<f64> {
+        Some(match self {
+            Metric::Stars => stats.stars as f64,
+            Metric::Forks => stats.forks as f64,
+            Metric::Followers => stats.followers as f64,
+            Metric::OpenIssues => stats.open_issues_count as f64,
+            Metric::OpenPrs => stats.open_prs as f64,
+            Metric::Size => stats.size as f64,
+            Metric::Health => stats.health_avg()?,
+            Metric::SecurityAlerts => (stats.dependabot_alerts + stats.code_scanning_alerts) as f64,
+            Metric::FailingCi => stats.ci_failing as f64,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub enum Comparator {
+    #[serde(rename = "<")]
+    Lt,
+    #[serde(rename = "<=")]
+    Le,
+    #[serde(rename = ">")]
+    Gt,
+    #[serde(rename = ">=")]
+    Ge,
+}
+
+impl Comparator {
+    pub fn check(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparator::Lt => value < threshold,
+            Comparator::Le => value <= threshold,
+            Comparator::Gt => value > threshold,
+            Comparator::Ge => value >= threshold,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    pub name: String,
+    pub org: Option<String>,
+    pub metric: Metric,
+    pub comparator: Comparator,
+    pub threshold: f64,
+    pub window: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Silence {
+    pub rule: Option<String>,
+    pub org: Option<String>,
+    pub until: NaiveDate,
+}
+
+impl Silence {
+    fn covers(&self, rule: &str, org: &str, now: DateTime<Utc>) -> bool {
+        now.date_naive() <= self.until
+            && self.rule.as_deref().map(|r| r == rule).unwrap_or(true)
+            && self
+                .org
+                .as_deref()
+                .map(|o| o.eq_ignore_ascii_case(org))
+                .unwrap_or(true)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AlertState {
+    pub firing: bool,
+    pub since: DateTime<Utc>,
+    pub value: f64,
+}
+
+pub struct Transition {
+    pub rule: String,
+    pub org: String,
+    pub firing: bool,
+    pub value: f64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct State {
+    alerts: HashMap<String, AlertState>,
+}
+
+impl State {
+    pub fn load(path: &str) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self).expect("serialize alert state ok");
+        std::fs::write(path, content).map_err(|e| format!("Cannot write {path}: {e}"))
+    }
+
+    pub fn evaluate(
+        &mut self,
+        rules: &[Rule],
+        silences: &[Silence],
+        org: &str,
+        current: &SumStats,
+        history: &[Snapshot],
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Transition>, String> {
+        let mut transitions = vec![];
+        for rule in rules {
+            if let Some(rule_org) = &rule.org {
+                if !rule_org.eq_ignore_ascii_case(org) {
+                    continue;
+                }
+            }
+            // Silenced rules keep their last state so an expired silence still reports a change
+            if silences.iter().any(|s| s.covers(&rule.name, org, now)) {
+                continue;
+            }
+            let Some(mut value) = rule.metric.value(current) else {
+                continue;
+            };
+            if let Some(window) = &rule.window {
+                let window = chrono::Duration::from_std(watch::parse_interval(window)?)
+                    .map_err(|e| e.to_string())?;
+                let Some(previous) = history::latest_before(history, org, now - window)
+                    .and_then(|s| rule.metric.value(&s.stats))
+                else {
+                    continue;
+                };
+                value -= previous;
+            }
+            let firing = rule.comparator.check(value, rule.threshold);
+            let key = format!("{}/{}", rule.name, org.to_lowercase());
+            let was_firing = self.alerts.get(&key).map(|s| s.firing).unwrap_or(false);
+            if firing != was_firing {
+                transitions.push(Transition {
+                    rule: rule.name.clone(),
+                    org: org.to_string(),
+                    firing,
+                    value,
+                });
+                self.alerts.insert(
+                    key,
+                    AlertState {
+                        firing,
+                        since: now,
+                        value,
+                    },
+                );
+            } else if let Some(state) = self.alerts.get_mut(&key) {
+                state.value = value;
+            }
+        }
+        Ok(transitions)
+    }
+}
+
+pub fn table(transitions: &[Transition]) -> Table {
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Alert").fg(Color::Green),
+        Cell::new("Organization").fg(Color::Green),
+        Cell::new("State").fg(Color::Green),
+        Cell::new("Value").fg(Color::Green),
+    ]);
+    for transition in transitions {
+        let (state, color) = if transition.firing {
+            ("firing", Color::Red)
+        } else {
+            ("resolved", Color::Green)
+        };
+        table.add_row(vec![
+            Cell::new(&transition.rule),
+            Cell::new(&transition.org),
+            Cell::new(state).fg(color),
+            Cell::new(transition.value),
+        ]);
+    }
+    table
+}
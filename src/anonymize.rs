@@ -0,0 +1,126 @@
+use sha2::{Digest, Sha256};
+
+use crate::scan::{Context, OrgReport};
+use crate::stats::{OrgProfile, RepoStats, SumStats};
+
+const HASH_LEN: usize = 12;
+
+// Lowercased like GitHub names; the salt keeps hashes from being matched against known names
+pub fn name(salt: &str, name: &str) -> String {
+    let digest = Sha256::digest(format!("{salt}\0{}", name.to_lowercase()));
+    digest
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>()[..HASH_LEN]
+        .to_string()
+}
+
+fn salt(ctx: &Context) -> &str {
+    ctx.args.anonymize_salt.as_deref().unwrap_or_default()
+}
+
+// Names and people are hashed; free text, URLs and provider values that could identify the
+// repository are dropped
+pub fn repo(ctx: &Context, org: &str, repo: &RepoStats) -> RepoStats {
+    let mut repo = repo.clone();
+    repo.name = name(salt(ctx), &format!("{org}/{}", repo.name));
+    repo.description = None;
+    repo.homepage = None;
+    repo.topics = vec![];
+    repo.html_url = None;
+    repo.ssh_url = None;
+    repo.clone_url = None;
+    repo.owner = repo.owner.map(|owner| name(salt(ctx), &owner));
+    if let Some(owners) = repo.codeowners.as_mut() {
+        owners
+            .iter_mut()
+            .for_each(|owner| *owner = name(salt(ctx), owner));
+    }
+    if let Some(pushes) = repo.pushes.as_mut() {
+        for (actor, _) in pushes.by_actor.iter_mut() {
+            *actor = name(salt(ctx), actor);
+        }
+    }
+    // Hashed rather than dropped so contributor overlap still compares across repositories
+    if let Some(churn) = repo.deep.as_mut().and_then(|deep| deep.churn.as_mut()) {
+        churn
+            .authors
+            .iter_mut()
+            .for_each(|author| *author = name(salt(ctx), author));
+    }
+    repo.custom.clear();
+    repo
+}
+
+pub fn org(ctx: &Context, org: &str) -> String {
+    match ctx.args.anonymize {
+        true => name(salt(ctx), org),
+        false => org.to_string(),
+    }
+}
+
+fn sums(ctx: &Context, stats: &SumStats) -> SumStats {
+    let mut stats = stats.clone();
+    stats.topics.clear();
+    stats.code_owners = stats
+        .code_owners
+        .into_iter()
+        .map(|(owner, count)| (name(salt(ctx), &owner), count))
+        .collect();
+    stats
+}
+
+// The grand total across organizations, None when anonymization is off like `report`
+pub fn totals(ctx: &Context, stats: &SumStats) -> Option<SumStats> {
+    ctx.args.anonymize.then(|| sums(ctx, stats))
+}
+
+// None when anonymization is off, so callers keep using the original report
+pub fn report(ctx: &Context, report: &OrgReport) -> Option<OrgReport> {
+    if !ctx.args.anonymize {
+        return None;
+    }
+    let org = name(salt(ctx), &report.org);
+    Some(OrgReport {
+        org: org.clone(),
+        name: org,
+        created_year: report.created_year,
+        rows: report
+            .rows
+            .iter()
+            .map(|(repo, extra)| {
+                // Metadata and provider values such as owners or ticket links are hashed,
+                // which keeps grouping by them working
+                let extra = extra
+                    .iter()
+                    .map(|value| match value.is_empty() {
+                        true => String::new(),
+                        false => name(salt(ctx), value),
+                    })
+                    .collect();
+                (self::repo(ctx, &report.org, repo), extra)
+            })
+            .collect(),
+        unavailable: report
+            .unavailable
+            .iter()
+            .map(|(repo, reason)| {
+                (
+                    name(salt(ctx), &format!("{}/{repo}", report.org)),
+                    reason.clone(),
+                )
+            })
+            .collect(),
+        stats: sums(ctx, &report.stats),
+        groups: None,
+        violations: None,
+        members: report.members.clone(),
+        audit: None,
+        profile: report.profile.as_ref().map(|profile| OrgProfile {
+            description: None,
+            blog: None,
+            location: None,
+            ..profile.clone()
+        }),
+    })
+}
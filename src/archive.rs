@@ -0,0 +1,157 @@
+use chrono::{Duration, Months, Utc};
+use comfy_table::{Cell, Color};
+
+use crate::diagnostics::Kind;
+use crate::fetch;
+use crate::format;
+use crate::render;
+use crate::scan::{self, Context};
+use crate::stats::RepoStats;
+
+pub const TOPIC: &str = "archive-candidate";
+const ISSUE_TITLE: &str = "Archive candidate: no activity";
+
+pub struct Actions {
+    pub stale_days: i64,
+    pub topic: bool,
+    pub issue: bool,
+}
+
+async fn mark(
+    ctx: &Context,
+    org: &str,
+    repo: &RepoStats,
+    actions: &Actions,
+) -> Result<Vec<String>, String> {
+    let mut done = vec![];
+    if actions.topic && !repo.topics.iter().any(|t| t == TOPIC) {
+        let topics: Vec<String> = repo
+            .topics
+            .iter()
+            .cloned()
+            .chain([TOPIC.to_string()])
+            .collect();
+        fetch::set_topics(&ctx.github, org, &repo.name, &topics)
+            .await
+            .map_err(|e| e.to_string())?;
+        done.push("topic added".to_string());
+    }
+    if actions.issue {
+        let body = format!(
+            "This repository has had no push since {} and was flagged as an archive candidate. \
+             Close this issue if it is still maintained, otherwise archive the repository.",
+            repo.last_push().format("%Y-%m-%d")
+        );
+        match fetch::open_issue_once(&ctx.github, org, &repo.name, ISSUE_TITLE, &body).await {
+            Ok(Some(number)) => done.push(format!("issue #{number} opened")),
+            Ok(None) => done.push("issue already open".to_string()),
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    Ok(done)
+}
+
+// Repositories nobody starred, forked or pushed to are dead rather than just quiet
+pub async fn audit(
+    ctx: &Context,
+    orgs: &[String],
+    months: u32,
+    commands: bool,
+    apply: bool,
+    yes: bool,
+) -> Result<(), String> {
+    let now = Utc::now();
+    let cutoff = now - Months::new(months);
+    let mut archive_commands = vec![];
+    for org_name in orgs {
+        let report = match scan::scan_org(ctx, org_name, now).await {
+            Ok(report) => report,
+            Err(e) => {
+                ctx.diagnostics
+                    .emit(Kind::OrgNotFound, Some(org_name), None, &e.to_string());
+                continue;
+            }
+        };
+        let mut table = render::new_table();
+        table.set_header(vec![
+            Cell::new(&report.name).fg(Color::Green),
+            Cell::new("Last push").fg(Color::Green),
+            Cell::new("Archived").fg(Color::Green),
+        ]);
+        for (repo, _) in report
+            .rows
+            .iter()
+            .filter(|(repo, _)| repo.stars == 0 && repo.forks == 0 && repo.last_push() < cutoff)
+        {
+            let owner = repo.owner.as_deref().unwrap_or(org_name);
+            archive_commands.push(format!("gh repo archive {owner}/{} --yes", repo.name));
+            let archived = if apply && !yes {
+                Cell::new("planned")
+            } else if apply {
+                match fetch::archive_repo(&ctx.github, owner, &repo.name).await {
+                    Ok(()) => Cell::new("yes").fg(Color::Green),
+                    Err(e) => Cell::new(e).fg(Color::Red),
+                }
+            } else {
+                Cell::new("")
+            };
+            table.add_row(vec![
+                Cell::new(&repo.name),
+                Cell::new(format::day(repo.pushed_at)),
+                archived,
+            ]);
+        }
+        println!("{table}");
+    }
+    if commands {
+        for command in archive_commands {
+            println!("{command}");
+        }
+    }
+    if apply && !yes {
+        println!("Dry run, pass --yes to archive the candidates");
+    }
+    Ok(())
+}
+
+pub async fn run(ctx: &Context, orgs: &[String], actions: &Actions) -> Result<(), String> {
+    let now = Utc::now();
+    let cutoff = now - Duration::days(actions.stale_days);
+    for org_name in orgs {
+        let report = match scan::scan_org(ctx, org_name, now).await {
+            Ok(report) => report,
+            Err(e) => {
+                ctx.diagnostics
+                    .emit(Kind::OrgNotFound, Some(org_name), None, &e.to_string());
+                continue;
+            }
+        };
+        let mut table = render::new_table();
+        table.set_header(vec![
+            Cell::new(&report.name).fg(Color::Green),
+            Cell::new("Last push").fg(Color::Green),
+            Cell::new("Action").fg(Color::Green),
+        ]);
+        for (repo, _) in report
+            .rows
+            .iter()
+            .filter(|(repo, _)| repo.last_push() < cutoff)
+        {
+            let action = if !actions.topic && !actions.issue {
+                Cell::new("")
+            } else {
+                match mark(ctx, org_name, repo, actions).await {
+                    Ok(done) => Cell::new(done.join(", ")).fg(Color::Green),
+                    Err(e) => Cell::new(e).fg(Color::Red),
+                }
+            };
+            table.add_row(vec![
+                Cell::new(&repo.name),
+                Cell::new(format::day(repo.pushed_at)),
+                action,
+            ]);
+        }
+        println!("{table}");
+    }
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use comfy_table::{Cell, Color, Table};
+
+use crate::render;
+use crate::stats::AuditEvent;
+
+fn describe(action: &str) -> &str {
+    match action {
+        "repo.destroy" => "repository deleted",
+        "repo.access" => "visibility changed",
+        "org.remove_member" => "member removed",
+        action => action,
+    }
+}
+
+pub fn table(events: &[AuditEvent]) -> Table {
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Audit event").fg(Color::Green),
+        Cell::new("Subject").fg(Color::Green),
+        Cell::new("Actor").fg(Color::Green),
+        Cell::new("At").fg(Color::Green),
+    ]);
+    for event in events {
+        table.add_row(vec![
+            Cell::new(describe(&event.action)).fg(Color::Yellow),
+            Cell::new(event.subject.as_deref().unwrap_or_default()),
+            Cell::new(event.actor.as_deref().unwrap_or_default()),
+            Cell::new(event.at.format("%Y-%m-%d %H:%M")),
+        ]);
+    }
+    table
+}
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use clap::ValueEnum;
+use comfy_table::{Cell, Color, Table};
+
+use crate::columns::Column;
+use crate::format::Units;
+use crate::render;
+use crate::stats::SumStats;
+
+const KEY_COLUMN: &str = "real_org_name";
+
+// Metrics where a rise is bad; for the rest a drop is
+const LOWER_IS_BETTER: [Column; 12] = [
+    Column::OpenIssues,
+    Column::Issues,
+    Column::Prs,
+    Column::Security,
+    Column::Ci,
+    Column::Advisories,
+    Column::BranchProtection,
+    Column::IssueAge,
+    Column::Status,
+    Column::Traceability,
+    Column::ReviewLatency,
+    Column::Docs,
+];
+
+pub struct Delta {
+    pub current: f64,
+    pub previous: f64,
+}
+
+impl Delta {
+    pub fn change(&self) -> f64 {
+        self.current - self.previous
+    }
+
+    pub fn percent(&self) -> Option<f64> {
+        (self.previous != 0.0).then(|| self.change() / self.previous * 100.0)
+    }
+
+    pub fn arrow(&self) -> String {
+        let arrow = match self.change() {
+            c if c > 0.0 => "▲",
+            c if c < 0.0 => "▼",
+            _ => "=",
+        };
+        match self.percent() {
+            Some(percent) => format!("{arrow} {:+} ({percent:+.1}%)", self.change()),
+            None => format!("{arrow} {:+}", self.change()),
+        }
+    }
+
+    pub fn is_regression(&self, column: Column) -> bool {
+        match LOWER_IS_BETTER.contains(&column) {
+            true => self.change() > 0.0,
+            false => self.change() < 0.0,
+        }
+    }
+
+    pub fn color(&self, column: Column) -> Option<Color> {
+        match self.change() {
+            c if c == 0.0 => None,
+            _ if self.is_regression(column) => Some(Color::Red),
+            _ => Some(Color::Green),
+        }
+    }
+}
+
+/// `--fail-on-regression` rule: a column that must not get worse, optionally
+/// by more than a percentage of the baseline value
+pub struct Regression {
+    pub column: Column,
+    pub percent: f64,
+}
+
+impl Regression {
+    pub fn parse(rule: &str) -> Result<Self, String> {
+        let (name, percent) = match rule.split_once(':') {
+            Some((name, percent)) => (
+                name,
+                percent
+                    .trim()
+                    .trim_end_matches('%')
+                    .parse::<f64>()
+                    .map_err(|_| format!("Invalid regression percentage in {rule}"))?,
+            ),
+            None => (rule, 0.0),
+        };
+        let column = Column::from_str(name.trim(), true)
+            .map_err(|_| format!("Unknown column {}", name.trim()))?;
+        if column.csv_field().is_none() {
+            return Err(format!("Column {} has no numeric summary", name.trim()));
+        }
+        Ok(Self { column, percent })
+    }
+
+    fn check(&self, delta: &Delta) -> bool {
+        delta.is_regression(self.column)
+            && delta
+                .percent()
+                .is_none_or(|percent| percent.abs() > self.percent)
+    }
+}
+
+pub struct Baseline {
+    rows: HashMap<String, HashMap<String, String>>,
+}
+
+impl Baseline {
+    pub fn load(path: &Path, delimiter: char) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read baseline file {}: {e}", path.display()))?;
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter as u8)
+            .from_reader(content.trim_start_matches('\u{feff}').as_bytes());
+        let headers = reader
+            .headers()
+            .map_err(|e| format!("Invalid baseline CSV header: {e}"))?
+            .clone();
+        let key_index = headers
+            .iter()
+            .position(|h| h == KEY_COLUMN)
+            .ok_or(format!("Baseline CSV needs a {KEY_COLUMN} column"))?;
+        let mut rows = HashMap::new();
+        // Appended files hold several rows per organization; the last one wins
+        for record in reader.records() {
+            let record = record.map_err(|e| format!("Invalid baseline CSV row: {e}"))?;
+            let fields = headers
+                .iter()
+                .zip(record.iter())
+                .map(|(h, v)| (h.to_string(), v.to_string()))
+                .collect();
+            rows.insert(record[key_index].to_string(), fields);
+        }
+        Ok(Self { rows })
+    }
+
+    pub fn delta(
+        &self,
+        org_name: &str,
+        column: Column,
+        current: &SumStats,
+        units: &Units,
+    ) -> Option<Delta> {
+        let previous = self.rows.get(org_name)?.get(column.csv_field()?)?;
+        Some(Delta {
+            current: column.csv_value(current, units).parse().ok()?,
+            previous: previous.parse().ok()?,
+        })
+    }
+
+    pub fn regressions(
+        &self,
+        rules: &[Regression],
+        org_name: &str,
+        current: &SumStats,
+        units: &Units,
+    ) -> Vec<String> {
+        rules
+            .iter()
+            .filter_map(|rule| {
+                let delta = self.delta(org_name, rule.column, current, units)?;
+                rule.check(&delta).then(|| {
+                    format!(
+                        "{org_name}: {} {} -> {}",
+                        rule.column.csv_field().unwrap_or_default(),
+                        delta.previous,
+                        delta.current
+                    )
+                })
+            })
+            .collect()
+    }
+
+    pub fn table(
+        &self,
+        org_name: &str,
+        current: &SumStats,
+        columns: &[Column],
+        units: &Units,
+    ) -> Option<Table> {
+        let previous = self.rows.get(org_name)?;
+        let mut table = render::new_table();
+        table.set_header(vec![
+            Cell::new("Metric").fg(Color::Green),
+            Cell::new("Current").fg(Color::Green),
+            Cell::new("Baseline").fg(Color::Green),
+            Cell::new("Change").fg(Color::Green),
+        ]);
+        for column in columns {
+            let Some(field) = column.csv_field() else {
+                continue;
+            };
+            let Some(previous_value) = previous.get(field) else {
+                continue;
+            };
+            let current_value = column.csv_value(current, units);
+            let change = match self.delta(org_name, *column, current, units) {
+                Some(delta) => match delta.color(*column) {
+                    Some(color) => Cell::new(delta.arrow()).fg(color),
+                    None => Cell::new(delta.arrow()),
+                },
+                None if &current_value == previous_value => Cell::new("unchanged"),
+                None => Cell::new("changed"),
+            };
+            table.add_row(vec![
+                Cell::new(field),
+                Cell::new(&current_value),
+                Cell::new(previous_value),
+                change,
+            ]);
+        }
+        Some(table)
+    }
+}
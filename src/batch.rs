@@ -0,0 +1,91 @@
+use std::path::{Path, PathBuf};
+
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches};
+use serde::Deserialize;
+
+use crate::cli::{Cli, OutputFormat};
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Spec {
+    jobs: Vec<Job>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Job {
+    pub name: String,
+    #[serde(default)]
+    pub orgs: Vec<String>,
+    // Any report flags, e.g. filters and enrichments: ["--pushed-since", "90d", "--security"]
+    #[serde(default)]
+    pub args: Vec<String>,
+    pub format: Option<OutputFormat>,
+    pub out_dir: Option<PathBuf>,
+}
+
+// The client, the config, dates and table rendering are set up once for the whole batch,
+// so a job setting these would be silently ignored
+const BATCH_ONLY: [&str; 15] = [
+    "token",
+    "provider",
+    "api_url",
+    "parallelism",
+    "retries",
+    "retry_delay",
+    "request_timeout",
+    "request_budget",
+    "user_agent",
+    "request_log",
+    "config",
+    "timezone",
+    "date_format",
+    "plain",
+    "max_width",
+];
+
+pub fn load(path: &Path) -> Result<Vec<Job>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read job spec {}: {e}", path.display()))?;
+    let spec: Spec = serde_yaml::from_str(&content)
+        .map_err(|e| format!("Invalid job spec {}: {e}", path.display()))?;
+    if spec.jobs.is_empty() {
+        return Err(format!("Job spec {} has no jobs", path.display()));
+    }
+    Ok(spec.jobs)
+}
+
+impl Job {
+    pub fn cli(&self) -> Result<Cli, String> {
+        let argv = std::iter::once("github-repo-info")
+            .chain(self.args.iter().map(String::as_str))
+            .chain(self.orgs.iter().map(String::as_str));
+        let invalid = |e: clap::Error| format!("Invalid arguments in job {}: {e}", self.name);
+        let matches = Cli::command().try_get_matches_from(argv).map_err(invalid)?;
+        if let Some(id) = BATCH_ONLY
+            .iter()
+            .find(|id| matches.value_source(id) == Some(ValueSource::CommandLine))
+        {
+            return Err(format!(
+                "Job {} sets --{}, which applies to the whole batch; pass it before the batch command instead",
+                self.name,
+                id.replace('_', "-")
+            ));
+        }
+        let mut args = Cli::from_arg_matches(&matches).map_err(invalid)?;
+        if args.command.is_some() {
+            return Err(format!(
+                "Job {} can only run a report, not a subcommand",
+                self.name
+            ));
+        }
+        if self.format.is_some() {
+            args.format = self.format;
+        }
+        if self.out_dir.is_some() {
+            args.out_dir = self.out_dir.clone();
+        }
+        Ok(args)
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::history::Snapshot;
+use crate::scan::OrgReport;
+use crate::stats::SumStats;
+
+const MOVERS: usize = 3;
+const STALE_NAMES: usize = 3;
+
+// Slack renders its :shortcodes: and *bold*; mail clients and other chats get the characters
+#[derive(Clone, Copy, PartialEq)]
+pub enum Flavor {
+    Slack,
+    Text,
+}
+
+impl Flavor {
+    fn mark(&self, slack: &'static str, text: &'static str) -> &'static str {
+        match self {
+            Flavor::Slack => slack,
+            Flavor::Text => text,
+        }
+    }
+
+    fn bold(&self, text: &str) -> String {
+        match self {
+            Flavor::Slack => format!("*{text}*"),
+            Flavor::Text => text.to_string(),
+        }
+    }
+
+    fn star(&self) -> &'static str {
+        self.mark(":star:", "⭐")
+    }
+
+    fn fork(&self) -> &'static str {
+        self.mark(":fork_and_knife:", "🍴")
+    }
+
+    fn issue(&self) -> &'static str {
+        self.mark(":beetle:", "🐞")
+    }
+}
+
+fn change(current: u64, previous: Option<u64>) -> String {
+    match previous {
+        Some(previous) if current != previous => {
+            format!(" ({:+})", current as i64 - previous as i64)
+        }
+        _ => "".to_string(),
+    }
+}
+
+fn totals(stats: &SumStats, previous: Option<&SumStats>, flavor: Flavor) -> String {
+    format!(
+        "{} {} stars{}  {} {} forks{}  {} {} open issues{}",
+        flavor.star(),
+        stats.stars,
+        change(stats.stars.into(), previous.map(|p| p.stars.into())),
+        flavor.fork(),
+        stats.forks,
+        change(stats.forks.into(), previous.map(|p| p.forks.into())),
+        flavor.issue(),
+        stats.open_issues_count,
+        change(
+            stats.open_issues_count.into(),
+            previous.map(|p| p.open_issues_count.into())
+        ),
+    )
+}
+
+pub fn org(
+    report: &OrgReport,
+    previous: Option<&Snapshot>,
+    flavor: Flavor,
+    now: DateTime<Utc>,
+    stale_days: i64,
+) -> String {
+    let mut lines = vec![
+        format!(
+            "{} ({} repositories)",
+            flavor.bold(&report.name),
+            report.rows.len()
+        ),
+        totals(&report.stats, previous.map(|p| &p.stats), flavor),
+    ];
+    if let Some(previous) = previous {
+        let before: HashMap<&str, u32> = previous
+            .repos
+            .iter()
+            .map(|r| (r.name.as_str(), r.stars))
+            .collect();
+        let mut movers: Vec<(&str, i64)> = report
+            .rows
+            .iter()
+            .filter_map(|(repo, _)| {
+                let stars = repo.stars as i64 - *before.get(repo.name.as_str())? as i64;
+                (stars != 0).then_some((repo.name.as_str(), stars))
+            })
+            .collect();
+        movers.sort_by(|a, b| b.1.abs().cmp(&a.1.abs()).then_with(|| a.0.cmp(b.0)));
+        if !movers.is_empty() {
+            let listed: Vec<String> = movers
+                .iter()
+                .take(MOVERS)
+                .map(|(name, stars)| format!("{name} {stars:+}"))
+                .collect();
+            lines.push(format!(
+                "{} Top movers since {}: {}",
+                flavor.mark(":chart_with_upwards_trend:", "📈"),
+                previous.collected_at.format("%Y-%m-%d"),
+                listed.join(", ")
+            ));
+        }
+    }
+    let mut stale: Vec<&str> = report
+        .rows
+        .iter()
+        .filter(|(repo, _)| (now - repo.last_push()).num_days() >= stale_days)
+        .map(|(repo, _)| repo.name.as_str())
+        .collect();
+    if !stale.is_empty() {
+        stale.sort_unstable();
+        let more = stale.len().saturating_sub(STALE_NAMES);
+        let mut names = stale[..stale.len().min(STALE_NAMES)].join(", ");
+        if more > 0 {
+            names = format!("{names} and {more} more");
+        }
+        lines.push(format!(
+            "{} {} stale (no push in {stale_days}+ days): {names}",
+            flavor.mark(":zzz:", "💤"),
+            stale.len()
+        ));
+    }
+    lines.join("\n") + "\n"
+}
+
+pub fn failure(org: &str, error: &str, flavor: Flavor) -> String {
+    format!(
+        "{} {} failed: {error}\n",
+        flavor.mark(":warning:", "⚠️"),
+        flavor.bold(org)
+    )
+}
+
+pub fn total(orgs: u32, repos: u32, stats: &SumStats, flavor: Flavor) -> String {
+    format!(
+        "{} ({orgs} organizations, {repos} repositories)\n{}\n",
+        flavor.bold("Total"),
+        totals(stats, None, flavor)
+    )
+}
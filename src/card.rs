@@ -0,0 +1,31 @@
+use comfy_table::{Cell, Color, Table};
+
+use crate::format::Style;
+use crate::render;
+use crate::scan::OrgReport;
+
+pub fn card(report: &OrgReport, style: &Style) -> Table {
+    let stats = &report.stats;
+    let top_language = stats
+        .languages
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(language, count)| format!("{language} ({count})"))
+        .unwrap_or("-".to_string());
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new(format!("{} [{}]", report.name, report.created_year)).fg(Color::Green),
+        Cell::new(""),
+    ]);
+    table.add_row(vec![
+        "Repositories".to_string(),
+        report.rows.len().to_string(),
+    ]);
+    table.add_row(vec![
+        "Stars / forks".to_string(),
+        format!("{} / {}", stats.stars, stats.forks),
+    ]);
+    table.add_row(vec!["Top language".to_string(), top_language]);
+    table.add_row(vec!["Latest push".to_string(), style.time(stats.pushed_at)]);
+    table
+}
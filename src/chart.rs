@@ -0,0 +1,234 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+
+use crate::history::Snapshot;
+
+const SIZE: (u32, u32) = (1200, 800);
+const FONT: &str = "sans-serif";
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum ChartKind {
+    // Stars of the most starred repositories in the latest snapshots
+    RepoStars,
+    // Stars of each organization in its latest snapshot
+    OrgStars,
+    // Stars of each organization over every snapshot
+    History,
+}
+
+enum Chart {
+    Bars {
+        title: String,
+        bars: Vec<(String, u32)>,
+    },
+    Lines {
+        title: String,
+        series: Vec<(String, Vec<(DateTime<Utc>, u32)>)>,
+    },
+}
+
+fn selected_orgs(history: &[Snapshot], orgs: &[String]) -> BTreeSet<String> {
+    match orgs.is_empty() {
+        true => history.iter().map(|s| s.org.clone()).collect(),
+        false => orgs.iter().cloned().collect(),
+    }
+}
+
+fn latest<'a>(history: &'a [Snapshot], org: &str) -> Option<&'a Snapshot> {
+    history
+        .iter()
+        .filter(|s| s.org.eq_ignore_ascii_case(org))
+        .max_by_key(|s| s.collected_at)
+}
+
+fn chart(kind: ChartKind, history: &[Snapshot], orgs: &[String], top: usize) -> Chart {
+    let orgs = selected_orgs(history, orgs);
+    match kind {
+        ChartKind::RepoStars => {
+            let mut bars: Vec<(String, u32)> = orgs
+                .iter()
+                .filter_map(|org| latest(history, org))
+                .flat_map(|snapshot| {
+                    snapshot.repos.iter().map(|repo| {
+                        let name = match orgs.len() {
+                            1 => repo.name.clone(),
+                            _ => format!("{}/{}", snapshot.org, repo.name),
+                        };
+                        (name, repo.stars)
+                    })
+                })
+                .collect();
+            bars.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            bars.truncate(top);
+            Chart::Bars {
+                title: format!("Stars of the {} most starred repositories", bars.len()),
+                bars,
+            }
+        }
+        ChartKind::OrgStars => {
+            let mut bars: Vec<(String, u32)> = orgs
+                .iter()
+                .filter_map(|org| latest(history, org))
+                .map(|snapshot| (snapshot.org.clone(), snapshot.stats.stars))
+                .collect();
+            bars.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            bars.truncate(top);
+            Chart::Bars {
+                title: "Stars per organization".to_string(),
+                bars,
+            }
+        }
+        ChartKind::History => Chart::Lines {
+            title: "Stars over time".to_string(),
+            series: orgs
+                .iter()
+                .map(|org| {
+                    let mut points: Vec<(DateTime<Utc>, u32)> = history
+                        .iter()
+                        .filter(|s| s.org.eq_ignore_ascii_case(org))
+                        .map(|s| (s.collected_at, s.stats.stars))
+                        .collect();
+                    points.sort_by_key(|(at, _)| *at);
+                    (org.clone(), points)
+                })
+                .filter(|(_, points)| !points.is_empty())
+                .collect(),
+        },
+    }
+}
+
+// Horizontal bars leave room for long repository names
+fn draw_bars<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    title: &str,
+    bars: &[(String, u32)],
+) -> Result<(), String> {
+    let error = |e: DrawingAreaErrorKind<DB::ErrorType>| format!("Cannot draw chart: {e}");
+    let max = bars
+        .iter()
+        .map(|(_, stars)| *stars)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let mut chart = ChartBuilder::on(root)
+        .caption(title, (FONT, 28))
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(280)
+        .build_cartesian_2d(0..max + max / 10, (0..bars.len() as u32).into_segmented())
+        .map_err(error)?;
+    chart
+        .configure_mesh()
+        .disable_y_mesh()
+        .y_labels(bars.len())
+        .y_label_formatter(&|y| match y {
+            SegmentValue::CenterOf(i) => bars
+                .get(*i as usize)
+                .map(|(name, _)| name.clone())
+                .unwrap_or_default(),
+            _ => String::new(),
+        })
+        .x_desc("Stars")
+        .draw()
+        .map_err(error)?;
+    chart
+        .draw_series(
+            Histogram::horizontal(&chart)
+                .style(BLUE.filled())
+                .margin(4)
+                .data(
+                    bars.iter()
+                        .enumerate()
+                        .map(|(i, (_, stars))| (i as u32, *stars)),
+                ),
+        )
+        .map_err(error)?;
+    Ok(())
+}
+
+fn draw_lines<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    title: &str,
+    series: &[(String, Vec<(DateTime<Utc>, u32)>)],
+) -> Result<(), String> {
+    let error = |e: DrawingAreaErrorKind<DB::ErrorType>| format!("Cannot draw chart: {e}");
+    let points = series.iter().flat_map(|(_, points)| points);
+    let (Some(start), Some(end)) = (
+        points.clone().map(|(at, _)| *at).min(),
+        points.clone().map(|(at, _)| *at).max(),
+    ) else {
+        return Err("No snapshots in the history to chart".to_string());
+    };
+    let max = points.map(|(_, stars)| *stars).max().unwrap_or(0).max(1);
+    let mut chart = ChartBuilder::on(root)
+        .caption(title, (FONT, 28))
+        .margin(20)
+        .x_label_area_size(40)
+        .y_label_area_size(70)
+        .build_cartesian_2d(start..end, 0..max + max / 10)
+        .map_err(error)?;
+    chart
+        .configure_mesh()
+        .x_label_formatter(&|at| at.format("%Y-%m-%d").to_string())
+        .y_desc("Stars")
+        .draw()
+        .map_err(error)?;
+    for (i, (org, points)) in series.iter().enumerate() {
+        let color = Palette99::pick(i).to_rgba();
+        chart
+            .draw_series(LineSeries::new(
+                points.iter().copied(),
+                color.stroke_width(2),
+            ))
+            .map_err(error)?
+            .label(org)
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+    }
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(error)?;
+    Ok(())
+}
+
+fn draw<DB: DrawingBackend>(root: DrawingArea<DB, Shift>, chart: &Chart) -> Result<(), String> {
+    let error = |e: DrawingAreaErrorKind<DB::ErrorType>| format!("Cannot draw chart: {e}");
+    root.fill(&WHITE).map_err(error)?;
+    match chart {
+        Chart::Bars { title, bars } => draw_bars(&root, title, bars)?,
+        Chart::Lines { title, series } => draw_lines(&root, title, series)?,
+    }
+    root.present().map_err(error)
+}
+
+// The file extension picks the format
+pub fn render(
+    path: &Path,
+    kind: ChartKind,
+    history: &[Snapshot],
+    orgs: &[String],
+    top: usize,
+) -> Result<(), String> {
+    let chart = chart(kind, history, orgs, top);
+    if let Chart::Bars { bars, .. } = &chart {
+        if bars.is_empty() {
+            return Err("No snapshots in the history to chart".to_string());
+        }
+    }
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("svg") => draw(SVGBackend::new(path, SIZE).into_drawing_area(), &chart),
+        Some("png") => draw(BitMapBackend::new(path, SIZE).into_drawing_area(), &chart),
+        _ => Err(format!(
+            "Cannot write chart {}: use a .svg or .png file name",
+            path.display()
+        )),
+    }
+}
@@ -0,0 +1,1389 @@
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+use clap::{arg, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use serde::Deserialize;
+
+use crate::chart::ChartKind;
+use crate::columns::Column;
+use crate::config::{Config, SortKeys};
+use crate::format::{Rounding, SizeUnit, Units};
+use crate::health::Weights;
+use crate::period::Period;
+use crate::policy;
+use crate::visibility::Visibility;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Csv,
+    Xlsx,
+    Ndjson,
+    Slack,
+    #[serde(rename = "text-summary")]
+    TextSummary,
+}
+
+impl OutputFormat {
+    // These formats print to stdout in place of the tables
+    pub fn owns_stdout(&self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Ndjson | OutputFormat::Slack | OutputFormat::TextSummary
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CsvSchema {
+    // The columns of the first releases, before any were configurable
+    V1,
+    #[default]
+    Latest,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum Provider {
+    #[default]
+    Github,
+    Gitlab,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    #[command(about = "Browse organizations and repositories interactively")]
+    Tui {
+        #[arg(help = "Organization names, or @group for a group defined in the config file")]
+        orgs: Vec<String>,
+    },
+    #[command(
+        about = "Write a markdown digest comparing the oldest and newest snapshot in a window"
+    )]
+    Digest {
+        #[arg(
+            long,
+            value_name = "WINDOW",
+            default_value = "7d",
+            help = "How far back to look, e.g. 7d or 2w"
+        )]
+        since: String,
+
+        #[arg(
+            help = "Organization names, or @group; defaults to every organization in the history"
+        )]
+        orgs: Vec<String>,
+    },
+    #[command(about = "Expose organization and repository metrics for Prometheus on /metrics")]
+    Serve {
+        #[arg(long, default_value = "127.0.0.1:9185", help = "Address to listen on")]
+        listen: String,
+
+        #[arg(
+            long,
+            value_name = "INTERVAL",
+            default_value = "15m",
+            help = "How often to re-fetch metrics, e.g. 5m or 1h"
+        )]
+        interval: String,
+
+        #[arg(help = "Organization names, or @group for a group defined in the config file")]
+        orgs: Vec<String>,
+    },
+    #[command(
+        about = "Suggest missing repository topics from languages, manifests and descriptions"
+    )]
+    SuggestTopics {
+        #[arg(long, help = "Add the suggested topics to the repositories")]
+        apply: bool,
+
+        #[arg(help = "Organization names, or @group for a group defined in the config file")]
+        orgs: Vec<String>,
+    },
+    #[command(
+        about = "Fill in missing descriptions, homepages and topics from the [remediation] config"
+    )]
+    Apply {
+        #[arg(
+            long,
+            help = "Update the repositories instead of only printing the planned changes"
+        )]
+        yes: bool,
+
+        #[arg(help = "Organization names, or @group for a group defined in the config file")]
+        orgs: Vec<String>,
+    },
+    #[command(
+        about = "List repositories with no stars, no forks and no recent push as archive candidates"
+    )]
+    Audit {
+        #[arg(
+            long,
+            value_name = "MONTHS",
+            default_value_t = 12,
+            help = "Flag repositories without a push in this many months"
+        )]
+        months: u32,
+
+        #[arg(long, help = "Print a gh repo archive command for each candidate")]
+        commands: bool,
+
+        #[arg(long, help = "Archive the candidates through the API")]
+        apply: bool,
+
+        #[arg(
+            long,
+            requires = "apply",
+            help = "Archive for real; without it --apply only marks what would be archived"
+        )]
+        yes: bool,
+
+        #[arg(help = "Organization names, or @group for a group defined in the config file")]
+        orgs: Vec<String>,
+    },
+    #[command(about = "List repositories without recent pushes and flag them for archiving")]
+    ArchiveCandidates {
+        #[arg(
+            long,
+            value_name = "DAYS",
+            default_value_t = 365,
+            help = "Flag repositories without a push in this many days"
+        )]
+        stale_days: i64,
+
+        #[arg(long, help = "Add the archive-candidate topic to flagged repositories")]
+        topic: bool,
+
+        #[arg(long, help = "Open a tracking issue in flagged repositories")]
+        issue: bool,
+
+        #[arg(help = "Organization names, or @group for a group defined in the config file")]
+        orgs: Vec<String>,
+    },
+    #[command(
+        about = "Find organizations through a repository search or repository names, then report on them"
+    )]
+    Discover {
+        #[arg(
+            long,
+            help = "GitHub repository search query, e.g. 'topic:kubernetes stars:>100'"
+        )]
+        query: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 10,
+            help = "Report on at most N discovered organizations"
+        )]
+        max_orgs: usize,
+
+        #[arg(help = "Repositories as owner/name whose owning organizations to include")]
+        repos: Vec<String>,
+    },
+    #[command(
+        about = "Match two organizations' repositories by name and compare their stars and last push"
+    )]
+    CompareRepos {
+        #[arg(help = "First organization, e.g. the upstream one")]
+        left: String,
+
+        #[arg(help = "Second organization, e.g. the one holding the forks")]
+        right: String,
+    },
+    #[command(
+        about = "Sample the stargazers of two organizations' most starred repositories through GraphQL and report how much their audiences overlap"
+    )]
+    Overlap {
+        #[arg(help = "First organization")]
+        left: String,
+
+        #[arg(help = "Second organization")]
+        right: String,
+
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 10,
+            help = "Sample stargazers of each organization's N most starred repositories"
+        )]
+        repos: usize,
+
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 1000,
+            help = "Sample at most N stargazers per organization"
+        )]
+        sample: usize,
+    },
+    #[command(
+        about = "Draw stars per repository, stars per organization or stars over time from the history as an SVG or PNG chart"
+    )]
+    Chart {
+        #[arg(value_enum, help = "What to draw")]
+        kind: ChartKind,
+
+        #[arg(
+            long,
+            short,
+            value_name = "FILE",
+            help = "Write the chart to FILE; the .svg or .png extension picks the format"
+        )]
+        output: PathBuf,
+
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 20,
+            help = "Draw at most N bars"
+        )]
+        top: usize,
+
+        #[arg(
+            help = "Organization names, or @group; defaults to every organization in the history"
+        )]
+        orgs: Vec<String>,
+    },
+    #[command(
+        about = "Print every repository as an NDJSON line with ids, URLs, visibility and flags, without statistics"
+    )]
+    Inventory {
+        #[arg(help = "Organization names, or @group for a group defined in the config file")]
+        orgs: Vec<String>,
+    },
+    #[command(
+        about = "Fetch and enrich repositories into an --incremental cache without printing a report"
+    )]
+    Warm {
+        #[arg(long, value_name = "FILE", help = "Cache file to create or refresh")]
+        cache: PathBuf,
+
+        #[arg(help = "Organization names, or @group for a group defined in the config file")]
+        orgs: Vec<String>,
+    },
+    #[command(about = "Maintain the history store")]
+    History {
+        #[command(subcommand)]
+        action: HistoryAction,
+    },
+    #[command(about = "Move collected data between machines without API access")]
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+    #[command(
+        about = "Run the report jobs of a YAML file in one process, sharing the API client and its rate limit"
+    )]
+    Batch {
+        #[arg(value_name = "FILE", help = "YAML file with a jobs list")]
+        jobs: PathBuf,
+    },
+    #[command(about = "Inspect the run statistics recorded by --perf-stats")]
+    Perf {
+        #[command(subcommand)]
+        action: PerfAction,
+    },
+    #[command(about = "Print a shell completion script")]
+    Completions {
+        #[arg(value_enum, help = "Shell to complete for")]
+        shell: Shell,
+    },
+    #[command(about = "Print the man page in roff format")]
+    Man,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PerfAction {
+    #[command(about = "Summarize duration, API calls and cache hits of recent runs")]
+    Report {
+        #[arg(
+            long,
+            value_name = "N",
+            default_value_t = 20,
+            help = "Number of most recent runs to show"
+        )]
+        last: usize,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HistoryAction {
+    #[command(about = "Keep one snapshot per day for recent history and one per week beyond it")]
+    Compact {
+        #[arg(
+            long,
+            value_name = "DAYS",
+            help = "Keep daily snapshots for this many days (defaults to retention.daily_days in the config, or 90)"
+        )]
+        daily_days: Option<i64>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SnapshotAction {
+    #[command(about = "Write the history and optionally a raw dump into a .tar.zst bundle")]
+    Export {
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Bundle file to write, e.g. bundle.tar.zst"
+        )]
+        out: PathBuf,
+
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Include a file written by --dump-raw in the bundle"
+        )]
+        dump: Option<PathBuf>,
+    },
+    #[command(about = "Merge a bundle's snapshots into the history and extract its dump")]
+    Import {
+        #[arg(help = "Bundle file written by snapshot export")]
+        bundle: PathBuf,
+
+        #[arg(
+            long,
+            value_name = "FILE",
+            default_value = "out/dump.json",
+            help = "Where to extract the bundled dump for use with --from-file"
+        )]
+        dump_out: PathBuf,
+    },
+}
+
+fn org_lines(content: &str) -> impl Iterator<Item = String> + '_ {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+}
+
+// Lets other scripts pipe organization lists in instead of pasting them as arguments
+pub fn read_orgs(orgs: &[String], file: Option<&Path>) -> Result<Vec<String>, String> {
+    let read_stdin = || {
+        std::io::read_to_string(std::io::stdin())
+            .map_err(|e| format!("Cannot read organizations from stdin: {e}"))
+    };
+    let mut names = vec![];
+    for org in orgs {
+        if org == "-" {
+            names.extend(org_lines(&read_stdin()?));
+        } else {
+            names.push(org.clone());
+        }
+    }
+    match file {
+        Some(path) if path == Path::new("-") => names.extend(org_lines(&read_stdin()?)),
+        Some(path) => names.extend(org_lines(
+            &std::fs::read_to_string(path)
+                .map_err(|e| format!("Cannot read {}: {e}", path.display()))?,
+        )),
+        None => {}
+    }
+    Ok(names)
+}
+
+// Accepts names pasted from a browser (https://github.com/rust-lang/) and drops repeats, which
+// would otherwise be scanned twice and double-counted in the totals
+pub fn normalize_orgs(orgs: Vec<String>, nested: bool) -> Vec<String> {
+    let mut normalized: Vec<String> = vec![];
+    for org in orgs {
+        let mut name = org.trim();
+        if let Some((_, rest)) = name.split_once("://") {
+            name = rest.split_once('/').map_or("", |(_, path)| path);
+        }
+        let name = name.trim_matches('/');
+        // GitLab groups nest, GitHub URLs continue with the repository name
+        let name = match nested {
+            true => name.split("/-/").next().unwrap_or(name),
+            false => name.split('/').next().unwrap_or(name),
+        };
+        if name.is_empty() {
+            tracing::warn!("Ignoring organization {org:?} without a name");
+        } else if normalized.iter().any(|o| o.eq_ignore_ascii_case(name)) {
+            tracing::warn!("Ignoring duplicate organization {org:?}");
+        } else {
+            normalized.push(name.to_string());
+        }
+    }
+    normalized
+}
+
+#[derive(Parser, Debug)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[arg(
+        help = "Organization names, or @group for a group defined in the config file; - reads them from stdin"
+    )]
+    pub orgs: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Read newline-separated organization names from a file, or from stdin with -"
+    )]
+    pub orgs_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "SLUG",
+        help = "Report on every organization of a GitHub enterprise, in addition to the ones named"
+    )]
+    pub enterprise: Option<String>,
+
+    #[arg(
+        long,
+        help = "Report on every organization the token's user belongs to, in addition to the ones named"
+    )]
+    pub all_orgs: bool,
+
+    #[arg(
+        short,
+        long,
+        value_name = "Take only n latest repositories for compactness"
+    )]
+    pub latest_n: Option<usize>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Compare each metric with the previous period using the history store"
+    )]
+    pub period: Option<Period>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Previous org_stats.csv to compare each organization's summary with, without a history store"
+    )]
+    pub baseline: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "COLUMN[:PERCENT]",
+        requires = "baseline",
+        help = "Exit with an error when a column got worse than in the baseline, optionally by more than PERCENT; repeatable"
+    )]
+    pub fail_on_regression: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "CSV or JSON file with extra per-repository columns, joined by repository name"
+    )]
+    pub metadata: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "COLUMN",
+        help = "Print subtotals grouped by a metadata column"
+    )]
+    pub group_by: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        help = "Comma-separated columns to show, in order"
+    )]
+    pub columns: Vec<Column>,
+
+    #[arg(
+        long = "exclude-repo",
+        value_name = "NAME",
+        help = "Leave a repository out of the report and totals; repeatable, supports * wildcards and org/name"
+    )]
+    pub exclude: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "DATE",
+        help = "Keep only repositories pushed to on or after DATE (YYYY-MM-DD) or within an age like 30d"
+    )]
+    pub pushed_since: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "DATE",
+        help = "Keep only repositories last pushed to before DATE (YYYY-MM-DD) or more than an age like 180d ago"
+    )]
+    pub pushed_before: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "DATE",
+        help = "Keep only repositories created on or after DATE (YYYY-MM-DD) or within an age like 365d"
+    )]
+    pub created_since: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "DATE",
+        help = "Keep only repositories created before DATE (YYYY-MM-DD) or more than an age like 365d ago"
+    )]
+    pub created_before: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "DATE",
+        help = "Keep only repositories updated on or after DATE (YYYY-MM-DD) or within an age like 30d"
+    )]
+    pub updated_since: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "DATE",
+        help = "Keep only repositories last updated before DATE (YYYY-MM-DD) or more than an age like 180d ago"
+    )]
+    pub updated_before: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "RULE",
+        help = "Color table cells matching a rule like open_issues>100:red or pushed_at<90d:yellow; repeatable"
+    )]
+    pub highlight: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Fetch open pull request counts and show issues and PRs separately"
+    )]
+    pub prs: bool,
+
+    #[arg(
+        long,
+        help = "Fetch 14-day views and clones for repositories the token can push to"
+    )]
+    pub traffic: bool,
+
+    #[arg(long, help = "Compute a 0-100 health score per repository")]
+    pub health: bool,
+
+    #[arg(
+        long,
+        value_name = "WEIGHTS",
+        help = "Health score weights, e.g. recency=30,issues=20,releases=20,license=15,docs=15"
+    )]
+    pub health_weights: Option<Weights>,
+
+    #[arg(
+        long,
+        help = "Show push counts by actor over the last 90 days from the repository activity API"
+    )]
+    pub activity: bool,
+
+    #[arg(
+        long,
+        help = "Detect devcontainer configuration and report adoption per organization"
+    )]
+    pub devcontainer: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Print the top N repositories across all organizations by the sort column (stars by default)"
+    )]
+    pub leaderboard: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Rank the N repositories whose stars and open issues changed most since the previous snapshot"
+    )]
+    pub movers: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Project stars and open issues 90 days out with a linear fit over the history"
+    )]
+    pub forecast: bool,
+
+    #[arg(
+        long,
+        help = "Show raw KiB sizes and timestamps in the table instead of human-readable values"
+    )]
+    pub raw: bool,
+
+    #[arg(
+        long,
+        visible_alias = "no-color",
+        help = "Print uncolored ASCII tables; implied by NO_COLOR or when stdout is not a terminal"
+    )]
+    pub plain: bool,
+
+    #[arg(
+        long,
+        value_name = "COLS",
+        help = "Wrap tables to this many columns [default: terminal width, 120 when not a terminal]"
+    )]
+    pub max_width: Option<u16>,
+
+    #[arg(
+        long,
+        short,
+        help = "Print no tables, only warnings and errors, e.g. when only a file export is wanted"
+    )]
+    pub quiet: bool,
+
+    #[arg(
+        long,
+        help = "Print a compact card per organization (repositories, stars, forks, top language, latest push) instead of its tables"
+    )]
+    pub summary_only: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "watch",
+        help = "Print repository rows as they are fetched, at most twice a second, before each organization's table"
+    )]
+    pub stream_rows: bool,
+
+    #[arg(
+        long,
+        help = "Leave empty repositories (no push yet) out of organization and grand totals; they are still listed"
+    )]
+    pub skip_empty: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = Visibility::Public,
+        help = "Which repositories to list; private and internal ones need an authenticated token with access"
+    )]
+    pub visibility: Visibility,
+
+    #[arg(
+        long,
+        value_name = "ZONE",
+        help = "Print timestamps in local time, UTC (default) or a named zone like Europe/Prague"
+    )]
+    pub timezone: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "STRFTIME",
+        help = "Format timestamps in tables and exports with a strftime pattern, e.g. '%d.%m.%Y %H:%M'; tables show it instead of relative times"
+    )]
+    pub date_format: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Size unit in tables: binary (KiB, MiB), decimal (kB, MB), a fixed kib, mib, gib, kb, mb or gb, or raw KiB as reported by GitHub, also in exports, which otherwise use bytes"
+    )]
+    pub size_unit: Option<SizeUnit>,
+
+    #[arg(
+        long,
+        value_name = "DIGITS",
+        help = "Decimal places for sizes, averages and percentages (default: 1 for sizes, 0 otherwise)"
+    )]
+    pub precision: Option<usize>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Rounding of sizes, averages and percentages (default: nearest for sizes, down otherwise)"
+    )]
+    pub rounding: Option<Rounding>,
+
+    #[arg(
+        long,
+        default_value_t = 60,
+        help = "Truncate descriptions in the table to this many characters"
+    )]
+    pub max_desc_len: usize,
+
+    #[arg(
+        long,
+        help = "Audit issue templates, issue forms and PR templates with a community health section"
+    )]
+    pub templates: bool,
+
+    #[arg(
+        long,
+        help = "Audit allowed merge methods, auto-merge and delete-branch-on-merge settings"
+    )]
+    pub merge_settings: bool,
+
+    #[arg(
+        long,
+        help = "Audit Actions permissions; compliant repos restrict allowed actions and use a read-only workflow token"
+    )]
+    pub actions_policy: bool,
+
+    #[arg(
+        long,
+        help = "List deployment environments and deployment frequency over the last 30 days"
+    )]
+    pub deployments: bool,
+
+    #[arg(
+        long,
+        help = "Check that releases from the last 90 days were deployed and that deployments were released"
+    )]
+    pub traceability: bool,
+
+    #[arg(
+        long,
+        help = "Count open Dependabot and code scanning alerts (requires the security_events scope)"
+    )]
+    pub security: bool,
+
+    #[arg(
+        long,
+        help = "Show the conclusion of the latest workflow run on the default branch"
+    )]
+    pub ci: bool,
+
+    #[arg(
+        long,
+        help = "Show new stars per month over the last 12 months as a sparkline (uses GraphQL)"
+    )]
+    pub star_history: bool,
+
+    #[arg(
+        long,
+        help = "Count Dependabot pull requests opened in the last 30 days and how they were resolved"
+    )]
+    pub dependabot_prs: bool,
+
+    #[arg(
+        long,
+        help = "Report organization member, public member and team counts (teams need the read:org scope)"
+    )]
+    pub members: bool,
+
+    #[arg(
+        long,
+        help = "Show each organization's description, blog, location, public repository and follower counts above its table and in exports"
+    )]
+    pub org_profile: bool,
+
+    #[arg(
+        long,
+        help = "Report only the repositories each organization pinned to its profile, its flagship projects"
+    )]
+    pub pinned_only: bool,
+
+    #[arg(
+        long,
+        help = "List published security advisories (GHSAs) with counts by severity and year"
+    )]
+    pub advisories: bool,
+
+    #[arg(
+        long,
+        help = "Show the default branch and whether branch protection is enabled on it"
+    )]
+    pub branches: bool,
+
+    #[arg(
+        long,
+        help = "Show the default branch and the number of branches, flagging repositories above --branch-threshold"
+    )]
+    pub branch_count: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Branch count above which --branch-count flags a repository (default: 50)"
+    )]
+    pub branch_threshold: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "COLUMN=AGG",
+        value_delimiter = ',',
+        help = "Statistic shown in a column's summary and CSV field: sum, min, max, avg or median, e.g. stars=median,size=max"
+    )]
+    pub agg: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "WINDOW",
+        help = "Summarize repository deletions, visibility changes and member removals from the audit log over WINDOW (e.g. 30d, needs read:audit_log)"
+    )]
+    pub audit_log: Option<String>,
+
+    #[arg(
+        long,
+        help = "Fetch open issues and show their median and oldest age per repository"
+    )]
+    pub issue_age: bool,
+
+    #[arg(
+        long,
+        help = "Sample the 20 most recently closed pull requests per repository and show the median hours to first review and to merge"
+    )]
+    pub review_latency: bool,
+
+    #[arg(
+        long,
+        help = "Show stars gained per month: since the oldest snapshot of the last year when the history has one, over the repository's lifetime otherwise"
+    )]
+    pub star_velocity: bool,
+
+    #[arg(
+        long,
+        help = "Count dependency graph manifests and direct dependencies per repository, flagging repositories without a graph"
+    )]
+    pub dependencies: bool,
+
+    #[arg(
+        long,
+        help = "Show whether issues, wiki, Pages and Discussions are enabled per repository, with per-organization counts"
+    )]
+    pub features: bool,
+
+    #[arg(
+        long,
+        help = "Count open issues per label for each repository, with a per-organization label table for triage"
+    )]
+    pub labels: bool,
+
+    #[arg(
+        long,
+        help = "Sum release asset download counts per repository and organization"
+    )]
+    pub downloads: bool,
+
+    #[arg(
+        long,
+        help = "Check for a README and description and detect their language, counting repositories without a README"
+    )]
+    pub docs: bool,
+
+    #[arg(
+        long,
+        help = "Count stars and forks gained since the organization's previous run in the history (the last 7 days without one)"
+    )]
+    pub growth: bool,
+
+    #[arg(
+        long,
+        help = "Print a calendar heatmap of commits across each organization's repositories over the last 12 months"
+    )]
+    pub heatmap: bool,
+
+    #[arg(
+        long,
+        help = "Classify repositories as Active, Slowing, Stale or Dead by days since the last push"
+    )]
+    pub status: bool,
+
+    #[arg(
+        long,
+        help = "List open milestones per repository with completion and aggregate progress per organization"
+    )]
+    pub milestones: bool,
+
+    #[arg(
+        long,
+        help = "Read each repository's CODEOWNERS file and list its owners per repository and organization"
+    )]
+    pub codeowners: bool,
+
+    #[arg(
+        long,
+        help = "Shallow-clone repositories with git to count lines of code and files and find the largest files"
+    )]
+    pub deep: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 2,
+        help = "Clone and analyze at most N repositories at a time in --deep mode"
+    )]
+    pub deep_concurrency: usize,
+
+    #[arg(
+        long,
+        value_name = "MB",
+        default_value_t = 500,
+        help = "Skip repositories larger than MB in --deep mode, as reported by the API"
+    )]
+    pub deep_max_size: u32,
+
+    #[arg(
+        long,
+        value_name = "MB",
+        help = "List every repository of at least MB, as reported by the API, instead of the three largest"
+    )]
+    pub size_threshold: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "WINDOW",
+        requires = "deep",
+        help = "In --deep mode, clone the history of the last WINDOW (e.g. 90d) for commit churn, hotspots and contributor overlap"
+    )]
+    pub churn_window: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "DAYS",
+        value_delimiter = ',',
+        help = "Days without a push after which a repository counts as Slowing, Stale and Dead (default: 90,180,365)"
+    )]
+    pub status_days: Vec<i64>,
+
+    #[arg(long, help = "Print a per-organization license breakdown")]
+    pub licenses: bool,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        conflicts_with = "from_file",
+        help = "Download each repository's license file into DIR/org/repo/ and aggregate them into DIR/NOTICE"
+    )]
+    pub collect_licenses: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Print how many repositories of each organization carry each topic"
+    )]
+    pub topics_summary: bool,
+
+    #[arg(
+        long,
+        help = "Print repositories and their current stars per creation year for each organization"
+    )]
+    pub cohorts: bool,
+
+    #[arg(
+        long,
+        visible_alias = "fail-if-no-license",
+        help = "Print the license breakdown and exit non-zero when a non-archived repository has no license"
+    )]
+    pub require_license: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Exit non-zero when a repository has more than N open issues (pull requests excluded when known)"
+    )]
+    pub fail_if_open_issues_gt: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "DAYS",
+        help = "Exit non-zero when a repository has not been pushed to for more than DAYS days"
+    )]
+    pub fail_if_stale_days_gt: Option<i64>,
+
+    #[arg(
+        long,
+        help = "List repositories with open PR and release counts through the GraphQL API, 100 per request; falls back to REST on failure"
+    )]
+    pub graphql: bool,
+
+    #[arg(
+        long,
+        value_name = "POINTS",
+        requires = "graphql",
+        help = "Maximum estimated rate-limit points per GraphQL query; larger listings are split into smaller pages"
+    )]
+    pub graphql_max_cost: Option<u32>,
+
+    #[arg(
+        long,
+        conflicts_with = "from_file",
+        help = "Fetch a sample of repositories through both REST and GraphQL, report differing fields and exit non-zero on any"
+    )]
+    pub verify: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 10,
+        requires = "verify",
+        help = "Repositories per organization compared by --verify"
+    )]
+    pub verify_sample: usize,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Export format: csv writes out/org_stats.csv, xlsx writes out/org_stats.xlsx with a sheet per organization, ndjson streams repository and organization records to stdout instead of tables, slack and text-summary print a short digest per organization instead of tables"
+    )]
+    pub format: Option<OutputFormat>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_name = "SCHEMA",
+        help = "CSV columns: v1 pins the original real_org_name..size columns in their order regardless of other options, latest follows the report columns"
+    )]
+    pub csv_schema: Option<CsvSchema>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Render the collected data with a Tera template, printed instead of the tables"
+    )]
+    pub template: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "template",
+        help = "Write the rendered --template to FILE and keep printing the tables"
+    )]
+    pub template_out: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Replace organization and repository names with stable hashes in CSV, xlsx, NDJSON and template output, dropping descriptions, topics and URLs"
+    )]
+    pub anonymize: bool,
+
+    #[arg(
+        long,
+        value_name = "SECRET",
+        requires = "anonymize",
+        help = "Mix SECRET into the --anonymize hashes so names cannot be guessed by hashing known organizations"
+    )]
+    pub anonymize_salt: Option<String>,
+
+    #[arg(
+        long,
+        help = "Print the export schema (CSV columns and NDJSON record fields with its version) as JSON and exit"
+    )]
+    pub schema: bool,
+
+    #[arg(
+        long,
+        help = "Append rows with a collected_at timestamp to the CSV instead of overwriting it"
+    )]
+    pub append: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Record completed organizations in FILE and skip them when rerun after an interruption; the CSV is appended to"
+    )]
+    pub resume: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Exit successfully even when some organizations could not be scanned"
+    )]
+    pub allow_failures: bool,
+
+    #[arg(
+        long,
+        default_value_t = ',',
+        help = "Field delimiter for the CSV export (e.g. ';' for locales using decimal commas)"
+    )]
+    pub delimiter: char,
+
+    #[arg(
+        long,
+        help = "Start a new CSV file with a UTF-8 byte order mark so Excel detects the encoding"
+    )]
+    pub bom: bool,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Write one export file per organization (<org>.csv or <org>.xlsx) and an index file with the summary rows to DIR"
+    )]
+    pub out_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "HEX",
+        help = "Brand color such as #0366d6 for the xlsx header rows and sheet tabs"
+    )]
+    pub brand_color: Option<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        help = "Sort repositories by comma-separated columns, descending; later columns break ties and the name breaks the rest"
+    )]
+    pub sort: Vec<Column>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "TOML file with expected and denied repositories per organization"
+    )]
+    pub policy: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Write structured JSON diagnostic events (warnings, skipped repos) to a file"
+    )]
+    pub diagnostics: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "INTERVAL",
+        help = "Re-fetch and redraw every INTERVAL (e.g. 30s, 5m), highlighting changed cells"
+    )]
+    pub watch: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with = "watch",
+        help = "Save the fetched repository data to a JSON file for later --from-file runs"
+    )]
+    pub dump_raw: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        conflicts_with_all = ["graphql", "from_file"],
+        help = "Write the untouched REST API JSON of each organization and repository to DIR/<org>/"
+    )]
+    pub raw_json: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with = "dump_raw",
+        help = "Render tables and exports from a --dump-raw file instead of calling the API; repeat to merge shard dumps"
+    )]
+    pub from_file: Vec<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["from_file", "watch"],
+        help = "Reuse enrichments from FILE for repositories whose updated_at and pushed_at are unchanged, then update FILE; listings are still fetched in full"
+    )]
+    pub incremental: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "N/M",
+        help = "Collect only the N-th of M deterministic partitions of each organization's repositories"
+    )]
+    pub shard: Option<String>,
+
+    #[arg(
+        long,
+        default_value_t = 3,
+        help = "Retry transient failures (5xx, secondary rate limits, network errors) up to N times"
+    )]
+    pub retries: u32,
+
+    #[arg(
+        long,
+        value_name = "TOKEN",
+        help = "GitHub token, repeatable to rotate requests across several tokens and enrich repositories in parallel"
+    )]
+    pub token: Vec<String>,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value_t,
+        help = "Hosting service to read organizations from; gitlab reads groups and their public projects"
+    )]
+    pub provider: Provider,
+
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "API base URL, e.g. https://gitlab.example.com/api/v4/ for a self-hosted GitLab (default: the provider's public API)"
+    )]
+    pub api_url: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "MS",
+        default_value_t = 1000,
+        help = "Initial retry delay in milliseconds, doubled on each attempt with random jitter"
+    )]
+    pub retry_delay: u64,
+
+    #[arg(
+        long,
+        value_name = "DURATION",
+        help = "Stop the report after this long (e.g. 30m), keeping the organizations finished so far and exiting with status 124"
+    )]
+    pub timeout: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "DURATION",
+        help = "Give up on a request after this long without a response (e.g. 30s); it is retried like other network errors"
+    )]
+    pub request_timeout: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 4,
+        help = "Concurrent API requests per token; repositories are enriched concurrently within this bound"
+    )]
+    pub parallelism: usize,
+
+    #[arg(
+        long,
+        value_name = "REQUESTS",
+        help = "Stop enriching repositories once the run has made this many API requests"
+    )]
+    pub request_budget: Option<u32>,
+
+    #[arg(
+        long,
+        value_name = "AGENT",
+        help = "Send this User-Agent header, e.g. to identify the tool to GitHub Enterprise admins"
+    )]
+    pub user_agent: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Append every API request (time, method, route, status, remaining rate limit) to FILE"
+    )]
+    pub request_log: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Estimate the API requests of the scan per organization and ask before using more than --estimate-threshold of the rate limit"
+    )]
+    pub estimate: bool,
+
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        default_value_t = 50,
+        requires = "estimate",
+        help = "Share of the remaining rate limit above which --estimate asks for confirmation"
+    )]
+    pub estimate_threshold: u32,
+
+    #[arg(
+        long,
+        conflicts_with = "from_file",
+        help = "Check the token's scopes before scanning and turn off traffic, security alerts, --members and --audit-log when they would fail"
+    )]
+    pub check_scopes: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Config file (defaults to ./github-repo-info.toml or ~/.config/github-repo-info/config.toml)"
+    )]
+    pub config: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Append this run's duration, API calls and cache hits to out/perf.ndjson; nothing leaves the machine"
+    )]
+    pub perf_stats: bool,
+
+    #[arg(skip)]
+    pub virtual_orgs: HashMap<String, Vec<String>>,
+}
+
+impl Cli {
+    pub fn merge(&mut self, config: &Config) {
+        self.latest_n = self.latest_n.or(config.latest_n);
+        self.period = self.period.or(config.period);
+        self.metadata = self.metadata.take().or_else(|| config.metadata.clone());
+        self.group_by = self.group_by.take().or_else(|| config.group_by.clone());
+        self.health_weights = self
+            .health_weights
+            .take()
+            .or_else(|| config.health_weights.clone());
+        if self.sort.is_empty() {
+            self.sort = config
+                .sort
+                .as_ref()
+                .map(SortKeys::columns)
+                .unwrap_or_default();
+        }
+        self.format = self.format.or(config.format);
+        self.brand_color = self
+            .brand_color
+            .take()
+            .or_else(|| config.brand_color.clone());
+        self.csv_schema = self.csv_schema.or(config.csv_schema);
+        self.timezone = self.timezone.take().or_else(|| config.timezone.clone());
+        self.date_format = self
+            .date_format
+            .take()
+            .or_else(|| config.date_format.clone());
+        self.policy = self.policy.take().or_else(|| config.policy.clone());
+        self.size_unit = self.size_unit.or(config.size_unit);
+        self.precision = self.precision.or(config.precision);
+        self.rounding = self.rounding.or(config.rounding);
+        if self.columns.is_empty() {
+            self.columns = config.columns.clone();
+        }
+        self.exclude.extend(config.exclude.iter().cloned());
+        self.highlight.extend(config.highlight.iter().cloned());
+        if self.status_days.is_empty() {
+            self.status_days = config.status_days.clone();
+        }
+        self.virtual_orgs = config.virtual_orgs.clone();
+        self.perf_stats |= config.perf_stats;
+        self.branch_threshold = self.branch_threshold.or(config.branch_threshold);
+    }
+
+    pub fn branch_threshold(&self) -> u32 {
+        self.branch_threshold.unwrap_or(50)
+    }
+
+    pub fn plain(&self) -> bool {
+        self.plain
+            || std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+            || !std::io::stdout().is_terminal()
+    }
+
+    pub fn units(&self) -> Units {
+        Units {
+            size: self.size_unit.unwrap_or_default(),
+            precision: self.precision,
+            rounding: self.rounding,
+        }
+    }
+
+    pub fn is_excluded(&self, org: &str, repo: &str) -> bool {
+        let full_name = format!("{org}/{repo}");
+        self.exclude.iter().any(|pattern| {
+            if pattern.contains('/') {
+                policy::matches(pattern, &full_name)
+            } else {
+                policy::matches(pattern, repo)
+            }
+        })
+    }
+
+    // Whether only some repositories of each organization are reported, which a history
+    // snapshot would record as the organization shrinking
+    pub fn filters_repos(&self) -> bool {
+        !self.exclude.is_empty()
+            || self.visibility != Visibility::default()
+            || self.latest_n.is_some()
+            || self.skip_empty
+            || [
+                &self.pushed_since,
+                &self.pushed_before,
+                &self.created_since,
+                &self.created_before,
+                &self.updated_since,
+                &self.updated_before,
+            ]
+            .iter()
+            .any(|date| date.is_some())
+    }
+}
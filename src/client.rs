@@ -0,0 +1,431 @@
+use std::fs::File;
+use std::future::Future;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::{SecondsFormat, Utc};
+use futures::FutureExt;
+use octocrab::{FromResponse, Octocrab};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+
+struct Token {
+    client: Octocrab,
+    requests: AtomicU32,
+    // The last x-ratelimit-remaining and x-ratelimit-reset (Unix seconds) GitHub sent
+    remaining: AtomicU32,
+    reset: AtomicU64,
+}
+
+impl Token {
+    fn is_exhausted(&self, now: u64) -> bool {
+        self.remaining.load(Ordering::Relaxed) == 0 && now < self.reset.load(Ordering::Relaxed)
+    }
+}
+
+pub struct Github {
+    clients: Vec<Token>,
+    next: AtomicUsize,
+    retries: u32,
+    delay: Duration,
+    parallelism: usize,
+    permits: Semaphore,
+    // Request slots given up after secondary rate limits
+    throttled: AtomicUsize,
+    // Every request waits until then once GitHub asked to back off
+    paused_until: Mutex<Option<Instant>>,
+    // --request-budget, the cap on the whole run's requests
+    budget: AtomicU32,
+    // Requests made so far plus what the rate limit allows, recomputed for every organization
+    window_budget: AtomicU32,
+    request_log: Mutex<Option<File>>,
+}
+
+// GitHub asks for at least a minute when a secondary rate limit comes without Retry-After
+const SECONDARY_WAIT: Duration = Duration::from_secs(60);
+// Waits on secondary rate limits are not counted as retries, but a request gives up after this many
+const MAX_SECONDARY_WAITS: u32 = 10;
+
+// Goes through octocrab's raw calls so every attempt's status and rate limit headers can be
+// logged, and server errors are retried by status whatever their body
+macro_rules! send {
+    ($github:ident, $method:literal, $route:expr, $call:ident, $argument:expr, $idempotent:expr) => {{
+        let route = $route;
+        $github
+            .attempts($idempotent, || async move {
+                let index = $github.pick();
+                let client = &$github.clients[index].client;
+                let url = client.absolute_url(route).map_err(|e| (e, false))?;
+                let response = client.$call(url, $argument).await.map_err(|e| (e, false))?;
+                let remaining = response
+                    .headers()
+                    .get("x-ratelimit-remaining")
+                    .and_then(|value| value.to_str().ok());
+                $github.log_request($method, route, response.status().as_u16(), remaining);
+                $github.record_rate_limit(
+                    index,
+                    remaining,
+                    response
+                        .headers()
+                        .get("x-ratelimit-reset")
+                        .and_then(|value| value.to_str().ok()),
+                );
+                if let Some(wait) = retry_after(
+                    response.status().as_u16(),
+                    response
+                        .headers()
+                        .get("retry-after")
+                        .and_then(|value| value.to_str().ok()),
+                ) {
+                    $github.pause(wait);
+                }
+                let server_error = response.status().is_server_error();
+                octocrab::map_github_error(response)
+                    .await
+                    .map_err(|e| (e, server_error))
+            })
+            .await
+    }};
+}
+
+#[derive(Deserialize)]
+struct RateLimitResponse {
+    resources: RateLimitResources,
+}
+
+#[derive(Deserialize)]
+struct RateLimitResources {
+    core: CoreRateLimit,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+pub struct CoreRateLimit {
+    pub limit: u32,
+    pub remaining: u32,
+}
+
+pub struct TokenUsage {
+    pub requests: u32,
+    pub core: Option<CoreRateLimit>,
+}
+
+// Secondary rate limits and abuse detection answer 403 or 429 with the seconds to wait
+fn retry_after(status: u16, header: Option<&str>) -> Option<Duration> {
+    if !matches!(status, 403 | 429) {
+        return None;
+    }
+    header
+        .and_then(|value| value.trim().parse().ok())
+        .map(Duration::from_secs)
+}
+
+fn is_secondary_limit(error: &octocrab::Error) -> bool {
+    match error {
+        octocrab::Error::GitHub { source, .. } => {
+            let message = source.message.to_lowercase();
+            message.contains("secondary rate limit") || message.contains("abuse detection")
+        }
+        _ => false,
+    }
+}
+
+// Connection failures and timeouts; server errors are told by their status in send!
+fn is_transient(error: &octocrab::Error) -> bool {
+    matches!(error, octocrab::Error::Http { .. })
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn jitter(max: Duration) -> Duration {
+    Duration::from_millis(rand::thread_rng().gen_range(0..=max.as_millis() as u64))
+}
+
+impl Github {
+    pub fn new(clients: Vec<Octocrab>, retries: u32, delay: Duration, parallelism: usize) -> Self {
+        let parallelism = parallelism.max(1);
+        Self {
+            permits: Semaphore::new(clients.len() * parallelism),
+            throttled: AtomicUsize::new(0),
+            paused_until: Mutex::new(None),
+            clients: clients
+                .into_iter()
+                .map(|client| Token {
+                    client,
+                    requests: AtomicU32::new(0),
+                    remaining: AtomicU32::new(u32::MAX),
+                    reset: AtomicU64::new(0),
+                })
+                .collect(),
+            next: AtomicUsize::new(0),
+            retries,
+            delay,
+            parallelism,
+            budget: AtomicU32::new(u32::MAX),
+            window_budget: AtomicU32::new(u32::MAX),
+            request_log: Mutex::new(None),
+        }
+    }
+
+    pub fn log_requests(&self, path: &Path) -> Result<(), String> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Cannot open request log {}: {e}", path.display()))?;
+        *self.request_log.lock().expect("request log lock ok") = Some(file);
+        Ok(())
+    }
+
+    // One tab-separated line per attempt: time, method, route, status, remaining rate limit
+    fn log_request(&self, method: &str, route: &str, status: u16, remaining: Option<&str>) {
+        let mut log = self.request_log.lock().expect("request log lock ok");
+        if let Some(file) = log.as_mut() {
+            let line = format!(
+                "{}\t{method}\t{route}\t{status}\t{}\n",
+                Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+                remaining.unwrap_or("-")
+            );
+            if let Err(e) = file.write_all(line.as_bytes()) {
+                tracing::warn!("Cannot write request log: {e}");
+            }
+        }
+    }
+
+    // Rotates through the tokens so concurrent requests spread across their rate limits,
+    // passing over tokens that have none left until their limit resets
+    fn pick(&self) -> usize {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let now = unix_now();
+        let index = (0..self.clients.len())
+            .map(|offset| (start + offset) % self.clients.len())
+            .find(|&i| !self.clients[i].is_exhausted(now))
+            .unwrap_or(start % self.clients.len());
+        self.clients[index].requests.fetch_add(1, Ordering::Relaxed);
+        index
+    }
+
+    pub fn octocrab(&self) -> &Octocrab {
+        &self.clients[self.pick()].client
+    }
+
+    fn record_rate_limit(&self, index: usize, remaining: Option<&str>, reset: Option<&str>) {
+        let token = &self.clients[index];
+        if let Some(remaining) = remaining.and_then(|value| value.parse().ok()) {
+            token.remaining.store(remaining, Ordering::Relaxed);
+        }
+        if let Some(reset) = reset.and_then(|value| value.parse().ok()) {
+            token.reset.store(reset, Ordering::Relaxed);
+        }
+    }
+
+    pub fn requests(&self) -> u32 {
+        self.clients
+            .iter()
+            .map(|token| token.requests.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    pub fn tokens(&self) -> usize {
+        self.clients.len()
+    }
+
+    pub fn concurrency(&self) -> usize {
+        self.clients.len() * self.parallelism
+    }
+
+    fn pause(&self, wait: Duration) {
+        let until = Instant::now() + wait;
+        let mut paused_until = self.paused_until.lock().expect("pause lock ok");
+        *paused_until = Some(paused_until.map_or(until, |at| at.max(until)));
+    }
+
+    fn pending_pause(&self) -> Option<Duration> {
+        let paused_until = self.paused_until.lock().expect("pause lock ok");
+        paused_until.and_then(|at| at.checked_duration_since(Instant::now()))
+    }
+
+    // Gives up one request slot for the rest of the run, keeping at least one
+    async fn throttle(&self) -> usize {
+        let total = self.concurrency();
+        let reserved = self
+            .throttled
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                (n + 1 < total).then_some(n + 1)
+            })
+            .is_ok();
+        if reserved {
+            self.permits
+                .acquire()
+                .await
+                .expect("request semaphore ok")
+                .forget();
+        }
+        total - self.throttled.load(Ordering::Relaxed)
+    }
+
+    // Caps the requests of the whole run; concurrent enrichments all draw from it
+    pub fn limit_budget(&self, requests: u32) {
+        self.budget.fetch_min(requests, Ordering::Relaxed);
+    }
+
+    // Replaced rather than lowered, so a rate limit reset in a long-running watch or serve
+    // process makes room again
+    pub fn set_window_budget(&self, requests: u32) {
+        self.window_budget.store(requests, Ordering::Relaxed);
+    }
+
+    pub fn budget_left(&self) -> bool {
+        let budget = self
+            .budget
+            .load(Ordering::Relaxed)
+            .min(self.window_budget.load(Ordering::Relaxed));
+        self.requests() < budget
+    }
+
+    pub async fn remaining(&self) -> Option<u32> {
+        self.token_usage()
+            .await
+            .iter()
+            .map(|usage| usage.core.map(|core| core.remaining))
+            .sum()
+    }
+
+    pub async fn token_usage(&self) -> Vec<TokenUsage> {
+        let mut usage = vec![];
+        for token in &self.clients {
+            let core = token
+                .client
+                .get::<RateLimitResponse, _, ()>("rate_limit", None)
+                .await
+                .ok()
+                .map(|r| r.resources.core);
+            usage.push(TokenUsage {
+                requests: token.requests.load(Ordering::Relaxed),
+                core,
+            });
+        }
+        usage
+    }
+
+    // Classic tokens list their scopes in a header; fine-grained and app tokens send none
+    pub async fn scopes(&self) -> octocrab::Result<Option<Vec<String>>> {
+        let response = send!(self, "GET", "user", _get, None::<&()>, true)?;
+        Ok(response
+            .headers()
+            .get("x-oauth-scopes")
+            .and_then(|value| value.to_str().ok())
+            .map(|scopes| {
+                scopes
+                    .split(',')
+                    .map(|scope| scope.trim().to_string())
+                    .filter(|scope| !scope.is_empty())
+                    .collect()
+            }))
+    }
+
+    pub async fn retry<T, F, Fut>(&self, mut call: F) -> octocrab::Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = octocrab::Result<T>>,
+    {
+        self.attempts(true, || call().map(|result| result.map_err(|e| (e, false))))
+            .await
+    }
+
+    // Each failure comes with whether the response had a server error status. Writes are not
+    // idempotent, so a failed one may have been applied and is only repeated after a secondary
+    // rate limit, which GitHub answers before acting on the request
+    async fn attempts<T, F, Fut>(&self, idempotent: bool, mut call: F) -> octocrab::Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, (octocrab::Error, bool)>>,
+    {
+        let mut attempt = 0;
+        let mut secondary_waits = 0;
+        loop {
+            while let Some(wait) = self.pending_pause() {
+                tokio::time::sleep(wait).await;
+            }
+            let result = {
+                let _permit = self.permits.acquire().await.expect("request semaphore ok");
+                call().await
+            };
+            match result {
+                Err((e, _)) if secondary_waits < MAX_SECONDARY_WAITS && is_secondary_limit(&e) => {
+                    if self.pending_pause().is_none() {
+                        self.pause(SECONDARY_WAIT);
+                    }
+                    let wait = self.pending_pause().unwrap_or_default();
+                    let concurrency = self.throttle().await;
+                    tracing::warn!(
+                        "GitHub secondary rate limit hit, pausing requests for {}s and lowering concurrency to {concurrency}",
+                        wait.as_secs()
+                    );
+                    secondary_waits += 1;
+                }
+                Err((e, server_error))
+                    if idempotent
+                        && attempt < self.retries
+                        && (server_error || is_transient(&e)) =>
+                {
+                    let backoff = self.delay * 2u32.pow(attempt);
+                    tokio::time::sleep(backoff + jitter(backoff / 2)).await;
+                    attempt += 1;
+                }
+                result => return result.map_err(|(e, _)| e),
+            }
+        }
+    }
+
+    pub async fn get<R, A, P>(&self, route: A, parameters: Option<&P>) -> octocrab::Result<R>
+    where
+        A: AsRef<str>,
+        P: Serialize + ?Sized,
+        R: FromResponse,
+    {
+        R::from_response(send!(self, "GET", route.as_ref(), _get, parameters, true)?).await
+    }
+
+    pub async fn post<R, A, B>(&self, route: A, body: Option<&B>) -> octocrab::Result<R>
+    where
+        A: AsRef<str>,
+        B: Serialize + ?Sized,
+        R: FromResponse,
+    {
+        R::from_response(send!(self, "POST", route.as_ref(), _post, body, false)?).await
+    }
+
+    pub async fn put<R, A, B>(&self, route: A, body: Option<&B>) -> octocrab::Result<R>
+    where
+        A: AsRef<str>,
+        B: Serialize + ?Sized,
+        R: FromResponse,
+    {
+        R::from_response(send!(self, "PUT", route.as_ref(), _put, body, false)?).await
+    }
+
+    pub async fn patch<R, A, B>(&self, route: A, body: Option<&B>) -> octocrab::Result<R>
+    where
+        A: AsRef<str>,
+        B: Serialize + ?Sized,
+        R: FromResponse,
+    {
+        R::from_response(send!(self, "PATCH", route.as_ref(), _patch, body, false)?).await
+    }
+
+    pub async fn graphql<R: FromResponse>(
+        &self,
+        body: &(impl Serialize + ?Sized),
+    ) -> octocrab::Result<R> {
+        R::from_response(send!(self, "POST", "graphql", _post, Some(body), true)?).await
+    }
+}
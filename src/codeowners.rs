@@ -0,0 +1,39 @@
+use std::collections::BTreeMap;
+
+use comfy_table::{Cell, Color, Table};
+
+use crate::render;
+
+// Owners are @users, @org/teams or email addresses following each path pattern
+pub fn parse(content: &str) -> Vec<String> {
+    let mut owners: Vec<String> = vec![];
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("");
+        for owner in line.split_whitespace().skip(1) {
+            if (owner.starts_with('@') || owner.contains('@')) && !owners.iter().any(|o| o == owner)
+            {
+                owners.push(owner.to_string());
+            }
+        }
+    }
+    owners.sort();
+    owners
+}
+
+pub fn table(owners: &BTreeMap<String, u32>, with: u32, checked: u32) -> Table {
+    let mut owners: Vec<(&String, &u32)> = owners.iter().collect();
+    owners.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new(format!(
+            "Code owner ({with}/{checked} repositories with CODEOWNERS)"
+        ))
+        .fg(Color::Green),
+        Cell::new("Repositories").fg(Color::Green),
+    ]);
+    for (owner, count) in owners {
+        table.add_row(vec![Cell::new(owner), Cell::new(count)]);
+    }
+    table
+}
@@ -0,0 +1,44 @@
+use std::collections::BTreeMap;
+
+use chrono::Datelike;
+use comfy_table::{Cell, Color, Table};
+
+use crate::render;
+use crate::scan::OrgReport;
+use crate::stats::{median, RepoStats};
+
+// Repositories bucketed by creation year, newest first, with the stars they
+// hold today
+pub fn table(report: &OrgReport) -> Table {
+    let mut cohorts: BTreeMap<i32, Vec<&RepoStats>> = BTreeMap::new();
+    for (repo, _) in &report.rows {
+        cohorts
+            .entry(repo.created_at.year())
+            .or_default()
+            .push(repo);
+    }
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Created").fg(Color::Green),
+        Cell::new("Repositories").fg(Color::Green),
+        Cell::new("Stars").fg(Color::Green),
+        Cell::new("Median stars").fg(Color::Green),
+        Cell::new("Most starred").fg(Color::Green),
+    ]);
+    for (year, repos) in cohorts.iter().rev() {
+        let stars: Vec<u32> = repos.iter().map(|repo| repo.stars).collect();
+        let top = repos
+            .iter()
+            .max_by(|a, b| a.stars.cmp(&b.stars).then_with(|| b.name.cmp(&a.name)))
+            .map(|repo| format!("{} ({})", repo.name, repo.stars))
+            .unwrap_or_default();
+        table.add_row(vec![
+            Cell::new(year),
+            Cell::new(repos.len()),
+            Cell::new(stars.iter().map(|s| *s as u64).sum::<u64>()),
+            Cell::new(median(&stars).unwrap_or(0)),
+            Cell::new(top),
+        ]);
+    }
+    table
+}
@@ -0,0 +1,1185 @@
+use std::cmp::Ordering;
+
+use chrono::{DateTime, Datelike, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::cli::{Cli, Provider};
+use crate::docs;
+use crate::format::{self, Style, Units};
+use crate::labels;
+use crate::stars;
+use crate::stats::{
+    is_failing_ci, median, FeatureCounts, Features, Milestone, RepoStats, ReviewLatency, SumStats,
+};
+use crate::status::{self, Status};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[value(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    Stars,
+    Forks,
+    License,
+    Followers,
+    UpdatedAt,
+    PushedAt,
+    OpenIssues,
+    Issues,
+    Prs,
+    Size,
+    Created,
+    Views,
+    Clones,
+    Health,
+    Pushes,
+    Devcontainer,
+    IssueTemplates,
+    PrTemplate,
+    MergeMethods,
+    AutoMerge,
+    DeleteBranch,
+    ActionsPolicy,
+    Environments,
+    Deployments,
+    Traceability,
+    Security,
+    Ci,
+    StarHistory,
+    Description,
+    Topics,
+    DependabotPrs,
+    Advisories,
+    DefaultBranch,
+    BranchProtection,
+    IssueAge,
+    Status,
+    Milestones,
+    Loc,
+    Files,
+    Churn,
+    Codeowners,
+    Branches,
+    ReviewLatency,
+    StarVelocity,
+    Dependencies,
+    HasIssues,
+    HasWiki,
+    HasPages,
+    HasDiscussions,
+    Labels,
+    Downloads,
+    Docs,
+    NewStars,
+    NewForks,
+    Commits,
+}
+
+pub const APPROXIMATE_MARK: &str = "*";
+pub const APPROXIMATE_NOTE: &str =
+    "* lower bound: the value comes from the first 100 items GitHub returned";
+
+pub const DEFAULT_COLUMNS: [Column; 9] = [
+    Column::Stars,
+    Column::Forks,
+    Column::License,
+    Column::Followers,
+    Column::UpdatedAt,
+    Column::PushedAt,
+    Column::OpenIssues,
+    Column::Size,
+    Column::Created,
+];
+
+// GitLab has no watchers or repository size in the project listing
+pub const NOT_ON_GITLAB: [Column; 2] = [Column::Followers, Column::Size];
+
+pub fn resolve(args: &Cli) -> Vec<Column> {
+    if !args.columns.is_empty() {
+        return args.columns.clone();
+    }
+    let mut columns: Vec<Column> = DEFAULT_COLUMNS
+        .into_iter()
+        .flat_map(|column| match column {
+            Column::OpenIssues if args.prs => vec![Column::Issues, Column::Prs],
+            column if args.provider == Provider::Gitlab && NOT_ON_GITLAB.contains(&column) => {
+                vec![]
+            }
+            column => vec![column],
+        })
+        .collect();
+    if args.traffic {
+        columns.extend([Column::Views, Column::Clones]);
+    }
+    if args.health {
+        columns.push(Column::Health);
+    }
+    if args.activity {
+        columns.push(Column::Pushes);
+    }
+    if args.devcontainer {
+        columns.push(Column::Devcontainer);
+    }
+    if args.templates {
+        columns.extend([Column::IssueTemplates, Column::PrTemplate]);
+    }
+    if args.merge_settings {
+        columns.extend([
+            Column::MergeMethods,
+            Column::AutoMerge,
+            Column::DeleteBranch,
+        ]);
+    }
+    if args.actions_policy {
+        columns.push(Column::ActionsPolicy);
+    }
+    if args.deployments {
+        columns.extend([Column::Environments, Column::Deployments]);
+    }
+    if args.traceability {
+        columns.push(Column::Traceability);
+    }
+    if args.security {
+        columns.push(Column::Security);
+    }
+    if args.ci {
+        columns.push(Column::Ci);
+    }
+    if args.star_history {
+        columns.push(Column::StarHistory);
+    }
+    if args.dependabot_prs {
+        columns.push(Column::DependabotPrs);
+    }
+    if args.advisories {
+        columns.push(Column::Advisories);
+    }
+    if args.branches {
+        columns.extend([Column::DefaultBranch, Column::BranchProtection]);
+    }
+    if args.branch_count {
+        if !columns.contains(&Column::DefaultBranch) {
+            columns.push(Column::DefaultBranch);
+        }
+        columns.push(Column::Branches);
+    }
+    if args.issue_age {
+        columns.push(Column::IssueAge);
+    }
+    if args.review_latency {
+        columns.push(Column::ReviewLatency);
+    }
+    if args.star_velocity {
+        columns.push(Column::StarVelocity);
+    }
+    if args.dependencies {
+        columns.push(Column::Dependencies);
+    }
+    if args.features {
+        columns.extend([
+            Column::HasIssues,
+            Column::HasWiki,
+            Column::HasPages,
+            Column::HasDiscussions,
+        ]);
+    }
+    if args.labels {
+        columns.push(Column::Labels);
+    }
+    if args.downloads {
+        columns.push(Column::Downloads);
+    }
+    if args.docs {
+        columns.push(Column::Docs);
+    }
+    if args.growth {
+        columns.push(Column::NewStars);
+        columns.push(Column::NewForks);
+    }
+    if args.heatmap {
+        columns.push(Column::Commits);
+    }
+    if args.status {
+        columns.push(Column::Status);
+    }
+    if args.milestones {
+        columns.push(Column::Milestones);
+    }
+    if args.codeowners {
+        columns.push(Column::Codeowners);
+    }
+    if args.deep {
+        columns.extend([Column::Loc, Column::Files]);
+    }
+    if args.deep && args.churn_window.is_some() {
+        columns.push(Column::Churn);
+    }
+    columns
+}
+
+// Descending by each key in turn, then by name so equal rows come out in the same order every run
+pub fn compare(keys: &[Column], a: &RepoStats, b: &RepoStats) -> Ordering {
+    keys.iter()
+        .map(|key| key.sort_key(b).cmp(&key.sort_key(a)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or_else(|| a.name.cmp(&b.name))
+}
+
+// Untagged so exported numbers and dates stay plain JSON values
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CellValue {
+    Number(f64),
+    Date(DateTime<Utc>),
+    Text(String),
+}
+
+pub fn percent(count: u32, total: u32) -> Option<u32> {
+    if total == 0 {
+        return None;
+    }
+    Some(count * 100 / total)
+}
+
+fn ratio(count: u32, total: u32) -> Option<f64> {
+    if total == 0 {
+        return None;
+    }
+    Some(count as f64 * 100.0 / total as f64)
+}
+
+// Parses a value of a numeric column, keeping "inf" and "NaN" as text
+pub fn typed(value: String) -> CellValue {
+    match value.parse::<f64>() {
+        Ok(number) if number.is_finite() => CellValue::Number(number),
+        _ => CellValue::Text(value),
+    }
+}
+
+fn yes_no(value: Option<bool>) -> String {
+    match value {
+        Some(true) => "yes".to_string(),
+        Some(false) => "no".to_string(),
+        None => "".to_string(),
+    }
+}
+
+fn feature(column: &Column, features: &Features) -> Option<bool> {
+    match column {
+        Column::HasIssues => features.has_issues,
+        Column::HasWiki => features.has_wiki,
+        Column::HasPages => features.has_pages,
+        Column::HasDiscussions => features.has_discussions,
+        _ => None,
+    }
+}
+
+fn feature_count(column: &Column, counts: &FeatureCounts) -> u32 {
+    match column {
+        Column::HasIssues => counts.issues,
+        Column::HasWiki => counts.wiki,
+        Column::HasPages => counts.pages,
+        Column::HasDiscussions => counts.discussions,
+        _ => 0,
+    }
+}
+
+fn issue_age(ages: &[u32]) -> String {
+    match (median(ages), ages.iter().max()) {
+        (Some(median), Some(oldest)) => format!("median {median}d, oldest {oldest}d"),
+        _ => "no open issues".to_string(),
+    }
+}
+
+fn review_latency(latency: &ReviewLatency) -> String {
+    let hours = |values: &[u32]| {
+        median(values)
+            .map(|m| format!("{m}h"))
+            .unwrap_or("-".to_string())
+    };
+    if latency.merge_hours.is_empty() {
+        return "no merged PRs".to_string();
+    }
+    format!(
+        "review {}, merge {}",
+        hours(&latency.first_review_hours),
+        hours(&latency.merge_hours)
+    )
+}
+
+fn milestone_progress(milestones: &[Milestone], style: &Style) -> String {
+    let open = milestones.len();
+    let (open_issues, closed_issues) = milestones
+        .iter()
+        .fold((0, 0), |(o, c), m| (o + m.open_issues, c + m.closed_issues));
+    match ratio(closed_issues, open_issues + closed_issues) {
+        Some(pct) => format!("{open} open, {}%", style.units.ratio(pct)),
+        None if open > 0 => format!("{open} open"),
+        None => "".to_string(),
+    }
+}
+
+fn total_advisories(stats: &SumStats) -> u32 {
+    stats.advisories.values().flat_map(|s| s.values()).sum()
+}
+
+impl Column {
+    /// Upper width bound for free-text columns so they wrap the same way on
+    /// every terminal instead of squeezing the numeric ones
+    pub fn max_width(&self) -> Option<u16> {
+        match self {
+            Column::Description => Some(40),
+            Column::Topics | Column::Labels | Column::Dependencies | Column::Churn => Some(30),
+            _ => None,
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Column::Stars => "Stars",
+            Column::Forks => "Forks",
+            Column::License => "License",
+            Column::Followers => "Followers",
+            Column::UpdatedAt => "Updated at",
+            Column::PushedAt => "Pushed at",
+            Column::OpenIssues => "Open issues",
+            Column::Issues => "Issues",
+            Column::Prs => "PRs",
+            Column::Size => "Size",
+            Column::Created => "Created",
+            Column::Views => "Views (14d)",
+            Column::Clones => "Clones (14d)",
+            Column::Health => "Health",
+            Column::Pushes => "Pushes (90d)",
+            Column::Devcontainer => "Devcontainer",
+            Column::IssueTemplates => "Issue templates",
+            Column::PrTemplate => "PR template",
+            Column::MergeMethods => "Merge methods",
+            Column::AutoMerge => "Auto-merge",
+            Column::DeleteBranch => "Delete branch",
+            Column::ActionsPolicy => "Actions policy",
+            Column::Environments => "Environments",
+            Column::Deployments => "Deploys (30d)",
+            Column::Traceability => "Release traceability",
+            Column::Security => "Open security alerts",
+            Column::Ci => "CI",
+            Column::StarHistory => "Stars (12 months)",
+            Column::Description => "Description",
+            Column::Topics => "Topics",
+            Column::DependabotPrs => "Dependabot PRs (30d)",
+            Column::Advisories => "Advisories",
+            Column::DefaultBranch => "Default branch",
+            Column::BranchProtection => "Protected",
+            Column::IssueAge => "Issue age",
+            Column::Status => "Status",
+            Column::Milestones => "Milestones",
+            Column::Loc => "LOC",
+            Column::Files => "Files",
+            Column::Churn => "Churn",
+            Column::Codeowners => "Code owners",
+            Column::Branches => "Branches",
+            Column::ReviewLatency => "Review latency",
+            Column::StarVelocity => "Stars/month",
+            Column::Dependencies => "Dependencies",
+            Column::HasIssues => "Issues enabled",
+            Column::HasWiki => "Wiki",
+            Column::HasPages => "Pages",
+            Column::HasDiscussions => "Discussions",
+            Column::Labels => "Labels",
+            Column::Downloads => "Downloads",
+            Column::Docs => "Docs",
+            Column::NewStars => "New stars",
+            Column::NewForks => "New forks",
+            Column::Commits => "Commits (12 months)",
+        }
+    }
+
+    pub fn value(&self, repo: &RepoStats, style: &Style) -> String {
+        match self {
+            Column::Stars => repo.stars.to_string(),
+            Column::Forks => repo.forks.to_string(),
+            Column::License => repo.license.clone().unwrap_or_default(),
+            Column::Followers => repo.followers.to_string(),
+            Column::UpdatedAt => style.time(repo.updated_at),
+            Column::PushedAt => repo
+                .pushed_at
+                .map(|at| style.time(at))
+                .unwrap_or(format::MISSING.to_string()),
+            Column::OpenIssues => repo.open_issues_count.to_string(),
+            Column::Issues => repo
+                .open_issues_only()
+                .map(|n| n.to_string())
+                .unwrap_or_default(),
+            Column::Prs => repo.open_prs.map(|n| n.to_string()).unwrap_or_default(),
+            Column::Size => style.size(repo.size as u64),
+            Column::Created => repo.created_at.year().to_string(),
+            Column::Views => repo
+                .traffic
+                .as_ref()
+                .map(|t| format!("{} ({} unique)", t.views, t.unique_views))
+                .unwrap_or_default(),
+            Column::Clones => repo
+                .traffic
+                .as_ref()
+                .map(|t| format!("{} ({} unique)", t.clones, t.unique_clones))
+                .unwrap_or_default(),
+            Column::Devcontainer => yes_no(repo.devcontainer),
+            Column::IssueTemplates => match &repo.community {
+                Some(c) if c.has_issue_forms == Some(true) => "forms".to_string(),
+                Some(c) if c.has_issue_template => "templates".to_string(),
+                Some(_) => "none".to_string(),
+                None => "".to_string(),
+            },
+            Column::PrTemplate => yes_no(repo.community.as_ref().map(|c| c.has_pr_template)),
+            Column::MergeMethods => repo.merge.as_ref().map(|m| m.methods()).unwrap_or_default(),
+            Column::AutoMerge => yes_no(repo.merge.as_ref().and_then(|m| m.allow_auto_merge)),
+            Column::DeleteBranch => {
+                yes_no(repo.merge.as_ref().and_then(|m| m.delete_branch_on_merge))
+            }
+            Column::ActionsPolicy => repo
+                .actions
+                .as_ref()
+                .map(|a| a.describe())
+                .unwrap_or_default(),
+            Column::Environments => repo
+                .deployments
+                .as_ref()
+                .map(|d| d.environments.join(", "))
+                .unwrap_or_default(),
+            Column::Deployments => repo
+                .deployments
+                .as_ref()
+                .map(|d| format!("{} ({})", d.last_30d, d.frequency()))
+                .unwrap_or_default(),
+            Column::Traceability => repo
+                .traceability
+                .as_ref()
+                .map(|t| t.describe())
+                .unwrap_or_default(),
+            Column::Security => repo
+                .security
+                .as_ref()
+                .map(|s| s.describe())
+                .unwrap_or_default(),
+            Column::Ci => repo.ci.clone().unwrap_or_default(),
+            Column::Description => repo
+                .description
+                .as_deref()
+                .map(|d| style.truncate(d))
+                .unwrap_or_default(),
+            Column::Topics => repo.topics.join(", "),
+            Column::DefaultBranch => repo.default_branch.clone().unwrap_or_default(),
+            Column::BranchProtection => yes_no(repo.branch_protected),
+            Column::IssueAge => repo
+                .issue_ages
+                .as_deref()
+                .map(issue_age)
+                .unwrap_or_default(),
+            Column::Status => repo
+                .status
+                .map(|s| s.label().to_string())
+                .unwrap_or_default(),
+            Column::Milestones => repo
+                .milestones
+                .as_ref()
+                .map(|m| milestone_progress(m, style))
+                .unwrap_or_default(),
+            Column::Loc => repo
+                .deep
+                .as_ref()
+                .map(|d| d.code_lines.to_string())
+                .unwrap_or_default(),
+            Column::Files => repo
+                .deep
+                .as_ref()
+                .map(|d| d.files.to_string())
+                .unwrap_or_default(),
+            Column::Churn => repo
+                .deep
+                .as_ref()
+                .and_then(|d| d.churn.as_ref())
+                .map(|c| format!("{} commits, +{}/-{}", c.commits, c.added, c.deleted))
+                .unwrap_or_default(),
+            Column::Branches => repo
+                .branch_count
+                .map(|count| count.to_string())
+                .unwrap_or_default(),
+            Column::ReviewLatency => repo
+                .review_latency
+                .as_ref()
+                .map(review_latency)
+                .unwrap_or_default(),
+            Column::StarVelocity => repo
+                .star_velocity
+                .map(|velocity| style.units.decimal(velocity))
+                .unwrap_or_default(),
+            Column::Dependencies => match &repo.dependencies {
+                Some(d) if d.manifests == 0 => "no graph".to_string(),
+                Some(d) => format!("{} direct, {} manifests", d.direct, d.manifests),
+                None => "".to_string(),
+            },
+            Column::HasIssues | Column::HasWiki | Column::HasPages | Column::HasDiscussions => {
+                yes_no(repo.features.as_ref().and_then(|f| feature(self, f)))
+            }
+            Column::Downloads => match &repo.downloads {
+                Some(d) if d.releases == 0 => "no releases".to_string(),
+                Some(d) => match d.latest {
+                    Some(latest) if d.releases > 1 => format!("{} (latest {latest})", d.total),
+                    _ => d.total.to_string(),
+                },
+                None => "".to_string(),
+            },
+            Column::Docs => repo.docs.as_ref().map(docs::describe).unwrap_or_default(),
+            Column::NewStars => repo
+                .growth
+                .as_ref()
+                .map(|g| format!("+{}", g.stars))
+                .unwrap_or_default(),
+            Column::NewForks => repo
+                .growth
+                .as_ref()
+                .map(|g| format!("+{}", g.forks))
+                .unwrap_or_default(),
+            Column::Commits => repo
+                .commit_days
+                .as_ref()
+                .map(|d| d.values().sum::<u32>().to_string())
+                .unwrap_or_default(),
+            Column::Labels => repo
+                .labels
+                .as_ref()
+                .map(|l| labels::top(l, 3))
+                .unwrap_or_default(),
+            Column::Codeowners => match &repo.codeowners {
+                Some(owners) if owners.is_empty() => "none".to_string(),
+                Some(owners) => owners.join(", "),
+                None => "".to_string(),
+            },
+            Column::Advisories => repo
+                .advisories
+                .as_ref()
+                .map(|a| a.len().to_string())
+                .unwrap_or_default(),
+            Column::DependabotPrs => repo
+                .dependency_updates
+                .as_ref()
+                .map(|d| d.describe())
+                .unwrap_or_default(),
+            Column::StarHistory => repo
+                .star_history
+                .as_ref()
+                .map(|h| format!("{} +{}", stars::sparkline(h), h.iter().sum::<u32>()))
+                .unwrap_or_default(),
+            Column::Health => repo.health.map(|h| h.to_string()).unwrap_or_default(),
+            Column::Pushes => repo
+                .pushes
+                .as_ref()
+                .map(|p| {
+                    let actors: Vec<String> = p
+                        .by_actor
+                        .iter()
+                        .take(3)
+                        .map(|(actor, count)| format!("{actor} {count}"))
+                        .collect();
+                    if actors.is_empty() {
+                        p.total.to_string()
+                    } else {
+                        format!("{} ({})", p.total, actors.join(", "))
+                    }
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn summary(&self, stats: &SumStats, style: &Style) -> String {
+        match self {
+            Column::Stars => format!("Sum: {}", stats.stars),
+            Column::Forks => format!("Sum: {}", stats.forks),
+            Column::Followers => format!("Sum: {}", stats.followers),
+            Column::UpdatedAt => format!("Latest: {}", style.time(stats.updated_at)),
+            Column::PushedAt => format!("Latest: {}", style.time(stats.pushed_at)),
+            Column::OpenIssues => format!("Sum: {}", stats.open_issues_count),
+            Column::Issues => format!(
+                "Sum: {}",
+                stats.open_issues_count.saturating_sub(stats.open_prs)
+            ),
+            Column::Prs => format!("Sum: {}", stats.open_prs),
+            Column::Views => format!("Sum: {}", stats.views),
+            Column::Clones => format!("Sum: {}", stats.clones),
+            Column::Health => stats
+                .health_avg()
+                .map(|h| format!("Avg: {}", style.units.ratio(h)))
+                .unwrap_or_default(),
+            Column::Pushes => format!("Sum: {}", stats.pushes),
+            Column::Devcontainer => stats
+                .devcontainer_pct()
+                .map(|pct| format!("Adoption: {}%", style.units.ratio(pct)))
+                .unwrap_or_default(),
+            Column::IssueTemplates => {
+                ratio(stats.community.issue_templates, stats.community.checked)
+                    .map(|pct| format!("Adoption: {}%", style.units.ratio(pct)))
+                    .unwrap_or_default()
+            }
+            Column::PrTemplate => ratio(stats.community.pr_templates, stats.community.checked)
+                .map(|pct| format!("Adoption: {}%", style.units.ratio(pct)))
+                .unwrap_or_default(),
+            Column::MergeMethods | Column::AutoMerge | Column::DeleteBranch => {
+                match stats.merge_configs.len() {
+                    0 => "".to_string(),
+                    1 => "Consistent".to_string(),
+                    n => format!("Configs: {n}"),
+                }
+            }
+            Column::ActionsPolicy => ratio(stats.actions_compliant, stats.actions_checked)
+                .map(|pct| format!("Compliant: {}%", style.units.ratio(pct)))
+                .unwrap_or_default(),
+            Column::Environments => "".to_string(),
+            Column::Deployments => format!("Sum: {}", stats.deployments_30d),
+            Column::Traceability => format!("Anomalies: {}", stats.traceability_anomalies),
+            Column::Security => {
+                let coverage = ratio(
+                    stats.private_reporting_enabled,
+                    stats.private_reporting_checked,
+                )
+                .map(|pct| format!(", private reporting: {}%", style.units.ratio(pct)))
+                .unwrap_or_default();
+                format!(
+                    "Sum: {} dependabot / {} code scanning{coverage}",
+                    stats.dependabot_alerts, stats.code_scanning_alerts
+                )
+            }
+            Column::Ci => {
+                if stats.ci_checked == 0 {
+                    "".to_string()
+                } else {
+                    format!("Failing: {}/{}", stats.ci_failing, stats.ci_checked)
+                }
+            }
+            Column::StarHistory => {
+                if stats.star_history.is_empty() {
+                    "".to_string()
+                } else {
+                    format!(
+                        "{} +{}",
+                        stars::sparkline(&stats.star_history),
+                        stats.star_history.iter().sum::<u32>()
+                    )
+                }
+            }
+            Column::DependabotPrs => {
+                ratio(stats.dependency_prs_merged, stats.dependency_prs_opened)
+                    .map(|pct| {
+                        format!(
+                            "Merged: {}% of {}, closed: {}",
+                            style.units.ratio(pct),
+                            stats.dependency_prs_opened,
+                            stats.dependency_prs_closed
+                        )
+                    })
+                    .unwrap_or_default()
+            }
+            Column::Advisories => format!("Sum: {}", total_advisories(stats)),
+            Column::Loc => format!("Sum: {}", stats.code_lines),
+            Column::Files => format!("Sum: {}", stats.files),
+            Column::Churn => format!(
+                "Sum: {} commits, {} lines",
+                stats.churn_commits, stats.churn_lines
+            ),
+            Column::Branches => format!("Sum: {}", stats.branches),
+            Column::ReviewLatency => review_latency(&stats.review_latency),
+            Column::StarVelocity => format!("Sum: {}", style.units.decimal(stats.star_velocity)),
+            Column::Dependencies => format!(
+                "Sum: {} direct, {}/{} without graph",
+                stats.direct_dependencies,
+                stats.dependency_graphs_missing,
+                stats.dependency_graphs_checked
+            ),
+            Column::HasIssues | Column::HasWiki | Column::HasPages | Column::HasDiscussions => {
+                if stats.features.checked == 0 {
+                    "".to_string()
+                } else {
+                    format!(
+                        "Enabled: {}/{}",
+                        feature_count(self, &stats.features),
+                        stats.features.checked
+                    )
+                }
+            }
+            Column::Labels => labels::top(&stats.labels, 3),
+            Column::Downloads => format!("Sum: {}", stats.release_downloads),
+            Column::NewStars => format!("Sum: +{}", stats.new_stars),
+            Column::NewForks => format!("Sum: +{}", stats.new_forks),
+            Column::Commits => format!("Sum: {}", stats.commits_12_months),
+            Column::Docs => {
+                if stats.docs.checked == 0 {
+                    "".to_string()
+                } else {
+                    format!(
+                        "No README: {}/{}",
+                        stats.docs.without_readme, stats.docs.checked
+                    )
+                }
+            }
+            Column::Codeowners => {
+                if stats.codeowners_checked == 0 {
+                    "".to_string()
+                } else {
+                    format!(
+                        "Owned: {}/{}, {} owners",
+                        stats.codeowners_present,
+                        stats.codeowners_checked,
+                        stats.code_owners.len()
+                    )
+                }
+            }
+            Column::Milestones => ratio(
+                stats.milestone_issues_closed,
+                stats.milestone_issues_open + stats.milestone_issues_closed,
+            )
+            .map(|pct| {
+                format!(
+                    "Open: {}, done: {}%",
+                    stats.milestones_open,
+                    style.units.ratio(pct)
+                )
+            })
+            .unwrap_or_default(),
+            Column::IssueAge => issue_age(&stats.issue_ages),
+            Column::Status => status::ALL
+                .iter()
+                .filter_map(|s| {
+                    let count = stats.statuses.get(s.label())?;
+                    Some(format!("{}: {count}", s.label()))
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            Column::BranchProtection => {
+                if stats.protection_checked == 0 {
+                    "".to_string()
+                } else {
+                    format!(
+                        "Unprotected: {}/{}",
+                        stats.unprotected_branches, stats.protection_checked
+                    )
+                }
+            }
+            Column::Size => format!("Sum: {}", style.size(stats.size)),
+            Column::License
+            | Column::Created
+            | Column::Description
+            | Column::Topics
+            | Column::DefaultBranch => "".to_string(),
+        }
+    }
+
+    pub fn sort_key(&self, repo: &RepoStats) -> i64 {
+        match self {
+            Column::Stars => repo.stars as i64,
+            Column::Forks => repo.forks as i64,
+            Column::License => repo.license.is_some() as i64,
+            Column::Followers => repo.followers as i64,
+            Column::UpdatedAt => repo.updated_at.timestamp(),
+            Column::PushedAt => repo.pushed_at.map(|at| at.timestamp()).unwrap_or(-1),
+            Column::OpenIssues => repo.open_issues_count as i64,
+            Column::Issues => repo.open_issues_only().map(|n| n as i64).unwrap_or(-1),
+            Column::Prs => repo.open_prs.map(|n| n as i64).unwrap_or(-1),
+            Column::Size => repo.size as i64,
+            Column::Created => repo.created_at.timestamp(),
+            Column::Views => repo.traffic.as_ref().map(|t| t.views as i64).unwrap_or(-1),
+            Column::Clones => repo.traffic.as_ref().map(|t| t.clones as i64).unwrap_or(-1),
+            Column::Health => repo.health.map(|h| h as i64).unwrap_or(-1),
+            Column::Pushes => repo.pushes.as_ref().map(|p| p.total as i64).unwrap_or(-1),
+            Column::Devcontainer => repo.devcontainer.map(|d| d as i64).unwrap_or(-1),
+            Column::IssueTemplates => match &repo.community {
+                Some(c) if c.has_issue_forms == Some(true) => 2,
+                Some(c) => c.has_issue_template as i64,
+                None => -1,
+            },
+            Column::PrTemplate => repo
+                .community
+                .as_ref()
+                .map(|c| c.has_pr_template as i64)
+                .unwrap_or(-1),
+            Column::MergeMethods => repo
+                .merge
+                .as_ref()
+                .map(|m| m.methods().split(',').filter(|m| !m.is_empty()).count() as i64)
+                .unwrap_or(-1),
+            Column::AutoMerge => repo
+                .merge
+                .as_ref()
+                .and_then(|m| m.allow_auto_merge)
+                .map(|b| b as i64)
+                .unwrap_or(-1),
+            Column::DeleteBranch => repo
+                .merge
+                .as_ref()
+                .and_then(|m| m.delete_branch_on_merge)
+                .map(|b| b as i64)
+                .unwrap_or(-1),
+            Column::ActionsPolicy => repo
+                .actions
+                .as_ref()
+                .map(|a| a.is_compliant() as i64)
+                .unwrap_or(-1),
+            Column::Environments => repo
+                .deployments
+                .as_ref()
+                .map(|d| d.environments.len() as i64)
+                .unwrap_or(-1),
+            Column::Deployments => repo
+                .deployments
+                .as_ref()
+                .map(|d| d.last_30d as i64)
+                .unwrap_or(-1),
+            Column::Traceability => repo
+                .traceability
+                .as_ref()
+                .map(|t| t.anomalies() as i64)
+                .unwrap_or(-1),
+            Column::Security => repo
+                .security
+                .as_ref()
+                .map(|s| s.total() as i64)
+                .unwrap_or(-1),
+            Column::Ci => match repo.ci.as_deref() {
+                Some(ci) if is_failing_ci(ci) => 2,
+                Some("success") => 0,
+                Some(_) => 1,
+                None => -1,
+            },
+            Column::StarHistory => repo
+                .star_history
+                .as_ref()
+                .map(|h| h.iter().sum::<u32>() as i64)
+                .unwrap_or(-1),
+            Column::Description => repo.description.is_some() as i64,
+            Column::Topics => repo.topics.len() as i64,
+            Column::DefaultBranch => repo.default_branch.is_some() as i64,
+            Column::BranchProtection => repo.branch_protected.map(|p| p as i64).unwrap_or(-1),
+            Column::IssueAge => repo
+                .issue_ages
+                .as_deref()
+                .and_then(median)
+                .map(|m| m as i64)
+                .unwrap_or(-1),
+            Column::Status => repo.status.map(|s| s as i64).unwrap_or(-1),
+            Column::Milestones => repo
+                .milestones
+                .as_ref()
+                .map(|m| m.len() as i64)
+                .unwrap_or(-1),
+            Column::Loc => repo
+                .deep
+                .as_ref()
+                .map(|d| d.code_lines as i64)
+                .unwrap_or(-1),
+            Column::Files => repo.deep.as_ref().map(|d| d.files as i64).unwrap_or(-1),
+            Column::Churn => repo
+                .deep
+                .as_ref()
+                .and_then(|d| d.churn.as_ref())
+                .map(|c| (c.added + c.deleted) as i64)
+                .unwrap_or(-1),
+            Column::Branches => repo.branch_count.map(|c| c as i64).unwrap_or(-1),
+            Column::ReviewLatency => repo
+                .review_latency
+                .as_ref()
+                .and_then(|l| median(&l.merge_hours))
+                .map(|m| m as i64)
+                .unwrap_or(-1),
+            // Whole stars per month, so filters and highlights compare against the shown value
+            Column::StarVelocity => repo.star_velocity.map(|v| v.round() as i64).unwrap_or(-1),
+            Column::Dependencies => repo
+                .dependencies
+                .as_ref()
+                .map(|d| d.direct as i64)
+                .unwrap_or(-1),
+            Column::Codeowners => repo
+                .codeowners
+                .as_ref()
+                .map(|o| o.len() as i64)
+                .unwrap_or(-1),
+            Column::Labels => repo.labels.as_ref().map(|l| l.len() as i64).unwrap_or(-1),
+            Column::Downloads => repo
+                .downloads
+                .as_ref()
+                .map(|d| d.total as i64)
+                .unwrap_or(-1),
+            Column::Docs => repo
+                .docs
+                .as_ref()
+                .map(|d| d.has_readme as i64)
+                .unwrap_or(-1),
+            Column::NewStars => repo.growth.as_ref().map(|g| g.stars as i64).unwrap_or(-1),
+            Column::NewForks => repo.growth.as_ref().map(|g| g.forks as i64).unwrap_or(-1),
+            Column::Commits => repo
+                .commit_days
+                .as_ref()
+                .map(|d| d.values().sum::<u32>() as i64)
+                .unwrap_or(-1),
+            Column::HasIssues | Column::HasWiki | Column::HasPages | Column::HasDiscussions => repo
+                .features
+                .as_ref()
+                .and_then(|f| feature(self, f))
+                .map(|b| b as i64)
+                .unwrap_or(-1),
+            Column::Advisories => repo
+                .advisories
+                .as_ref()
+                .map(|a| a.len() as i64)
+                .unwrap_or(-1),
+            Column::DependabotPrs => repo
+                .dependency_updates
+                .as_ref()
+                .map(|d| d.opened as i64 - d.merged as i64 - d.closed_unmerged as i64)
+                .unwrap_or(-1),
+        }
+    }
+
+    pub fn is_approximate(&self, repo: &RepoStats) -> bool {
+        match self {
+            Column::Pushes => repo.pushes.as_ref().is_some_and(|p| p.truncated),
+            Column::Deployments => repo.deployments.as_ref().is_some_and(|d| d.truncated),
+            Column::DependabotPrs => repo
+                .dependency_updates
+                .as_ref()
+                .is_some_and(|d| d.truncated),
+            Column::Downloads => repo.downloads.as_ref().is_some_and(|d| d.truncated),
+            Column::NewStars | Column::NewForks => {
+                repo.growth.as_ref().is_some_and(|g| g.truncated)
+            }
+            Column::Security => repo.security.as_ref().is_some_and(|s| s.truncated),
+            Column::Advisories => repo.advisories_truncated,
+            Column::IssueAge => repo.issue_ages_truncated,
+            Column::Health => repo.releases_truncated,
+            _ => false,
+        }
+    }
+
+    pub fn needs_prs(&self) -> bool {
+        matches!(self, Column::Issues | Column::Prs)
+    }
+
+    pub fn needs_traffic(&self) -> bool {
+        matches!(self, Column::Views | Column::Clones)
+    }
+
+    pub fn needs_templates(&self) -> bool {
+        matches!(self, Column::IssueTemplates | Column::PrTemplate)
+    }
+
+    pub fn needs_merge_settings(&self) -> bool {
+        matches!(
+            self,
+            Column::MergeMethods | Column::AutoMerge | Column::DeleteBranch
+        )
+    }
+
+    pub fn needs_growth(&self) -> bool {
+        matches!(self, Column::NewStars | Column::NewForks)
+    }
+
+    pub fn needs_features(&self) -> bool {
+        matches!(
+            self,
+            Column::HasIssues | Column::HasWiki | Column::HasPages | Column::HasDiscussions
+        )
+    }
+
+    pub fn csv_field(&self) -> Option<&'static str> {
+        match self {
+            Column::Stars => Some("stars"),
+            Column::Forks => Some("forks"),
+            Column::Followers => Some("followers"),
+            Column::UpdatedAt => Some("updated_at"),
+            Column::PushedAt => Some("pushed_at"),
+            Column::OpenIssues => Some("open_issues_count"),
+            Column::Issues => Some("issues"),
+            Column::Prs => Some("open_prs"),
+            Column::Views => Some("views"),
+            Column::Clones => Some("clones"),
+            Column::Health => Some("health_avg"),
+            Column::Pushes => Some("pushes_90d"),
+            Column::Devcontainer => Some("devcontainer_pct"),
+            Column::IssueTemplates => Some("issue_templates_pct"),
+            Column::PrTemplate => Some("pr_templates_pct"),
+            Column::MergeMethods => Some("merge_configs"),
+            Column::AutoMerge | Column::DeleteBranch => None,
+            Column::ActionsPolicy => Some("actions_compliant_pct"),
+            Column::Environments => None,
+            Column::Deployments => Some("deployments_30d"),
+            Column::Traceability => Some("traceability_anomalies"),
+            Column::Security => Some("security_alerts"),
+            Column::Ci => Some("ci_failing"),
+            Column::StarHistory => Some("stars_12_months"),
+            Column::DependabotPrs => Some("dependabot_merged_pct"),
+            Column::Advisories => Some("advisories"),
+            Column::BranchProtection => Some("unprotected_branches"),
+            Column::IssueAge => Some("issue_age_median_days"),
+            Column::Status => Some("dead_repos"),
+            Column::Milestones => Some("milestone_completion"),
+            Column::Loc => Some("code_lines"),
+            Column::Files => Some("files"),
+            Column::Churn => Some("churn_lines"),
+            Column::Codeowners => Some("repos_with_codeowners"),
+            Column::Branches => Some("branches"),
+            Column::ReviewLatency => Some("merge_hours_median"),
+            Column::StarVelocity => Some("stars_per_month"),
+            Column::Dependencies => Some("direct_dependencies"),
+            Column::HasIssues => Some("repos_with_issues"),
+            Column::HasWiki => Some("repos_with_wiki"),
+            Column::HasPages => Some("repos_with_pages"),
+            Column::HasDiscussions => Some("repos_with_discussions"),
+            Column::Labels => Some("open_issue_labels"),
+            Column::Downloads => Some("release_downloads"),
+            Column::Docs => Some("repos_without_readme"),
+            Column::NewStars => Some("new_stars"),
+            Column::NewForks => Some("new_forks"),
+            Column::Commits => Some("commits_12_months"),
+            Column::DefaultBranch => None,
+            Column::Size => Some("size"),
+            Column::License | Column::Created | Column::Description | Column::Topics => None,
+        }
+    }
+
+    // Whether the raw value of a repository is a plain number, so names,
+    // branches or descriptions that happen to parse stay text
+    fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Column::Stars
+                | Column::Forks
+                | Column::Followers
+                | Column::OpenIssues
+                | Column::Issues
+                | Column::Prs
+                | Column::Size
+                | Column::Health
+                | Column::Advisories
+                | Column::Loc
+                | Column::Files
+                | Column::Branches
+                | Column::StarVelocity
+                | Column::Commits
+        )
+    }
+
+    pub fn typed_value(&self, repo: &RepoStats, style: &Style) -> CellValue {
+        match self {
+            Column::UpdatedAt => CellValue::Date(repo.updated_at),
+            Column::PushedAt => repo
+                .pushed_at
+                .map(CellValue::Date)
+                .unwrap_or(CellValue::Text(String::new())),
+            Column::Created => CellValue::Date(repo.created_at),
+            _ => {
+                let value = self.value(repo, &Style::raw(style.now, style.units));
+                if self.is_numeric() {
+                    typed(value)
+                } else {
+                    CellValue::Text(value)
+                }
+            }
+        }
+    }
+
+    pub fn typed_summary(&self, stats: &SumStats, units: &Units) -> CellValue {
+        match self {
+            Column::UpdatedAt => CellValue::Date(stats.updated_at),
+            Column::PushedAt => CellValue::Date(stats.pushed_at),
+            _ => typed(self.csv_value(stats, units)),
+        }
+    }
+
+    pub fn csv_value(&self, stats: &SumStats, units: &Units) -> String {
+        match self {
+            Column::Stars => stats.stars.to_string(),
+            Column::Forks => stats.forks.to_string(),
+            Column::Followers => stats.followers.to_string(),
+            Column::UpdatedAt => format::timestamp(stats.updated_at),
+            Column::PushedAt => format::timestamp(stats.pushed_at),
+            Column::OpenIssues => stats.open_issues_count.to_string(),
+            Column::Issues => stats
+                .open_issues_count
+                .saturating_sub(stats.open_prs)
+                .to_string(),
+            Column::Prs => stats.open_prs.to_string(),
+            Column::Views => stats.views.to_string(),
+            Column::Clones => stats.clones.to_string(),
+            Column::Health => stats
+                .health_avg()
+                .map(|h| units.ratio(h))
+                .unwrap_or_default(),
+            Column::Pushes => stats.pushes.to_string(),
+            Column::Devcontainer => stats
+                .devcontainer_pct()
+                .map(|pct| units.ratio(pct))
+                .unwrap_or_default(),
+            Column::IssueTemplates => {
+                ratio(stats.community.issue_templates, stats.community.checked)
+                    .map(|pct| units.ratio(pct))
+                    .unwrap_or_default()
+            }
+            Column::PrTemplate => ratio(stats.community.pr_templates, stats.community.checked)
+                .map(|pct| units.ratio(pct))
+                .unwrap_or_default(),
+            Column::MergeMethods => stats.merge_configs.len().to_string(),
+            Column::ActionsPolicy => ratio(stats.actions_compliant, stats.actions_checked)
+                .map(|pct| units.ratio(pct))
+                .unwrap_or_default(),
+            Column::Deployments => stats.deployments_30d.to_string(),
+            Column::Traceability => stats.traceability_anomalies.to_string(),
+            Column::Security => (stats.dependabot_alerts + stats.code_scanning_alerts).to_string(),
+            Column::Ci => stats.ci_failing.to_string(),
+            Column::StarHistory => stats.star_history.iter().sum::<u32>().to_string(),
+            Column::Advisories => total_advisories(stats).to_string(),
+            Column::BranchProtection => stats.unprotected_branches.to_string(),
+            Column::IssueAge => median(&stats.issue_ages)
+                .map(|m| m.to_string())
+                .unwrap_or_default(),
+            Column::Status => stats
+                .statuses
+                .get(Status::Dead.label())
+                .copied()
+                .unwrap_or(0)
+                .to_string(),
+            Column::DefaultBranch => "".to_string(),
+            Column::Loc => stats.code_lines.to_string(),
+            Column::Files => stats.files.to_string(),
+            Column::Churn => stats.churn_lines.to_string(),
+            Column::Codeowners => stats.codeowners_present.to_string(),
+            Column::Branches => stats.branches.to_string(),
+            Column::ReviewLatency => median(&stats.review_latency.merge_hours)
+                .map(|m| m.to_string())
+                .unwrap_or_default(),
+            Column::StarVelocity => units.decimal(stats.star_velocity),
+            Column::Dependencies => stats.direct_dependencies.to_string(),
+            Column::Labels => stats.labels.len().to_string(),
+            Column::Downloads => stats.release_downloads.to_string(),
+            Column::Docs => stats.docs.without_readme.to_string(),
+            Column::NewStars => stats.new_stars.to_string(),
+            Column::NewForks => stats.new_forks.to_string(),
+            Column::Commits => stats.commits_12_months.to_string(),
+            Column::HasIssues | Column::HasWiki | Column::HasPages | Column::HasDiscussions => {
+                feature_count(self, &stats.features).to_string()
+            }
+            Column::Milestones => ratio(
+                stats.milestone_issues_closed,
+                stats.milestone_issues_open + stats.milestone_issues_closed,
+            )
+            .map(|pct| units.ratio(pct))
+            .unwrap_or_default(),
+            Column::DependabotPrs => {
+                ratio(stats.dependency_prs_merged, stats.dependency_prs_opened)
+                    .map(|pct| units.ratio(pct))
+                    .unwrap_or_default()
+            }
+            Column::Size => units.size_value(stats.size),
+            Column::AutoMerge | Column::DeleteBranch | Column::Environments => "".to_string(),
+            Column::License | Column::Created | Column::Description | Column::Topics => {
+                "".to_string()
+            }
+        }
+    }
+}
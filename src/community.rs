@@ -0,0 +1,30 @@
+use comfy_table::{Cell, Color, Table};
+
+use crate::columns::percent;
+use crate::render;
+use crate::stats::CommunityCounts;
+
+pub fn table(counts: &CommunityCounts) -> Table {
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Community health").fg(Color::Green),
+        Cell::new("Repositories").fg(Color::Green),
+        Cell::new("Adoption").fg(Color::Green),
+    ]);
+    for (name, count) in [
+        ("README", counts.readme),
+        ("CONTRIBUTING", counts.contributing),
+        ("Issue templates", counts.issue_templates),
+        ("Issue forms", counts.issue_forms),
+        ("PR template", counts.pr_templates),
+    ] {
+        table.add_row(vec![
+            name.to_string(),
+            format!("{count}/{}", counts.checked),
+            percent(count, counts.checked)
+                .map(|pct| format!("{pct}%"))
+                .unwrap_or_default(),
+        ]);
+    }
+    table
+}
@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use comfy_table::{Cell, Color};
+
+use crate::format;
+use crate::render;
+use crate::scan::{self, Context};
+use crate::stats::RepoStats;
+
+fn delta(left: u32, right: u32) -> Cell {
+    let delta = right as i64 - left as i64;
+    match delta {
+        0 => Cell::new("0"),
+        d if d > 0 => Cell::new(format!("+{d}")).fg(Color::Green),
+        d => Cell::new(d).fg(Color::Red),
+    }
+}
+
+fn pushed(repo: Option<&RepoStats>, newer: bool) -> Cell {
+    match repo {
+        Some(repo) if newer => Cell::new(format::day(repo.pushed_at)).fg(Color::Green),
+        Some(repo) => Cell::new(format::day(repo.pushed_at)),
+        None => Cell::new(""),
+    }
+}
+
+// Matches repositories by name, e.g. an upstream organization against the one holding its forks
+pub async fn run(ctx: &Context, left: &str, right: &str) -> Result<(), String> {
+    let now = Utc::now();
+    let (left_report, right_report) = futures::join!(
+        scan::scan_org(ctx, left, now),
+        scan::scan_org(ctx, right, now)
+    );
+    let left_report = left_report.map_err(|e| format!("Cannot scan {left}: {e}"))?;
+    let right_report = right_report.map_err(|e| format!("Cannot scan {right}: {e}"))?;
+
+    let mut repos: BTreeMap<&str, (Option<&RepoStats>, Option<&RepoStats>)> = BTreeMap::new();
+    for (repo, _) in &left_report.rows {
+        repos.entry(&repo.name).or_default().0 = Some(repo);
+    }
+    for (repo, _) in &right_report.rows {
+        repos.entry(&repo.name).or_default().1 = Some(repo);
+    }
+
+    let mut table = render::new_table();
+    table.set_header(
+        [
+            "Repository".to_string(),
+            format!("{left} stars"),
+            format!("{right} stars"),
+            "Difference".to_string(),
+            format!("{left} last push"),
+            format!("{right} last push"),
+        ]
+        .map(|title| Cell::new(title).fg(Color::Green)),
+    );
+    let (mut only_left, mut only_right, mut both) = (0, 0, 0);
+    for (name, (l, r)) in &repos {
+        let stars =
+            |repo: &Option<&RepoStats>| repo.map(|r| r.stars.to_string()).unwrap_or_default();
+        let difference = match (l, r) {
+            (Some(l), Some(r)) => {
+                both += 1;
+                delta(l.stars, r.stars)
+            }
+            (Some(_), None) => {
+                only_left += 1;
+                Cell::new(format!("only in {left}")).fg(Color::Yellow)
+            }
+            _ => {
+                only_right += 1;
+                Cell::new(format!("only in {right}")).fg(Color::Yellow)
+            }
+        };
+        let (left_newer, right_newer) = match (l, r) {
+            (Some(l), Some(r)) => (l.pushed_at > r.pushed_at, r.pushed_at > l.pushed_at),
+            _ => (false, false),
+        };
+        table.add_row(vec![
+            Cell::new(name),
+            Cell::new(stars(l)),
+            Cell::new(stars(r)),
+            difference,
+            pushed(*l, left_newer),
+            pushed(*r, right_newer),
+        ]);
+    }
+    println!("{table}");
+    println!(
+        "{both} in both, {only_left} only in {left}, {only_right} only in {right}; \
+         stars {} vs {}",
+        left_report.stats.stars, right_report.stats.stars
+    );
+    Ok(())
+}
@@ -0,0 +1,17 @@
+use clap::CommandFactory;
+use clap_complete::Shell;
+use clap_mangen::Man;
+
+use crate::cli::Cli;
+
+pub fn print(shell: Shell) {
+    let mut command = Cli::command();
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+}
+
+pub fn print_man_page() -> Result<(), String> {
+    Man::new(Cli::command())
+        .render(&mut std::io::stdout())
+        .map_err(|e| format!("Cannot write man page: {e}"))
+}
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::alerts::{Rule, Silence};
+use crate::cli::{CsvSchema, OutputFormat};
+use crate::columns::Column;
+use crate::format::{Rounding, SizeUnit};
+use crate::health::Weights;
+use crate::history::Retention;
+use crate::period::Period;
+use crate::remediation::Remediation;
+
+const LOCAL_CONFIG: &str = "github-repo-info.toml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub orgs: Vec<String>,
+    pub groups: HashMap<String, Vec<String>>,
+    pub token_env: Option<String>,
+    pub token_file: Option<PathBuf>,
+    pub latest_n: Option<usize>,
+    pub period: Option<Period>,
+    pub metadata: Option<PathBuf>,
+    pub group_by: Option<String>,
+    pub columns: Vec<Column>,
+    pub policy: Option<PathBuf>,
+    pub health_weights: Option<Weights>,
+    pub sort: Option<SortKeys>,
+    pub format: Option<OutputFormat>,
+    pub brand_color: Option<String>,
+    pub csv_schema: Option<CsvSchema>,
+    pub timezone: Option<String>,
+    pub date_format: Option<String>,
+    pub exclude: Vec<String>,
+    pub highlight: Vec<String>,
+    pub retention: Retention,
+    pub history_passphrase_env: Option<String>,
+    pub history_url: Option<String>,
+    pub alerts: Vec<Rule>,
+    pub silences: Vec<Silence>,
+    pub remediation: Remediation,
+    pub size_unit: Option<SizeUnit>,
+    pub precision: Option<usize>,
+    pub rounding: Option<Rounding>,
+    pub status_days: Vec<i64>,
+    pub virtual_orgs: HashMap<String, Vec<String>>,
+    pub perf_stats: bool,
+    pub branch_threshold: Option<u32>,
+}
+
+// `sort = "stars"` keeps working next to the list form
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum SortKeys {
+    One(Column),
+    Many(Vec<Column>),
+}
+
+impl SortKeys {
+    pub fn columns(&self) -> Vec<Column> {
+        match self {
+            SortKeys::One(column) => vec![*column],
+            SortKeys::Many(columns) => columns.clone(),
+        }
+    }
+}
+
+fn user_config_path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("github-repo-info").join("config.toml"))
+}
+
+impl Config {
+    pub fn load(path: Option<&Path>) -> Result<Self, String> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => {
+                let local = PathBuf::from(LOCAL_CONFIG);
+                match user_config_path() {
+                    _ if local.exists() => local,
+                    Some(user) if user.exists() => user,
+                    _ => return Ok(Self::default()),
+                }
+            }
+        };
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Cannot read config file {}: {e}", path.display()))?;
+        toml::from_str(&content).map_err(|e| format!("Invalid config file {}: {e}", path.display()))
+    }
+
+    pub fn expand_orgs(&self, orgs: &[String]) -> Result<Vec<String>, String> {
+        let orgs = if orgs.is_empty() {
+            &self.orgs[..]
+        } else {
+            orgs
+        };
+        let mut expanded = vec![];
+        for org in orgs {
+            match org.strip_prefix('@') {
+                Some(group) => expanded.extend(
+                    self.groups
+                        .get(group)
+                        .ok_or(format!("Unknown org group {group}"))?
+                        .iter()
+                        .cloned(),
+                ),
+                None => expanded.push(org.clone()),
+            }
+        }
+        Ok(expanded)
+    }
+
+    pub fn tokens(&self, tokens: &[String]) -> Result<Vec<String>, String> {
+        if !tokens.is_empty() {
+            return Ok(tokens.to_vec());
+        }
+        if let Some(path) = &self.token_file {
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| format!("Cannot read token file {}: {e}", path.display()))?;
+            return Ok(content
+                .lines()
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string)
+                .collect());
+        }
+        let var = self.token_env.as_deref().unwrap_or("GITHUB_TOKEN");
+        Ok(std::env::var(var)
+            .ok()
+            .filter(|t| !t.is_empty())
+            .into_iter()
+            .collect())
+    }
+
+    pub fn history_passphrase(&self) -> Result<Option<String>, String> {
+        match &self.history_passphrase_env {
+            Some(var) => std::env::var(var)
+                .ok()
+                .filter(|p| !p.is_empty())
+                .map(Some)
+                .ok_or(format!("History passphrase variable {var} is not set")),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::cli::Cli;
+use crate::stats::RepoStats;
+use crate::watch;
+
+#[derive(Default)]
+pub struct DateFilter {
+    pushed_since: Option<DateTime<Utc>>,
+    pushed_before: Option<DateTime<Utc>>,
+    created_since: Option<DateTime<Utc>>,
+    created_before: Option<DateTime<Utc>>,
+    updated_since: Option<DateTime<Utc>>,
+    updated_before: Option<DateTime<Utc>>,
+}
+
+// Either a calendar date or an age such as 180d, counted back from now
+fn parse(value: Option<&str>, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, String> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let midnight = date.and_hms_opt(0, 0, 0).expect("midnight ok");
+        return Ok(Some(DateTime::<Utc>::from_utc(midnight, Utc)));
+    }
+    let age = watch::parse_interval(value)
+        .map_err(|_| format!("Invalid date {value}, expected YYYY-MM-DD or an age like 180d"))?;
+    Ok(Some(
+        now - chrono::Duration::from_std(age).map_err(|e| e.to_string())?,
+    ))
+}
+
+fn within(at: DateTime<Utc>, since: Option<DateTime<Utc>>, before: Option<DateTime<Utc>>) -> bool {
+    since.is_none_or(|since| at >= since) && before.is_none_or(|before| at < before)
+}
+
+impl DateFilter {
+    pub fn from_args(args: &Cli, now: DateTime<Utc>) -> Result<Self, String> {
+        Ok(Self {
+            pushed_since: parse(args.pushed_since.as_deref(), now)?,
+            pushed_before: parse(args.pushed_before.as_deref(), now)?,
+            created_since: parse(args.created_since.as_deref(), now)?,
+            created_before: parse(args.created_before.as_deref(), now)?,
+            updated_since: parse(args.updated_since.as_deref(), now)?,
+            updated_before: parse(args.updated_before.as_deref(), now)?,
+        })
+    }
+
+    pub fn matches(&self, repo: &RepoStats) -> bool {
+        within(repo.last_push(), self.pushed_since, self.pushed_before)
+            && within(repo.created_at, self.created_since, self.created_before)
+            && within(repo.updated_at, self.updated_since, self.updated_before)
+    }
+}
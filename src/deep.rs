@@ -0,0 +1,256 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+
+use comfy_table::{Cell, Color, Table};
+use tokei::{Config, Languages};
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+
+use crate::diagnostics::{Diagnostics, Kind};
+use crate::format::Style;
+use crate::render;
+use crate::scan::OrgReport;
+use crate::stats::{Churn, DeepStats, RepoStats};
+
+const LARGEST_FILES: usize = 3;
+const HOTSPOTS: usize = 3;
+const OVERLAPS: usize = 10;
+
+pub struct Analyzer {
+    permits: Semaphore,
+    // Repository size as reported by the API, in KiB
+    max_size: u32,
+    dir: PathBuf,
+    churn_since: Option<DateTime<Utc>>,
+}
+
+fn walk(dir: &Path, root: &Path, files: &mut Vec<(String, u64)>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() && entry.file_name() != ".git" {
+            walk(&entry.path(), root, files)?;
+        } else if file_type.is_file() {
+            let path = entry.path();
+            let name = path.strip_prefix(root).unwrap_or(&path);
+            files.push((name.display().to_string(), entry.metadata()?.len()));
+        }
+    }
+    Ok(())
+}
+
+fn churn(path: &Path, since: DateTime<Utc>) -> std::io::Result<Churn> {
+    // The oldest commit of a shallow clone diffs against nothing and would count the whole tree
+    let boundary: HashSet<String> = std::fs::read_to_string(path.join(".git/shallow"))
+        .unwrap_or_default()
+        .lines()
+        .map(str::to_string)
+        .collect();
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(["log", "--no-merges", "--numstat", "--format=@%H %aE"])
+        .arg(format!("--since={}", since.to_rfc3339()))
+        .output()?;
+    let mut churn = Churn::default();
+    let mut directories: HashMap<String, u64> = HashMap::new();
+    let mut skip = false;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(commit) = line.strip_prefix('@') {
+            let (hash, author) = commit.split_once(' ').unwrap_or((commit, ""));
+            skip = boundary.contains(hash);
+            churn.commits += 1;
+            let author = author.to_lowercase();
+            if !author.is_empty() && !churn.authors.contains(&author) {
+                churn.authors.push(author);
+            }
+            continue;
+        }
+        let mut fields = line.split('\t');
+        let (Some(added), Some(deleted), Some(file)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        if skip {
+            continue;
+        }
+        // Binary files report - instead of line counts
+        let added: u64 = added.parse().unwrap_or(0);
+        let deleted: u64 = deleted.parse().unwrap_or(0);
+        churn.added += added;
+        churn.deleted += deleted;
+        let directory = file.rsplit_once('/').map_or(".", |(dir, _)| dir);
+        *directories.entry(directory.to_string()).or_insert(0) += added + deleted;
+    }
+    let mut hotspots: Vec<(String, u64)> = directories.into_iter().collect();
+    hotspots.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    hotspots.truncate(HOTSPOTS);
+    churn.hotspots = hotspots;
+    Ok(churn)
+}
+
+fn analyze_checkout(path: &Path, churn_since: Option<DateTime<Utc>>) -> std::io::Result<DeepStats> {
+    let mut languages = Languages::new();
+    languages.get_statistics(&[path], &[".git"], &Config::default());
+    let mut files = vec![];
+    walk(path, path, &mut files)?;
+    let count = files.len() as u32;
+    files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    files.truncate(LARGEST_FILES);
+    Ok(DeepStats {
+        code_lines: languages.total().code as u64,
+        files: count,
+        largest_files: files,
+        churn: churn_since.map(|since| churn(path, since)).transpose()?,
+    })
+}
+
+impl Analyzer {
+    pub fn new(concurrency: usize, max_size_mb: u32, churn_since: Option<DateTime<Utc>>) -> Self {
+        Self {
+            permits: Semaphore::new(concurrency.max(1)),
+            max_size: max_size_mb.saturating_mul(1024),
+            dir: std::env::temp_dir().join(format!("github-repo-info-{}", std::process::id())),
+            churn_since,
+        }
+    }
+
+    pub async fn analyze(&self, org: &str, repo: &mut RepoStats, diagnostics: &Diagnostics) {
+        let name = repo.name.clone();
+        let skipped =
+            |message: &str| diagnostics.emit(Kind::DegradedMetric, Some(org), Some(&name), message);
+        if repo.size > self.max_size {
+            skipped(&format!(
+                "deep analysis skipped, {} KiB exceeds --deep-max-size",
+                repo.size
+            ));
+            return;
+        }
+        let Some(url) = repo.clone_url.clone() else {
+            skipped("deep analysis skipped, no clone URL");
+            return;
+        };
+        let _permit = self.permits.acquire().await.expect("deep semaphore ok");
+        let checkout = self.dir.join(format!("{org}-{name}"));
+        let clone = |history: Vec<String>| {
+            Command::new("git")
+                .args(["clone", "--quiet", "--no-tags", "--single-branch"])
+                .args(history)
+                .arg(&url)
+                .arg(&checkout)
+                .env("GIT_TERMINAL_PROMPT", "0")
+                .status()
+        };
+        let depth = vec!["--depth".to_string(), "1".to_string()];
+        let mut cloned = match self.churn_since {
+            Some(since) => clone(vec![format!("--shallow-since={}", since.to_rfc3339())]).await,
+            None => clone(depth.clone()).await,
+        };
+        // Git refuses --shallow-since when no commit falls inside the window
+        if self.churn_since.is_some() && !matches!(&cloned, Ok(status) if status.success()) {
+            let _ = tokio::fs::remove_dir_all(&checkout).await;
+            cloned = clone(depth).await;
+        }
+        let result = match cloned {
+            Ok(status) if status.success() => {
+                let path = checkout.clone();
+                let churn_since = self.churn_since;
+                tokio::task::spawn_blocking(move || analyze_checkout(&path, churn_since))
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|r| r.map_err(|e| e.to_string()))
+            }
+            Ok(status) => Err(format!("git clone exited with {status}")),
+            Err(e) => Err(format!("cannot run git: {e}")),
+        };
+        // Checkouts are removed right away so disk usage stays at the clones in flight
+        let _ = tokio::fs::remove_dir_all(&checkout).await;
+        match result {
+            Ok(deep) => repo.deep = Some(deep),
+            Err(e) => skipped(&format!("deep analysis failed: {e}")),
+        }
+    }
+}
+
+pub fn largest_files_table(report: &OrgReport, style: &Style) -> Table {
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Repository").fg(Color::Green),
+        Cell::new("Largest files").fg(Color::Green),
+        Cell::new("Size").fg(Color::Green),
+    ]);
+    for (repo, _) in &report.rows {
+        let Some(deep) = &repo.deep else {
+            continue;
+        };
+        for (path, bytes) in &deep.largest_files {
+            table.add_row(vec![
+                Cell::new(&repo.name),
+                Cell::new(path),
+                Cell::new(style.units.human_size(*bytes)),
+            ]);
+        }
+    }
+    table
+}
+
+pub fn hotspots_table(report: &OrgReport) -> Table {
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Repository").fg(Color::Green),
+        Cell::new("Hotspot directory").fg(Color::Green),
+        Cell::new("Changed lines").fg(Color::Green),
+    ]);
+    for (repo, _) in &report.rows {
+        let Some(churn) = repo.deep.as_ref().and_then(|d| d.churn.as_ref()) else {
+            continue;
+        };
+        for (directory, lines) in &churn.hotspots {
+            table.add_row(vec![
+                Cell::new(&repo.name),
+                Cell::new(directory),
+                Cell::new(lines),
+            ]);
+        }
+    }
+    table
+}
+
+pub fn overlap_table(report: &OrgReport) -> Option<Table> {
+    let repos: Vec<(&str, &Churn)> = report
+        .rows
+        .iter()
+        .filter_map(|(repo, _)| Some((repo.name.as_str(), repo.deep.as_ref()?.churn.as_ref()?)))
+        .collect();
+    let mut pairs = vec![];
+    for (i, (a, a_churn)) in repos.iter().enumerate() {
+        for (b, b_churn) in &repos[i + 1..] {
+            let shared = a_churn
+                .authors
+                .iter()
+                .filter(|author| b_churn.authors.contains(author))
+                .count();
+            if shared > 0 {
+                pairs.push((*a, *b, shared));
+            }
+        }
+    }
+    if pairs.is_empty() {
+        return None;
+    }
+    pairs.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| (a.0, a.1).cmp(&(b.0, b.1))));
+    pairs.truncate(OVERLAPS);
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Repository").fg(Color::Green),
+        Cell::new("Repository").fg(Color::Green),
+        Cell::new("Shared contributors").fg(Color::Green),
+    ]);
+    for (a, b, shared) in pairs {
+        table.add_row(vec![Cell::new(a), Cell::new(b), Cell::new(shared)]);
+    }
+    Some(table)
+}
@@ -0,0 +1,135 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Level {
+    Info,
+    Warning,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    OrgNotFound,
+    OrgFailed,
+    RepoSkipped,
+    MetadataMissing,
+    DegradedMetric,
+    AlertFiring,
+    AlertResolved,
+}
+
+impl Kind {
+    fn level(&self) -> Level {
+        match self {
+            Kind::OrgNotFound
+            | Kind::OrgFailed
+            | Kind::MetadataMissing
+            | Kind::DegradedMetric
+            | Kind::AlertFiring => Level::Warning,
+            Kind::RepoSkipped | Kind::AlertResolved => Level::Info,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Event<'a> {
+    time: DateTime<Utc>,
+    level: Level,
+    kind: Kind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    org: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repo: Option<&'a str>,
+    message: &'a str,
+}
+
+#[derive(Default)]
+pub struct Diagnostics {
+    file: Mutex<Option<File>>,
+    warnings: AtomicU32,
+    degraded: AtomicU32,
+    failed_orgs: AtomicU32,
+    allow_failures: bool,
+}
+
+impl Diagnostics {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = File::create(path)
+            .map_err(|e| format!("Cannot create diagnostics file {}: {e}", path.display()))?;
+        Ok(Self {
+            file: Mutex::new(Some(file)),
+            ..Self::default()
+        })
+    }
+
+    pub fn allow_failures(self, allow: bool) -> Self {
+        Self {
+            allow_failures: allow,
+            ..self
+        }
+    }
+
+    pub fn emit(&self, kind: Kind, org: Option<&str>, repo: Option<&str>, message: &str) {
+        if matches!(kind.level(), Level::Warning) {
+            self.warnings.fetch_add(1, Ordering::Relaxed);
+        }
+        if matches!(kind, Kind::DegradedMetric) {
+            self.degraded.fetch_add(1, Ordering::Relaxed);
+        }
+        if matches!(kind, Kind::OrgNotFound | Kind::OrgFailed) {
+            self.failed_orgs.fetch_add(1, Ordering::Relaxed);
+        }
+        // Degraded metrics are counted in the run summary, one line each would bury the warnings
+        match (kind.level(), kind) {
+            (_, Kind::DegradedMetric) | (Level::Info, _) => {
+                tracing::info!(kind = ?kind, org, repo, "{message}")
+            }
+            (Level::Warning, _) => tracing::warn!(kind = ?kind, org, repo, "{message}"),
+        }
+        let mut file = self.file.lock().expect("diagnostics lock ok");
+        if let Some(file) = file.as_mut() {
+            let event = Event {
+                time: Utc::now(),
+                level: kind.level(),
+                kind,
+                org,
+                repo,
+                message,
+            };
+            let mut line = serde_json::to_string(&event).expect("serialize diagnostic ok");
+            line.push('\n');
+            file.write_all(line.as_bytes())
+                .expect("diagnostics file write ok");
+        }
+    }
+
+    pub fn warnings(&self) -> u32 {
+        self.warnings.load(Ordering::Relaxed)
+    }
+
+    pub fn degraded(&self) -> u32 {
+        self.degraded.load(Ordering::Relaxed)
+    }
+
+    pub fn failed_orgs(&self) -> u32 {
+        self.failed_orgs.load(Ordering::Relaxed)
+    }
+
+    pub fn check_orgs(&self) -> Result<(), String> {
+        match self.failed_orgs() {
+            0 => Ok(()),
+            _ if self.allow_failures => Ok(()),
+            n => Err(format!(
+                "{n} organizations failed, pass --allow-failures to exit successfully anyway"
+            )),
+        }
+    }
+}
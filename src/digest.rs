@@ -0,0 +1,158 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::history::{RepoSnapshot, Snapshot};
+
+const STALE_DAYS: i64 = 90;
+const TOP_GAINERS: usize = 5;
+const AVATAR_SIZE: u32 = 40;
+
+// github.com serves every account's avatar under a stable URL, so no API call is needed
+fn heading(org: &str) -> String {
+    format!(
+        "## <img src=\"https://github.com/{org}.png?size={AVATAR_SIZE}\" alt=\"\" width=\"20\" height=\"20\"> {org}"
+    )
+}
+
+fn signed(change: i64) -> String {
+    format!("{change:+}")
+}
+
+fn is_stale(repo: &RepoSnapshot, at: DateTime<Utc>) -> bool {
+    at - repo.pushed_at > Duration::days(STALE_DAYS)
+}
+
+fn org_section(out: &mut String, org: &str, oldest: &Snapshot, newest: &Snapshot) {
+    let before: HashMap<&str, &RepoSnapshot> =
+        oldest.repos.iter().map(|r| (r.name.as_str(), r)).collect();
+
+    writeln!(out, "{}\n", heading(org)).expect("write digest ok");
+    writeln!(
+        out,
+        "Stars went from {} to {} ({}), open issues from {} to {} ({}), across {} repositories.\n",
+        oldest.stats.stars,
+        newest.stats.stars,
+        signed(newest.stats.stars as i64 - oldest.stats.stars as i64),
+        oldest.stats.open_issues_count,
+        newest.stats.open_issues_count,
+        signed(newest.stats.open_issues_count as i64 - oldest.stats.open_issues_count as i64),
+        newest.repos.len(),
+    )
+    .expect("write digest ok");
+
+    let mut gainers: Vec<(&RepoSnapshot, i64)> = newest
+        .repos
+        .iter()
+        .filter_map(|repo| {
+            let previous = before.get(repo.name.as_str())?;
+            Some((repo, repo.stars as i64 - previous.stars as i64))
+        })
+        .filter(|(_, gain)| *gain > 0)
+        .collect();
+    gainers.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.name.cmp(&b.0.name)));
+    writeln!(out, "**Top gainers**\n").expect("write digest ok");
+    if gainers.is_empty() {
+        writeln!(out, "No repository gained stars.").expect("write digest ok");
+    }
+    for (repo, gain) in gainers.into_iter().take(TOP_GAINERS) {
+        writeln!(
+            out,
+            "- {} gained {gain} stars, now at {}",
+            repo.name, repo.stars
+        )
+        .expect("write digest ok");
+    }
+
+    // Old snapshots without per-repository data would list everything as new
+    if !oldest.repos.is_empty() {
+        let new_repos: Vec<&RepoSnapshot> = newest
+            .repos
+            .iter()
+            .filter(|repo| !before.contains_key(repo.name.as_str()))
+            .collect();
+        writeln!(out, "\n**New repositories**\n").expect("write digest ok");
+        if new_repos.is_empty() {
+            writeln!(out, "No new repositories.").expect("write digest ok");
+        }
+        for repo in new_repos {
+            writeln!(
+                out,
+                "- {}, created {}",
+                repo.name,
+                repo.created_at.format("%Y-%m-%d")
+            )
+            .expect("write digest ok");
+        }
+    }
+
+    let newly_stale: Vec<&RepoSnapshot> = newest
+        .repos
+        .iter()
+        .filter(|repo| is_stale(repo, newest.collected_at))
+        .filter(|repo| {
+            before
+                .get(repo.name.as_str())
+                .map(|previous| !is_stale(previous, oldest.collected_at))
+                .unwrap_or(false)
+        })
+        .collect();
+    writeln!(out, "\n**Newly stale** (no push in {STALE_DAYS} days)\n").expect("write digest ok");
+    if newly_stale.is_empty() {
+        writeln!(out, "No repositories went stale.").expect("write digest ok");
+    }
+    for repo in newly_stale {
+        writeln!(
+            out,
+            "- {}, last push {}",
+            repo.name,
+            repo.pushed_at.format("%Y-%m-%d")
+        )
+        .expect("write digest ok");
+    }
+    writeln!(out).expect("write digest ok");
+}
+
+pub fn render(
+    history: &[Snapshot],
+    orgs: &[String],
+    since: Duration,
+    now: DateTime<Utc>,
+) -> String {
+    let start = now - since;
+    let orgs: BTreeSet<String> = if orgs.is_empty() {
+        history.iter().map(|s| s.org.clone()).collect()
+    } else {
+        orgs.iter().cloned().collect()
+    };
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "# Digest {} to {}\n",
+        start.format("%Y-%m-%d"),
+        now.format("%Y-%m-%d")
+    )
+    .expect("write digest ok");
+    for org in orgs {
+        let window: Vec<&Snapshot> = history
+            .iter()
+            .filter(|s| s.org.eq_ignore_ascii_case(&org) && s.collected_at >= start)
+            .collect();
+        let oldest = window.iter().min_by_key(|s| s.collected_at);
+        let newest = window.iter().max_by_key(|s| s.collected_at);
+        match (oldest, newest) {
+            (Some(oldest), Some(newest)) if oldest.collected_at < newest.collected_at => {
+                org_section(&mut out, &org, oldest, newest)
+            }
+            _ => writeln!(
+                out,
+                "{}\n\nNot enough snapshots in this window to compare.\n",
+                heading(&org)
+            )
+            .expect("write digest ok"),
+        }
+    }
+    out
+}
@@ -0,0 +1,87 @@
+use serde::Deserialize;
+
+use crate::client::Github;
+
+#[derive(Deserialize)]
+struct SearchResults {
+    items: Vec<FoundRepo>,
+}
+
+#[derive(Deserialize)]
+struct FoundRepo {
+    owner: Owner,
+}
+
+#[derive(Deserialize)]
+struct Owner {
+    login: String,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+fn push_org(orgs: &mut Vec<String>, owner: Owner) {
+    if owner.kind == "Organization" && !orgs.iter().any(|o| o.eq_ignore_ascii_case(&owner.login)) {
+        orgs.push(owner.login);
+    }
+}
+
+pub async fn orgs(
+    github: &Github,
+    query: Option<&str>,
+    repos: &[String],
+    max_orgs: usize,
+) -> Result<Vec<String>, String> {
+    if query.is_none() && repos.is_empty() {
+        return Err("discover needs --query or owner/name repositories".to_string());
+    }
+    let mut orgs = vec![];
+    for repo in repos {
+        let found: FoundRepo = github
+            .get(format!("repos/{repo}"), None::<&()>)
+            .await
+            .map_err(|e| format!("Cannot look up {repo}: {e}"))?;
+        push_org(&mut orgs, found.owner);
+    }
+    if let Some(query) = query {
+        // Search results are sorted by stars, so the most prominent organizations come first
+        let results: SearchResults = github
+            .get(
+                "search/repositories",
+                Some(&[("q", query), ("sort", "stars"), ("per_page", "100")]),
+            )
+            .await
+            .map_err(|e| format!("Search failed: {e}"))?;
+        for found in results.items {
+            push_org(&mut orgs, found.owner);
+        }
+    }
+    orgs.truncate(max_orgs);
+    Ok(orgs)
+}
+
+#[derive(Deserialize)]
+struct VisibleOrg {
+    login: String,
+}
+
+// Organizations the token's user belongs to; with read:org this includes private memberships
+pub async fn visible_orgs(github: &Github) -> Result<Vec<String>, String> {
+    let mut orgs = vec![];
+    let mut page = 1;
+    loop {
+        let page_param = page.to_string();
+        let found: Vec<VisibleOrg> = github
+            .get(
+                "user/orgs",
+                Some(&[("per_page", "100"), ("page", page_param.as_str())]),
+            )
+            .await
+            .map_err(|e| format!("Cannot list organizations: {e}"))?;
+        let last = found.len() < 100;
+        orgs.extend(found.into_iter().map(|org| org.login));
+        if last {
+            return Ok(orgs);
+        }
+        page += 1;
+    }
+}
@@ -0,0 +1,74 @@
+use comfy_table::{Cell, Color, Table};
+
+use crate::render;
+use crate::stats::{Docs, DocsCounts};
+
+// Long READMEs are mostly code and tables further down, the opening prose is
+// enough for the detector
+const SAMPLE_CHARS: usize = 2000;
+
+// ISO 639-3 code of the text's language, when the detector is confident
+pub fn detect(text: &str) -> Option<String> {
+    let sample: String = text
+        .lines()
+        .filter(|line| {
+            !line
+                .trim_start()
+                .starts_with(['#', '<', '[', '!', '|', '`'])
+        })
+        .flat_map(|line| line.chars().chain([' ']))
+        .take(SAMPLE_CHARS)
+        .collect();
+    whatlang::detect(&sample)
+        .filter(|info| info.is_reliable())
+        .map(|info| info.lang().code().to_string())
+}
+
+pub fn check(readme: Option<&str>, description: Option<&str>) -> Docs {
+    let description = description.filter(|d| !d.trim().is_empty());
+    Docs {
+        has_readme: readme.is_some(),
+        readme_language: readme.and_then(detect),
+        has_description: description.is_some(),
+        description_language: description.and_then(detect),
+    }
+}
+
+pub fn describe(docs: &Docs) -> String {
+    let readme = match (docs.has_readme, &docs.readme_language) {
+        (false, _) => "none",
+        (true, Some(language)) => language,
+        (true, None) => "?",
+    };
+    let description = match (docs.has_description, &docs.description_language) {
+        (false, _) => "none",
+        (true, Some(language)) => language,
+        (true, None) => "?",
+    };
+    format!("README {readme}, description {description}")
+}
+
+pub fn table(counts: &DocsCounts) -> Table {
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("README language").fg(Color::Green),
+        Cell::new("Repositories").fg(Color::Green),
+    ]);
+    let mut languages: Vec<(&String, &u32)> = counts.languages.iter().collect();
+    languages.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    let detected: u32 = counts.languages.values().sum();
+    for (language, count) in languages {
+        table.add_row(vec![Cell::new(language), Cell::new(count)]);
+    }
+    let undetected = counts.checked - counts.without_readme - detected;
+    if undetected > 0 {
+        table.add_row(vec![Cell::new("undetected"), Cell::new(undetected)]);
+    }
+    if counts.without_readme > 0 {
+        table.add_row(vec![
+            Cell::new("no README").fg(Color::Red),
+            Cell::new(counts.without_readme).fg(Color::Red),
+        ]);
+    }
+    table
+}
@@ -0,0 +1,65 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::scan::OrgListing;
+use crate::stats::{AuditEvent, Members, OrgProfile};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DumpedOrg {
+    pub org: String,
+    pub listing: OrgListing,
+    pub members: Option<Members>,
+    #[serde(default)]
+    pub audit: Option<Vec<AuditEvent>>,
+    #[serde(default)]
+    pub profile: Option<OrgProfile>,
+    #[serde(default)]
+    pub pinned: Option<Vec<String>>,
+}
+
+pub fn load(path: &Path) -> Result<Vec<DumpedOrg>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read dump {}: {e}", path.display()))?;
+    serde_json::from_str(&content).map_err(|e| format!("Invalid dump {}: {e}", path.display()))
+}
+
+// Shards of one organization collected on different runners become a single listing
+pub fn load_all(paths: &[PathBuf]) -> Result<Vec<DumpedOrg>, String> {
+    let mut orgs: Vec<DumpedOrg> = vec![];
+    for path in paths {
+        for org in load(path)? {
+            match orgs
+                .iter_mut()
+                .find(|o| o.org.eq_ignore_ascii_case(&org.org))
+            {
+                Some(merged) => {
+                    for repo in org.listing.repos {
+                        if !merged.listing.repos.iter().any(|r| r.name == repo.name) {
+                            merged.listing.repos.push(repo);
+                        }
+                    }
+                }
+                None => orgs.push(org),
+            }
+        }
+    }
+    Ok(orgs)
+}
+
+// Organizations not scanned this run keep their cached entries
+pub fn update_cache(path: &Path, scanned: &[DumpedOrg], cache: &[DumpedOrg]) -> Result<(), String> {
+    let mut orgs = scanned.to_vec();
+    orgs.extend(
+        cache
+            .iter()
+            .filter(|c| !scanned.iter().any(|o| o.org.eq_ignore_ascii_case(&c.org)))
+            .cloned(),
+    );
+    save(path, &orgs)
+}
+
+pub fn save(path: &Path, orgs: &[DumpedOrg]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(orgs).expect("serialize dump ok");
+    std::fs::write(path, content).map_err(|e| format!("Cannot write dump {}: {e}", path.display()))
+}
@@ -0,0 +1,535 @@
+use std::fmt::Display;
+use std::future::Future;
+
+use chrono::{DateTime, Utc};
+use futures::future::{join_all, BoxFuture};
+use futures::FutureExt;
+
+use crate::client::Github;
+use crate::codeowners;
+use crate::columns::Column;
+use crate::diagnostics::{Diagnostics, Kind};
+use crate::docs;
+use crate::fetch;
+use crate::graphql;
+use crate::provider::MetricProvider;
+use crate::stats::{Growth, RepoStats};
+
+pub struct Enrichments {
+    pub prs: bool,
+    pub traffic: bool,
+    pub health: bool,
+    pub activity: bool,
+    pub devcontainer: bool,
+    pub templates: bool,
+    pub merge_settings: bool,
+    pub actions_policy: bool,
+    pub deployments: bool,
+    pub traceability: bool,
+    pub security: bool,
+    pub ci: bool,
+    pub star_history: bool,
+    pub dependabot_prs: bool,
+    pub advisories: bool,
+    pub branch_protection: bool,
+    pub issue_age: bool,
+    pub milestones: bool,
+    pub codeowners: bool,
+    pub branch_count: bool,
+    pub review_latency: bool,
+    pub dependencies: bool,
+    pub features: bool,
+    // Part of `features`, the one flag repository listings leave out
+    pub discussions: bool,
+    pub labels: bool,
+    pub downloads: bool,
+    pub docs: bool,
+    pub growth: bool,
+    pub commit_activity: bool,
+}
+
+impl Enrichments {
+    pub fn count(&self) -> u32 {
+        [
+            self.prs,
+            self.traffic,
+            self.health,
+            self.activity,
+            self.devcontainer,
+            self.templates,
+            self.merge_settings,
+            self.actions_policy,
+            self.deployments,
+            self.traceability,
+            self.security,
+            self.ci,
+            self.star_history,
+            self.dependabot_prs,
+            self.advisories,
+            self.branch_protection,
+            self.issue_age,
+            self.milestones,
+            self.codeowners,
+            self.branch_count,
+            self.review_latency,
+            self.dependencies,
+            self.features,
+            self.labels,
+            self.downloads,
+            self.docs,
+            self.growth,
+            self.commit_activity,
+        ]
+        .into_iter()
+        .filter(|enabled| *enabled)
+        .count() as u32
+    }
+
+    pub fn from_columns(columns: &[Column]) -> Self {
+        Self {
+            prs: columns.iter().any(Column::needs_prs),
+            traffic: columns.iter().any(Column::needs_traffic),
+            health: columns.contains(&Column::Health),
+            activity: columns.contains(&Column::Pushes),
+            devcontainer: columns.contains(&Column::Devcontainer),
+            templates: columns.iter().any(Column::needs_templates),
+            merge_settings: columns.iter().any(Column::needs_merge_settings),
+            actions_policy: columns.contains(&Column::ActionsPolicy),
+            deployments: columns.contains(&Column::Environments)
+                || columns.contains(&Column::Deployments),
+            traceability: columns.contains(&Column::Traceability),
+            security: columns.contains(&Column::Security),
+            ci: columns.contains(&Column::Ci),
+            star_history: columns.contains(&Column::StarHistory),
+            dependabot_prs: columns.contains(&Column::DependabotPrs),
+            advisories: columns.contains(&Column::Advisories),
+            branch_protection: columns.contains(&Column::BranchProtection),
+            issue_age: columns.contains(&Column::IssueAge),
+            milestones: columns.contains(&Column::Milestones),
+            codeowners: columns.contains(&Column::Codeowners),
+            branch_count: columns.contains(&Column::Branches),
+            review_latency: columns.contains(&Column::ReviewLatency),
+            dependencies: columns.contains(&Column::Dependencies),
+            features: columns.iter().any(Column::needs_features),
+            discussions: columns.contains(&Column::HasDiscussions),
+            labels: columns.contains(&Column::Labels),
+            downloads: columns.contains(&Column::Downloads),
+            docs: columns.contains(&Column::Docs),
+            growth: columns.iter().any(Column::needs_growth),
+            commit_activity: columns.contains(&Column::Commits),
+        }
+    }
+}
+
+fn degraded(diagnostics: &Diagnostics, org: &str, repo: &str, message: &str) {
+    diagnostics.emit(Kind::DegradedMetric, Some(org), Some(repo), message);
+}
+
+// Writes a fetched value into the repository once all of its enrichments are in
+type Update = Box<dyn FnOnce(&mut RepoStats) + Send>;
+
+// The repository the enrichments of one call fetch for
+#[derive(Clone, Copy)]
+struct Target<'a> {
+    github: &'a Github,
+    org: &'a str,
+    name: &'a str,
+    diagnostics: &'a Diagnostics,
+}
+
+impl<'a> Target<'a> {
+    fn degraded(&self, message: &str) {
+        degraded(self.diagnostics, self.org, self.name, message);
+    }
+
+    // A failed fetch is reported as `{unavailable}: {error}` and leaves the field unset
+    fn task<T, E>(
+        self,
+        unavailable: &'static str,
+        fetched: impl Future<Output = Result<T, E>> + Send + 'a,
+        apply: impl FnOnce(&mut RepoStats, T) + Send + 'static,
+    ) -> BoxFuture<'a, Option<Update>>
+    where
+        T: Send + 'static,
+        E: Display,
+    {
+        Box::pin(async move {
+            match fetched.await {
+                Ok(value) => {
+                    Some(Box::new(move |repo: &mut RepoStats| apply(repo, value)) as Update)
+                }
+                Err(e) => {
+                    self.degraded(&format!("{unavailable}: {e}"));
+                    None
+                }
+            }
+        })
+    }
+}
+
+// Independent enrichments run concurrently; the client's request slots bound how many
+// requests are in flight across all repositories
+pub async fn enrich(
+    github: &Github,
+    org: &str,
+    repo: &mut RepoStats,
+    enrichments: &Enrichments,
+    diagnostics: &Diagnostics,
+) {
+    if !github.budget_left() {
+        degraded(
+            diagnostics,
+            org,
+            &repo.name,
+            "request budget exhausted, enrichments skipped",
+        );
+        return;
+    }
+    let target = Target {
+        github,
+        org,
+        name: &repo.name,
+        diagnostics,
+    };
+    let updates = join_all(tasks(target, repo, enrichments)).await;
+    for update in updates.into_iter().flatten() {
+        update(repo);
+    }
+}
+
+fn tasks<'a>(
+    target: Target<'a>,
+    repo: &'a RepoStats,
+    enrichments: &'a Enrichments,
+) -> Vec<BoxFuture<'a, Option<Update>>> {
+    let Target {
+        github, org, name, ..
+    } = target;
+    let mut tasks: Vec<BoxFuture<'a, Option<Update>>> = vec![];
+    if enrichments.prs && repo.open_prs.is_none() {
+        tasks.push(target.task(
+            "open PR count unavailable",
+            fetch::open_pr_count(github, org, name),
+            |repo, count| repo.open_prs = Some(count),
+        ));
+    }
+    if enrichments.traffic {
+        if repo.can_push {
+            tasks.push(target.task(
+                "traffic unavailable",
+                fetch::traffic(github, org, name),
+                |repo, traffic| repo.traffic = Some(traffic),
+            ));
+        } else {
+            target.degraded("traffic requires push access");
+        }
+    }
+    if enrichments.health && repo.releases_last_year.is_none() {
+        tasks.push(target.task(
+            "releases unavailable",
+            fetch::releases_last_year(github, org, name),
+            |repo, (count, truncated)| {
+                repo.releases_last_year = Some(count);
+                repo.releases_truncated = truncated;
+            },
+        ));
+    }
+    // Issue forms are checked on the community profile, so both go in one task
+    let forms = enrichments.templates
+        && repo
+            .community
+            .as_ref()
+            .is_none_or(|c| c.has_issue_forms.is_none());
+    if ((enrichments.health || enrichments.templates) && repo.community.is_none()) || forms {
+        tasks.push(Box::pin(async move {
+            let mut community = match &repo.community {
+                Some(community) => community.clone(),
+                None => match fetch::community(github, org, name).await {
+                    Ok(community) => community,
+                    Err(e) => {
+                        target.degraded(&format!("community profile unavailable: {e}"));
+                        return None;
+                    }
+                },
+            };
+            if enrichments.templates && community.has_issue_forms.is_none() {
+                match fetch::has_issue_forms(github, org, name).await {
+                    Ok(forms) => community.has_issue_forms = Some(forms),
+                    Err(e) => target.degraded(&format!("issue forms check failed: {e}")),
+                }
+            }
+            Some(Box::new(move |repo: &mut RepoStats| repo.community = Some(community)) as Update)
+        }));
+    }
+    if enrichments.activity {
+        tasks.push(target.task(
+            "repository activity unavailable",
+            fetch::push_activity(github, org, name),
+            |repo, pushes| repo.pushes = Some(pushes),
+        ));
+    }
+    if enrichments.devcontainer && repo.devcontainer.is_none() {
+        tasks.push(target.task(
+            "devcontainer check failed",
+            fetch::has_devcontainer(github, org, name),
+            |repo, devcontainer| repo.devcontainer = Some(devcontainer),
+        ));
+    }
+    // The listing carries every feature flag but discussions
+    let features = enrichments.features
+        && repo
+            .features
+            .as_ref()
+            .is_none_or(|f| enrichments.discussions && f.has_discussions.is_none());
+    if (enrichments.merge_settings && repo.merge.is_none()) || features {
+        let merge = enrichments.merge_settings;
+        tasks.push(target.task(
+            "repository settings unavailable",
+            fetch::settings(github, org, name),
+            move |repo, (settings, flags)| {
+                if merge && repo.merge.is_none() {
+                    repo.merge = Some(settings);
+                }
+                if features {
+                    repo.features = Some(flags);
+                }
+            },
+        ));
+    }
+    if enrichments.downloads {
+        tasks.push(target.task(
+            "release downloads unavailable",
+            fetch::downloads(github, org, name),
+            |repo, downloads| repo.downloads = Some(downloads),
+        ));
+    }
+    if enrichments.docs && repo.docs.is_none() {
+        tasks.push(target.task(
+            "README unavailable",
+            fetch::readme(github, org, name),
+            |repo, readme: Option<String>| {
+                repo.docs = Some(docs::check(readme.as_deref(), repo.description.as_deref()))
+            },
+        ));
+    }
+    if enrichments.commit_activity {
+        let fetched = async move {
+            match repo.pushed_at {
+                Some(_) => fetch::commit_days(github, org, name).await,
+                // Empty repositories have no statistics to compute
+                None => Ok(Default::default()),
+            }
+        };
+        tasks.push(
+            target.task("commit activity unavailable", fetched, |repo, days| {
+                repo.commit_days = Some(days)
+            }),
+        );
+    }
+    if enrichments.labels {
+        tasks.push(target.task(
+            "labels unavailable",
+            graphql::labels(github, org, name),
+            |repo, labels| repo.labels = Some(labels),
+        ));
+    }
+    if enrichments.actions_policy {
+        tasks.push(target.task(
+            "actions permissions unavailable",
+            fetch::actions_policy(github, org, name),
+            |repo, actions| repo.actions = Some(actions),
+        ));
+    }
+    if enrichments.deployments {
+        tasks.push(target.task(
+            "deployments unavailable",
+            fetch::deployments(github, org, name),
+            |repo, deployments| repo.deployments = Some(deployments),
+        ));
+    }
+    if enrichments.traceability {
+        tasks.push(target.task(
+            "release traceability unavailable",
+            fetch::traceability(github, org, name),
+            |repo, traceability| repo.traceability = traceability,
+        ));
+    }
+    if enrichments.security {
+        let fetched = fetch::security_alerts(github, org, name).inspect(move |result| {
+            if let Ok(security) = result {
+                if security.dependabot.is_none() && security.code_scanning.is_none() {
+                    target.degraded(
+                        "security alerts unavailable, token needs the security_events scope",
+                    );
+                }
+            }
+        });
+        tasks.push(
+            target.task("security alerts unavailable", fetched, |repo, security| {
+                repo.security = Some(security)
+            }),
+        );
+    }
+    if enrichments.ci {
+        if let Some(branch) = repo.default_branch.as_deref() {
+            tasks.push(target.task(
+                "workflow runs unavailable",
+                fetch::ci_status(github, org, name, branch),
+                |repo, ci| repo.ci = Some(ci),
+            ));
+        }
+    }
+    if enrichments.star_history {
+        tasks.push(target.task(
+            "star history unavailable",
+            graphql::star_history(github, org, name),
+            |repo, history| repo.star_history = Some(history),
+        ));
+    }
+    if enrichments.dependabot_prs {
+        tasks.push(target.task(
+            "Dependabot pull requests unavailable",
+            fetch::dependency_updates(github, org, name),
+            |repo, updates| repo.dependency_updates = Some(updates),
+        ));
+    }
+    if enrichments.advisories {
+        tasks.push(target.task(
+            "security advisories unavailable",
+            fetch::advisories(github, org, name),
+            |repo, (advisories, truncated)| {
+                repo.advisories = Some(advisories);
+                repo.advisories_truncated = truncated;
+            },
+        ));
+    }
+    if enrichments.branch_protection {
+        if let Some(branch) = repo.default_branch.as_deref() {
+            tasks.push(target.task(
+                "branch protection unavailable",
+                fetch::branch_protected(github, org, name, branch),
+                |repo, protected| repo.branch_protected = Some(protected),
+            ));
+        }
+    }
+    if enrichments.issue_age {
+        tasks.push(target.task(
+            "open issues unavailable",
+            fetch::issue_ages(github, org, name),
+            |repo, (ages, truncated)| {
+                repo.issue_ages = Some(ages);
+                repo.issue_ages_truncated = truncated;
+            },
+        ));
+    }
+    if enrichments.milestones {
+        tasks.push(target.task(
+            "milestones unavailable",
+            fetch::milestones(github, org, name),
+            |repo, milestones| repo.milestones = Some(milestones),
+        ));
+    }
+    if enrichments.codeowners && repo.codeowners.is_none() {
+        tasks.push(target.task(
+            "CODEOWNERS unavailable",
+            graphql::codeowners(github, org, name),
+            |repo, content: Option<String>| {
+                repo.codeowners = Some(
+                    content
+                        .as_deref()
+                        .map(codeowners::parse)
+                        .unwrap_or_default(),
+                )
+            },
+        ));
+    }
+    if enrichments.branch_count && repo.branch_count.is_none() {
+        tasks.push(target.task(
+            "branches unavailable",
+            fetch::branch_count(github, org, name),
+            |repo, count| repo.branch_count = Some(count),
+        ));
+    }
+    if enrichments.review_latency {
+        tasks.push(target.task(
+            "pull request reviews unavailable",
+            fetch::review_latency(github, org, name),
+            |repo, latency| repo.review_latency = Some(latency),
+        ));
+    }
+    if enrichments.dependencies && repo.dependencies.is_none() {
+        let fetched = graphql::dependencies(github, org, name).inspect(move |result| {
+            if result.as_ref().is_ok_and(|d| d.manifests == 0) {
+                target.degraded("no dependency graph manifests, the graph may be disabled");
+            }
+        });
+        tasks.push(target.task(
+            "dependency graph unavailable",
+            fetched,
+            |repo, dependencies| repo.dependencies = Some(dependencies),
+        ));
+    }
+    tasks
+}
+
+// Kept apart from enrich because the window depends on the organization's previous run
+pub async fn growth(
+    github: &Github,
+    org: &str,
+    repo: &mut RepoStats,
+    since: DateTime<Utc>,
+    diagnostics: &Diagnostics,
+) {
+    if !github.budget_left() {
+        return;
+    }
+    let stars = match graphql::stars_since(github, org, &repo.name, since).await {
+        Ok(stars) => stars,
+        Err(e) => {
+            return degraded(
+                diagnostics,
+                org,
+                &repo.name,
+                &format!("new stars unavailable: {e}"),
+            )
+        }
+    };
+    match fetch::forks_since(github, org, &repo.name, since).await {
+        Ok(forks) => {
+            repo.growth = Some(Growth {
+                since,
+                stars: stars.0,
+                forks: forks.0,
+                truncated: stars.1 || forks.1,
+            })
+        }
+        Err(e) => degraded(
+            diagnostics,
+            org,
+            &repo.name,
+            &format!("new forks unavailable: {e}"),
+        ),
+    }
+}
+
+pub async fn custom(
+    providers: &[Box<dyn MetricProvider>],
+    org: &str,
+    repo: &mut RepoStats,
+    diagnostics: &Diagnostics,
+) {
+    for provider in providers {
+        match provider.enrich(org, repo).await {
+            Ok(Some(value)) => {
+                repo.custom.insert(provider.name().to_string(), value);
+            }
+            Ok(None) => {}
+            Err(e) => degraded(
+                diagnostics,
+                org,
+                &repo.name,
+                &format!("{} unavailable: {e}", provider.name()),
+            ),
+        }
+    }
+}
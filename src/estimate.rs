@@ -0,0 +1,143 @@
+use std::io::IsTerminal;
+
+use comfy_table::{Cell, Color, Table};
+use serde::Deserialize;
+
+use crate::cli::Provider;
+use crate::render;
+use crate::scan::Context;
+use crate::visibility::Visibility;
+
+// Repositories per listing page, REST and GraphQL alike
+const PAGE_SIZE: u32 = 100;
+
+#[derive(Deserialize)]
+struct OrgCounts {
+    public_repos: u32,
+    // Only present for members of the organization
+    total_private_repos: Option<u32>,
+}
+
+struct OrgEstimate {
+    org: String,
+    repos: u32,
+    listing: u32,
+    enrichments: u32,
+}
+
+impl OrgEstimate {
+    fn requests(&self) -> u32 {
+        self.listing + self.enrichments
+    }
+}
+
+async fn org_estimate(ctx: &Context, org: &str) -> Result<OrgEstimate, String> {
+    let per_repo = ctx.enrichments.count();
+    if let Some(repos) = ctx.args.virtual_orgs.get(org) {
+        let repos = repos.len() as u32;
+        return Ok(OrgEstimate {
+            org: org.to_string(),
+            repos,
+            listing: repos,
+            enrichments: repos * per_repo,
+        });
+    }
+    let counts: OrgCounts = ctx
+        .github
+        .get(format!("orgs/{org}"), None::<&()>)
+        .await
+        .map_err(|e| format!("Cannot estimate {org}: {e}"))?;
+    let private = counts.total_private_repos.unwrap_or(0);
+    let repos = match ctx.args.visibility {
+        Visibility::Public => counts.public_repos,
+        Visibility::Private | Visibility::Internal => private,
+        Visibility::All => counts.public_repos + private,
+    };
+    // The organization lookup, the listing pages and the optional profile
+    let listing = 1 + repos.div_ceil(PAGE_SIZE).max(1) + ctx.args.org_profile as u32;
+    Ok(OrgEstimate {
+        org: org.to_string(),
+        repos,
+        listing,
+        enrichments: repos * per_repo,
+    })
+}
+
+fn table(estimates: &[OrgEstimate], total: u32, remaining: Option<u32>) -> Table {
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Organization").fg(Color::Green),
+        Cell::new("Repos").fg(Color::Green),
+        Cell::new("Listing").fg(Color::Green),
+        Cell::new("Enrichments").fg(Color::Green),
+        Cell::new("Requests").fg(Color::Green),
+    ]);
+    for estimate in estimates {
+        table.add_row(vec![
+            estimate.org.clone(),
+            estimate.repos.to_string(),
+            estimate.listing.to_string(),
+            estimate.enrichments.to_string(),
+            estimate.requests().to_string(),
+        ]);
+    }
+    table.add_row(vec![
+        Cell::new("Total"),
+        Cell::new(""),
+        Cell::new(""),
+        Cell::new(""),
+        Cell::new(total),
+    ]);
+    table.add_row(vec![
+        Cell::new("Rate limit remaining"),
+        Cell::new(""),
+        Cell::new(""),
+        Cell::new(""),
+        Cell::new(
+            remaining
+                .map(|r| r.to_string())
+                .unwrap_or("unknown".to_string()),
+        ),
+    ]);
+    table
+}
+
+fn confirm(question: &str) -> Result<bool, String> {
+    if !std::io::stdin().is_terminal() {
+        return Err(format!("{question} Rerun in a terminal to confirm"));
+    }
+    eprint!("{question} Continue? [y/N] ");
+    let mut answer = String::new();
+    std::io::stdin()
+        .read_line(&mut answer)
+        .map_err(|e| format!("Cannot read the answer: {e}"))?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+// Counts come from the organization's repository totals, so cached or filtered repositories
+// make the estimate an upper bound
+pub async fn check(ctx: &Context, orgs: &[String], threshold: u32) -> Result<(), String> {
+    if ctx.args.provider == Provider::Gitlab {
+        return Err("--estimate supports GitHub organizations only".to_string());
+    }
+    let mut estimates = vec![];
+    for org in orgs {
+        estimates.push(org_estimate(ctx, org).await?);
+    }
+    let total: u32 = estimates.iter().map(OrgEstimate::requests).sum();
+    let remaining = ctx.github.remaining().await;
+    eprintln!("{}", table(&estimates, total, remaining));
+    let Some(remaining) = remaining else {
+        return Ok(());
+    };
+    if total as u64 * 100 <= remaining as u64 * threshold as u64 {
+        return Ok(());
+    }
+    let question = format!(
+        "The scan needs about {total} requests, more than {threshold}% of the {remaining} left."
+    );
+    match confirm(&question)? {
+        true => Ok(()),
+        false => Err("Scan cancelled".to_string()),
+    }
+}
@@ -0,0 +1,81 @@
+use comfy_table::{Cell, Color, Table};
+use serde::Serialize;
+
+use crate::render;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reason {
+    NotFound,
+    Forbidden,
+    RateLimited,
+    Other,
+}
+
+impl Reason {
+    // Scans report errors as text, so GitHub's messages are matched the way client.rs does
+    pub fn classify(message: &str) -> Self {
+        let message = message.to_lowercase();
+        if message.contains("rate limit") {
+            Reason::RateLimited
+        } else if message.contains("not found") || message.contains("not in the dump file") {
+            Reason::NotFound
+        } else if message.contains("forbidden")
+            || message.contains("must have admin rights")
+            || message.contains("resource not accessible")
+            || message.contains("sso")
+        {
+            Reason::Forbidden
+        } else {
+            Reason::Other
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Reason::NotFound => "not found",
+            Reason::Forbidden => "forbidden",
+            Reason::RateLimited => "rate limited",
+            Reason::Other => "error",
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Failure {
+    pub org: String,
+    pub reason: Reason,
+    pub message: String,
+}
+
+impl Failure {
+    pub fn new(org: &str, message: String) -> Self {
+        Self {
+            org: org.to_string(),
+            reason: Reason::classify(&message),
+            message,
+        }
+    }
+
+    // The error column of exports
+    pub fn describe(&self) -> String {
+        format!("{}: {}", self.reason.label(), self.message)
+    }
+}
+
+pub fn table(failures: &[Failure]) -> Table {
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Failed organization").fg(Color::Red),
+        Cell::new("Reason").fg(Color::Red),
+        Cell::new("Error").fg(Color::Red),
+    ]);
+    for failure in failures {
+        table.add_row(vec![
+            Cell::new(&failure.org),
+            Cell::new(failure.reason.label()),
+            Cell::new(&failure.message),
+        ]);
+    }
+    table
+}
@@ -0,0 +1,1149 @@
+use std::collections::{BTreeMap, HashMap};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use octocrab::models::Repository;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::client::Github;
+use crate::inventory::InventoryRepo;
+use crate::stats::{
+    ActionsPolicy, Advisory, AuditEvent, Community, DependencyUpdates, Deployments, Downloads,
+    Features, Members, MergeSettings, Milestone, OrgProfile, PushActivity, ReviewLatency,
+    SecurityAlerts, Traceability, Traffic,
+};
+use crate::visibility::Visibility;
+
+const PAGE_SIZE: usize = 100;
+const REVIEW_SAMPLE: &str = "20";
+// Pages of open issues read for their ages, newest first
+const ISSUE_PAGES: u32 = 10;
+
+#[derive(Deserialize)]
+struct TrafficCount {
+    count: u32,
+    uniques: u32,
+}
+
+pub async fn open_pr_count(github: &Github, org: &str, repo: &str) -> octocrab::Result<u32> {
+    let query = format!("repo:{org}/{repo} is:pr is:open");
+    let query = query.as_str();
+    let page = github
+        .retry(|| async move {
+            github
+                .octocrab()
+                .search()
+                .issues_and_pull_requests(query)
+                .per_page(1)
+                .send()
+                .await
+        })
+        .await?;
+    Ok(page.total_count.unwrap_or(0) as u32)
+}
+
+pub async fn traffic(github: &Github, org: &str, repo: &str) -> octocrab::Result<Traffic> {
+    let views: TrafficCount = github
+        .get(format!("repos/{org}/{repo}/traffic/views"), None::<&()>)
+        .await?;
+    let clones: TrafficCount = github
+        .get(format!("repos/{org}/{repo}/traffic/clones"), None::<&()>)
+        .await?;
+    Ok(Traffic {
+        views: views.count,
+        unique_views: views.uniques,
+        clones: clones.count,
+        unique_clones: clones.uniques,
+    })
+}
+
+#[derive(Deserialize)]
+struct Release {
+    published_at: Option<DateTime<Utc>>,
+}
+
+// Only the newest page of releases is listed, so a full page of new ones is a lower bound
+pub async fn releases_since(
+    github: &Github,
+    org: &str,
+    repo: &str,
+    since: DateTime<Utc>,
+) -> octocrab::Result<(u32, bool)> {
+    let releases: Vec<Release> = github
+        .get(
+            format!("repos/{org}/{repo}/releases"),
+            Some(&[("per_page", "100")]),
+        )
+        .await?;
+    let count = releases
+        .iter()
+        .filter(|r| r.published_at.map(|at| at >= since).unwrap_or(false))
+        .count();
+    Ok((count as u32, count == PAGE_SIZE))
+}
+
+#[derive(Deserialize)]
+struct Fork {
+    created_at: DateTime<Utc>,
+}
+
+// Only the newest page of forks is listed, so a full page of new ones is a lower bound
+pub async fn forks_since(
+    github: &Github,
+    org: &str,
+    repo: &str,
+    since: DateTime<Utc>,
+) -> octocrab::Result<(u32, bool)> {
+    let forks: Vec<Fork> = github
+        .get(
+            format!("repos/{org}/{repo}/forks"),
+            Some(&[("sort", "newest"), ("per_page", "100")]),
+        )
+        .await?;
+    let count = forks.iter().filter(|f| f.created_at >= since).count();
+    Ok((count as u32, count == PAGE_SIZE))
+}
+
+#[derive(Deserialize)]
+struct ReleaseAssets {
+    assets: Vec<Asset>,
+}
+
+#[derive(Deserialize)]
+struct Asset {
+    download_count: u64,
+}
+
+// Releases come newest first
+pub async fn downloads(github: &Github, org: &str, repo: &str) -> octocrab::Result<Downloads> {
+    let releases: Vec<ReleaseAssets> = github
+        .get(
+            format!("repos/{org}/{repo}/releases"),
+            Some(&[("per_page", "100")]),
+        )
+        .await?;
+    let sum = |release: &ReleaseAssets| release.assets.iter().map(|a| a.download_count).sum();
+    Ok(Downloads {
+        releases: releases.len() as u32,
+        total: releases.iter().map(sum).sum(),
+        latest: releases.first().map(sum),
+        truncated: releases.len() == PAGE_SIZE,
+    })
+}
+
+#[derive(Deserialize)]
+struct CommitWeek {
+    // Unix time of the Sunday the week starts on
+    week: i64,
+    days: Vec<u32>,
+}
+
+// Commits per day over the last 52 weeks, leaving out days without any
+pub async fn commit_days(
+    github: &Github,
+    org: &str,
+    repo: &str,
+) -> Result<BTreeMap<NaiveDate, u32>, String> {
+    let response: Value = github
+        .get(
+            format!("repos/{org}/{repo}/stats/commit_activity"),
+            None::<&()>,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    // GitHub answers 202 with an empty object while it computes the statistics
+    if !response.is_array() {
+        return Err("GitHub is still computing the statistics, try again shortly".to_string());
+    }
+    let weeks: Vec<CommitWeek> = serde_json::from_value(response).map_err(|e| e.to_string())?;
+    let mut days = BTreeMap::new();
+    for week in weeks {
+        let start = Utc
+            .timestamp_opt(week.week, 0)
+            .single()
+            .ok_or(format!("invalid week {}", week.week))?
+            .date_naive();
+        for (offset, count) in week.days.into_iter().enumerate() {
+            if count > 0 {
+                days.insert(start + Duration::days(offset as i64), count);
+            }
+        }
+    }
+    Ok(days)
+}
+
+pub async fn releases_last_year(
+    github: &Github,
+    org: &str,
+    repo: &str,
+) -> octocrab::Result<(u32, bool)> {
+    releases_since(github, org, repo, Utc::now() - Duration::days(365)).await
+}
+
+#[derive(Deserialize)]
+struct CommunityProfile {
+    health_percentage: u32,
+    files: CommunityFiles,
+}
+
+#[derive(Deserialize)]
+struct CommunityFiles {
+    readme: Option<Value>,
+    contributing: Option<Value>,
+    issue_template: Option<Value>,
+    pull_request_template: Option<Value>,
+}
+
+pub async fn community(github: &Github, org: &str, repo: &str) -> octocrab::Result<Community> {
+    let profile: CommunityProfile = github
+        .get(format!("repos/{org}/{repo}/community/profile"), None::<&()>)
+        .await?;
+    Ok(Community {
+        health_percentage: profile.health_percentage,
+        has_readme: profile.files.readme.is_some(),
+        has_contributing: profile.files.contributing.is_some(),
+        has_issue_template: profile.files.issue_template.is_some(),
+        has_pr_template: profile.files.pull_request_template.is_some(),
+        has_issue_forms: None,
+    })
+}
+
+pub async fn org_profile(github: &Github, org: &str) -> octocrab::Result<OrgProfile> {
+    github.get(format!("orgs/{org}"), None::<&()>).await
+}
+
+#[derive(Deserialize)]
+struct ContentEntry {
+    name: String,
+}
+
+pub async fn has_issue_forms(github: &Github, org: &str, repo: &str) -> octocrab::Result<bool> {
+    let entries: Vec<ContentEntry> = match github
+        .get(
+            format!("repos/{org}/{repo}/contents/.github/ISSUE_TEMPLATE"),
+            None::<&()>,
+        )
+        .await
+    {
+        Ok(entries) => entries,
+        Err(e) if is_not_found(&e) => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    Ok(entries.iter().any(|entry| {
+        let name = entry.name.to_lowercase();
+        (name.ends_with(".yml") || name.ends_with(".yaml"))
+            && name != "config.yml"
+            && name != "config.yaml"
+    }))
+}
+
+#[derive(Deserialize)]
+struct Activity {
+    actor: Option<Actor>,
+}
+
+#[derive(Deserialize)]
+struct Actor {
+    login: String,
+}
+
+pub async fn push_activity(
+    github: &Github,
+    org: &str,
+    repo: &str,
+) -> octocrab::Result<PushActivity> {
+    let activities: Vec<Activity> = github
+        .get(
+            format!("repos/{org}/{repo}/activity"),
+            Some(&[
+                ("time_period", "quarter"),
+                ("activity_type", "push"),
+                ("per_page", "100"),
+            ]),
+        )
+        .await?;
+    let mut by_actor: Vec<(String, u32)> = vec![];
+    for activity in &activities {
+        let login = activity
+            .actor
+            .as_ref()
+            .map(|a| a.login.clone())
+            .unwrap_or("ghost".to_string());
+        match by_actor.iter_mut().find(|(actor, _)| *actor == login) {
+            Some((_, count)) => *count += 1,
+            None => by_actor.push((login, 1)),
+        }
+    }
+    by_actor.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(PushActivity {
+        total: activities.len() as u32,
+        by_actor,
+        truncated: activities.len() == PAGE_SIZE,
+    })
+}
+
+pub fn is_not_found(error: &octocrab::Error) -> bool {
+    matches!(error, octocrab::Error::GitHub { source, .. } if source.message == "Not Found")
+}
+
+pub async fn path_exists(
+    github: &Github,
+    org: &str,
+    repo: &str,
+    path: &str,
+) -> octocrab::Result<bool> {
+    match github
+        .get::<Value, _, ()>(format!("repos/{org}/{repo}/contents/{path}"), None)
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(e) if is_not_found(&e) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize)]
+struct LicenseContent {
+    path: String,
+    content: String,
+    license: Option<LicenseKind>,
+}
+
+#[derive(Deserialize)]
+struct LicenseKind {
+    spdx_id: Option<String>,
+}
+
+pub struct LicenseFile {
+    pub path: String,
+    pub text: String,
+    pub spdx_id: Option<String>,
+}
+
+pub async fn license_file(
+    github: &Github,
+    org: &str,
+    repo: &str,
+) -> Result<Option<LicenseFile>, String> {
+    let response: LicenseContent = match github
+        .get(format!("repos/{org}/{repo}/license"), None::<&()>)
+        .await
+    {
+        Ok(response) => response,
+        Err(e) if is_not_found(&e) => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    // The API wraps the base64 content at 60 characters
+    let encoded: String = response.content.split_whitespace().collect();
+    let text = STANDARD.decode(encoded).map_err(|e| e.to_string())?;
+    Ok(Some(LicenseFile {
+        path: response.path,
+        text: String::from_utf8_lossy(&text).into_owned(),
+        spdx_id: response.license.and_then(|l| l.spdx_id),
+    }))
+}
+
+#[derive(Deserialize)]
+struct ReadmeContent {
+    content: String,
+}
+
+pub async fn readme(github: &Github, org: &str, repo: &str) -> Result<Option<String>, String> {
+    let response: ReadmeContent = match github
+        .get(format!("repos/{org}/{repo}/readme"), None::<&()>)
+        .await
+    {
+        Ok(response) => response,
+        Err(e) if is_not_found(&e) => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    let encoded: String = response.content.split_whitespace().collect();
+    let text = STANDARD.decode(encoded).map_err(|e| e.to_string())?;
+    Ok(Some(String::from_utf8_lossy(&text).into_owned()))
+}
+
+pub async fn has_devcontainer(github: &Github, org: &str, repo: &str) -> octocrab::Result<bool> {
+    Ok(path_exists(github, org, repo, ".devcontainer").await?
+        || path_exists(github, org, repo, ".devcontainer.json").await?)
+}
+
+#[derive(Deserialize)]
+struct Settings {
+    #[serde(flatten)]
+    merge: MergeSettings,
+    #[serde(flatten)]
+    features: Features,
+}
+
+// Merge settings and feature flags are both read from the single repository response
+pub async fn settings(
+    github: &Github,
+    org: &str,
+    repo: &str,
+) -> octocrab::Result<(MergeSettings, Features)> {
+    let settings: Settings = github
+        .get(format!("repos/{org}/{repo}"), None::<&()>)
+        .await?;
+    Ok((settings.merge, settings.features))
+}
+
+#[derive(Deserialize)]
+struct ActionsPermissions {
+    enabled: bool,
+    allowed_actions: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct WorkflowPermissions {
+    default_workflow_permissions: String,
+}
+
+pub async fn actions_policy(
+    github: &Github,
+    org: &str,
+    repo: &str,
+) -> octocrab::Result<ActionsPolicy> {
+    let permissions: ActionsPermissions = github
+        .get(
+            format!("repos/{org}/{repo}/actions/permissions"),
+            None::<&()>,
+        )
+        .await?;
+    let workflow: WorkflowPermissions = github
+        .get(
+            format!("repos/{org}/{repo}/actions/permissions/workflow"),
+            None::<&()>,
+        )
+        .await?;
+    Ok(ActionsPolicy {
+        enabled: permissions.enabled,
+        allowed_actions: permissions.allowed_actions,
+        default_workflow_permissions: workflow.default_workflow_permissions,
+    })
+}
+
+#[derive(Deserialize)]
+struct Environments {
+    environments: Vec<Environment>,
+}
+
+#[derive(Deserialize)]
+struct Environment {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct Deployment {
+    id: u64,
+    sha: String,
+    #[serde(rename = "ref")]
+    git_ref: String,
+    created_at: DateTime<Utc>,
+}
+
+pub async fn deployments(github: &Github, org: &str, repo: &str) -> octocrab::Result<Deployments> {
+    let environments: Environments = github
+        .get(format!("repos/{org}/{repo}/environments"), None::<&()>)
+        .await?;
+    let deployments: Vec<Deployment> = github
+        .get(
+            format!("repos/{org}/{repo}/deployments"),
+            Some(&[("per_page", "100")]),
+        )
+        .await?;
+    let since = Utc::now() - Duration::days(30);
+    Ok(Deployments {
+        environments: environments
+            .environments
+            .into_iter()
+            .map(|e| e.name)
+            .collect(),
+        last_30d: deployments.iter().filter(|d| d.created_at >= since).count() as u32,
+        truncated: deployments.len() == PAGE_SIZE,
+    })
+}
+
+#[derive(Deserialize)]
+struct TaggedRelease {
+    tag_name: String,
+    draft: bool,
+    published_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct Tag {
+    name: String,
+    commit: TagCommit,
+}
+
+#[derive(Deserialize)]
+struct TagCommit {
+    sha: String,
+}
+
+#[derive(Deserialize)]
+struct DeploymentStatus {
+    state: String,
+}
+
+pub async fn traceability(
+    github: &Github,
+    org: &str,
+    repo: &str,
+) -> octocrab::Result<Option<Traceability>> {
+    let since = Utc::now() - Duration::days(90);
+    let releases: Vec<TaggedRelease> = github
+        .get(
+            format!("repos/{org}/{repo}/releases"),
+            Some(&[("per_page", "100")]),
+        )
+        .await?;
+    let releases: Vec<TaggedRelease> = releases
+        .into_iter()
+        .filter(|r| !r.draft && r.published_at.map(|at| at >= since).unwrap_or(false))
+        .collect();
+    let deployments: Vec<Deployment> = github
+        .get(
+            format!("repos/{org}/{repo}/deployments"),
+            Some(&[("per_page", "30")]),
+        )
+        .await?;
+    if releases.is_empty() || deployments.is_empty() {
+        return Ok(None);
+    }
+    let tags: Vec<Tag> = github
+        .get(
+            format!("repos/{org}/{repo}/tags"),
+            Some(&[("per_page", "100")]),
+        )
+        .await?;
+    let tag_shas: HashMap<String, String> = tags
+        .into_iter()
+        .map(|tag| (tag.name, tag.commit.sha))
+        .collect();
+
+    let mut deployed = vec![];
+    for deployment in deployments.iter().filter(|d| d.created_at >= since) {
+        let statuses: Vec<DeploymentStatus> = github
+            .get(
+                format!("repos/{org}/{repo}/deployments/{}/statuses", deployment.id),
+                Some(&[("per_page", "1")]),
+            )
+            .await?;
+        if statuses
+            .first()
+            .map(|s| s.state == "success")
+            .unwrap_or(false)
+        {
+            deployed.push(deployment);
+        }
+    }
+
+    let undeployed = releases
+        .iter()
+        .filter(|release| {
+            let sha = tag_shas.get(&release.tag_name);
+            !deployed
+                .iter()
+                .any(|d| d.git_ref == release.tag_name || Some(&d.sha) == sha)
+        })
+        .map(|release| release.tag_name.clone())
+        .collect();
+    let mut unreleased: Vec<String> = vec![];
+    for deployment in &deployed {
+        let released = tag_shas.contains_key(&deployment.git_ref)
+            || tag_shas.values().any(|sha| *sha == deployment.sha);
+        let label = format!(
+            "{}@{}",
+            deployment.git_ref,
+            &deployment.sha[..deployment.sha.len().min(7)]
+        );
+        if !released && !unreleased.contains(&label) {
+            unreleased.push(label);
+        }
+    }
+    Ok(Some(Traceability {
+        releases: releases.len() as u32,
+        undeployed,
+        unreleased,
+    }))
+}
+
+// Disabled features and missing scopes come back as 403/404 with these messages;
+// anything else (bad credentials, server errors, rate limits) is a real failure
+fn is_not_enabled(error: &octocrab::Error) -> bool {
+    const MESSAGES: [&str; 5] = [
+        "disabled",
+        "not enabled",
+        "must be enabled",
+        "no analysis found",
+        "resource not accessible",
+    ];
+    match error {
+        octocrab::Error::GitHub { source, .. } => {
+            let message = source.message.to_lowercase();
+            is_not_found(error) || MESSAGES.iter().any(|m| message.contains(m))
+        }
+        _ => false,
+    }
+}
+
+// The count, and whether the single page of alerts was full
+async fn open_alert_count(github: &Github, route: String) -> octocrab::Result<Option<(u32, bool)>> {
+    match github
+        .get::<Vec<Value>, _, _>(route, Some(&[("state", "open"), ("per_page", "100")]))
+        .await
+    {
+        Ok(alerts) => Ok(Some((alerts.len() as u32, alerts.len() == PAGE_SIZE))),
+        Err(e) if is_not_enabled(&e) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(Deserialize)]
+struct PrivateReporting {
+    enabled: bool,
+}
+
+pub async fn security_alerts(
+    github: &Github,
+    org: &str,
+    repo: &str,
+) -> octocrab::Result<SecurityAlerts> {
+    let dependabot =
+        open_alert_count(github, format!("repos/{org}/{repo}/dependabot/alerts")).await?;
+    let code_scanning =
+        open_alert_count(github, format!("repos/{org}/{repo}/code-scanning/alerts")).await?;
+    Ok(SecurityAlerts {
+        dependabot: dependabot.map(|(count, _)| count),
+        code_scanning: code_scanning.map(|(count, _)| count),
+        private_reporting: match github
+            .get::<PrivateReporting, _, _>(
+                format!("repos/{org}/{repo}/private-vulnerability-reporting"),
+                None::<&()>,
+            )
+            .await
+        {
+            Ok(reporting) => Some(reporting.enabled),
+            Err(e) if is_not_enabled(&e) => None,
+            Err(e) => return Err(e),
+        },
+        truncated: [dependabot, code_scanning]
+            .iter()
+            .flatten()
+            .any(|(_, truncated)| *truncated),
+    })
+}
+
+#[derive(Deserialize)]
+struct Branch {
+    protected: bool,
+}
+
+pub async fn branch_protected(
+    github: &Github,
+    org: &str,
+    repo: &str,
+    branch: &str,
+) -> octocrab::Result<bool> {
+    let branch: Branch = github
+        .get(format!("repos/{org}/{repo}/branches/{branch}"), None::<&()>)
+        .await?;
+    Ok(branch.protected)
+}
+
+#[derive(Deserialize)]
+struct WorkflowRuns {
+    workflow_runs: Vec<WorkflowRun>,
+}
+
+#[derive(Deserialize)]
+struct WorkflowRun {
+    status: Option<String>,
+    conclusion: Option<String>,
+}
+
+pub async fn ci_status(
+    github: &Github,
+    org: &str,
+    repo: &str,
+    branch: &str,
+) -> octocrab::Result<String> {
+    let runs: WorkflowRuns = github
+        .get(
+            format!("repos/{org}/{repo}/actions/runs"),
+            Some(&[
+                ("branch", branch),
+                ("exclude_pull_requests", "true"),
+                ("per_page", "1"),
+            ]),
+        )
+        .await?;
+    Ok(match runs.workflow_runs.into_iter().next() {
+        Some(run) => run
+            .conclusion
+            .or(run.status)
+            .unwrap_or("unknown".to_string()),
+        None => "none".to_string(),
+    })
+}
+
+#[derive(Deserialize)]
+struct PullRequestUser {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct DependencyPullRequest {
+    user: PullRequestUser,
+    created_at: DateTime<Utc>,
+    closed_at: Option<DateTime<Utc>>,
+    merged_at: Option<DateTime<Utc>>,
+}
+
+pub async fn dependency_updates(
+    github: &Github,
+    org: &str,
+    repo: &str,
+) -> octocrab::Result<DependencyUpdates> {
+    let since = Utc::now() - Duration::days(30);
+    let pulls: Vec<DependencyPullRequest> = github
+        .get(
+            format!("repos/{org}/{repo}/pulls"),
+            Some(&[
+                ("state", "all"),
+                ("sort", "created"),
+                ("direction", "desc"),
+                ("per_page", "100"),
+            ]),
+        )
+        .await?;
+    let truncated = pulls.len() == PAGE_SIZE;
+    let pulls: Vec<&DependencyPullRequest> = pulls
+        .iter()
+        .filter(|pr| pr.user.login == "dependabot[bot]" && pr.created_at >= since)
+        .collect();
+    Ok(DependencyUpdates {
+        opened: pulls.len() as u32,
+        merged: pulls.iter().filter(|pr| pr.merged_at.is_some()).count() as u32,
+        closed_unmerged: pulls
+            .iter()
+            .filter(|pr| pr.closed_at.is_some() && pr.merged_at.is_none())
+            .count() as u32,
+        truncated,
+    })
+}
+
+#[derive(Deserialize)]
+struct RepositoryAdvisory {
+    ghsa_id: String,
+    severity: Option<String>,
+    published_at: Option<DateTime<Utc>>,
+    summary: String,
+}
+
+// The advisories, and whether the single page of them was full
+pub async fn advisories(
+    github: &Github,
+    org: &str,
+    repo: &str,
+) -> octocrab::Result<(Vec<Advisory>, bool)> {
+    let advisories: Vec<RepositoryAdvisory> = github
+        .get(
+            format!("repos/{org}/{repo}/security-advisories"),
+            Some(&[("state", "published"), ("per_page", "100")]),
+        )
+        .await?;
+    let truncated = advisories.len() == PAGE_SIZE;
+    let advisories = advisories
+        .into_iter()
+        .filter_map(|a| {
+            Some(Advisory {
+                ghsa_id: a.ghsa_id,
+                severity: a.severity.unwrap_or("unknown".to_string()),
+                published_at: a.published_at?,
+                summary: a.summary,
+            })
+        })
+        .collect();
+    Ok((advisories, truncated))
+}
+
+pub const AUDIT_ACTIONS: [&str; 3] = ["repo.destroy", "repo.access", "org.remove_member"];
+
+#[derive(Deserialize)]
+struct RawAuditEvent {
+    #[serde(rename = "@timestamp")]
+    timestamp: i64,
+    action: String,
+    actor: Option<String>,
+    repo: Option<String>,
+    user: Option<String>,
+}
+
+pub async fn audit_events(
+    github: &Github,
+    org: &str,
+    since: DateTime<Utc>,
+) -> octocrab::Result<Vec<AuditEvent>> {
+    let mut events = vec![];
+    for action in AUDIT_ACTIONS {
+        let phrase = format!("action:{action} created:>={}", since.format("%Y-%m-%d"));
+        let raw: Vec<RawAuditEvent> = github
+            .get(
+                format!("orgs/{org}/audit-log"),
+                Some(&[("phrase", phrase.as_str()), ("per_page", "100")]),
+            )
+            .await?;
+        events.extend(raw.into_iter().filter_map(|e| {
+            Some(AuditEvent {
+                action: e.action,
+                actor: e.actor,
+                subject: e.repo.or(e.user),
+                at: Utc.timestamp_millis_opt(e.timestamp).single()?,
+            })
+        }));
+    }
+    events.sort_by_key(|e| e.at);
+    Ok(events)
+}
+
+pub async fn milestones(
+    github: &Github,
+    org: &str,
+    repo: &str,
+) -> octocrab::Result<Vec<Milestone>> {
+    github
+        .get(
+            format!("repos/{org}/{repo}/milestones"),
+            Some(&[("state", "open"), ("sort", "due_on"), ("per_page", "100")]),
+        )
+        .await
+}
+
+#[derive(Deserialize)]
+struct OpenIssue {
+    created_at: DateTime<Utc>,
+    pull_request: Option<Value>,
+}
+
+// The ages stop at ISSUE_PAGES pages, which leaves out the oldest issues of a large backlog
+pub async fn issue_ages(
+    github: &Github,
+    org: &str,
+    repo: &str,
+) -> octocrab::Result<(Vec<u32>, bool)> {
+    let now = Utc::now();
+    let mut ages = vec![];
+    let mut page = 1;
+    loop {
+        let page_param = page.to_string();
+        let issues: Vec<OpenIssue> = github
+            .get(
+                format!("repos/{org}/{repo}/issues"),
+                Some(&[
+                    ("state", "open"),
+                    ("per_page", "100"),
+                    ("page", page_param.as_str()),
+                ]),
+            )
+            .await?;
+        ages.extend(
+            issues
+                .iter()
+                .filter(|issue| issue.pull_request.is_none())
+                .map(|issue| (now - issue.created_at).num_days().max(0) as u32),
+        );
+        if issues.len() < PAGE_SIZE {
+            return Ok((ages, false));
+        }
+        if page == ISSUE_PAGES {
+            return Ok((ages, true));
+        }
+        page += 1;
+    }
+}
+
+#[derive(Deserialize)]
+struct ClosedPull {
+    number: u64,
+    created_at: DateTime<Utc>,
+    merged_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct Review {
+    submitted_at: Option<DateTime<Utc>>,
+}
+
+fn hours(from: DateTime<Utc>, to: DateTime<Utc>) -> u32 {
+    (to - from).num_hours().max(0) as u32
+}
+
+// Samples the most recently updated closed pull requests and keeps the merged ones
+pub async fn review_latency(
+    github: &Github,
+    org: &str,
+    repo: &str,
+) -> octocrab::Result<ReviewLatency> {
+    let pulls: Vec<ClosedPull> = github
+        .get(
+            format!("repos/{org}/{repo}/pulls"),
+            Some(&[
+                ("state", "closed"),
+                ("sort", "updated"),
+                ("direction", "desc"),
+                ("per_page", REVIEW_SAMPLE),
+            ]),
+        )
+        .await?;
+    let mut latency = ReviewLatency::default();
+    for pull in pulls {
+        let Some(merged_at) = pull.merged_at else {
+            continue;
+        };
+        latency.merge_hours.push(hours(pull.created_at, merged_at));
+        let reviews: Vec<Review> = github
+            .get(
+                format!("repos/{org}/{repo}/pulls/{}/reviews", pull.number),
+                Some(&[("per_page", "100")]),
+            )
+            .await?;
+        if let Some(first) = reviews.iter().filter_map(|r| r.submitted_at).min() {
+            latency
+                .first_review_hours
+                .push(hours(pull.created_at, first));
+        }
+    }
+    Ok(latency)
+}
+
+// Every page of the organization's repositories, so sharding and budgets see the whole organization
+pub async fn org_repos(
+    github: &Github,
+    org: &str,
+    visibility: Visibility,
+) -> octocrab::Result<Vec<Repository>> {
+    let mut repos = vec![];
+    let mut page = 1;
+    loop {
+        let page_param = page.to_string();
+        let items: Vec<Repository> = github
+            .get(
+                format!("orgs/{org}/repos"),
+                Some(&[
+                    ("type", visibility.name()),
+                    ("per_page", "100"),
+                    ("page", page_param.as_str()),
+                ]),
+            )
+            .await?;
+        let last = items.len() < PAGE_SIZE;
+        repos.extend(items);
+        if last {
+            return Ok(repos);
+        }
+        page += 1;
+    }
+}
+
+pub async fn inventory(github: &Github, org: &str) -> octocrab::Result<Vec<InventoryRepo>> {
+    let mut repos = vec![];
+    let mut page = 1;
+    loop {
+        let page_param = page.to_string();
+        let items: Vec<InventoryRepo> = github
+            .get(
+                format!("orgs/{org}/repos"),
+                Some(&[
+                    ("type", "all"),
+                    ("per_page", "100"),
+                    ("page", page_param.as_str()),
+                ]),
+            )
+            .await?;
+        let last = items.len() < 100;
+        repos.extend(items);
+        if last {
+            return Ok(repos);
+        }
+        page += 1;
+    }
+}
+
+async fn count_all(github: &Github, route: String) -> octocrab::Result<u32> {
+    let mut count = 0;
+    let mut page = 1;
+    loop {
+        let page_param = page.to_string();
+        let items: Vec<Value> = github
+            .get(
+                &route,
+                Some(&[("per_page", "100"), ("page", page_param.as_str())]),
+            )
+            .await?;
+        count += items.len() as u32;
+        if items.len() < 100 {
+            return Ok(count);
+        }
+        page += 1;
+    }
+}
+
+pub async fn branch_count(github: &Github, org: &str, repo: &str) -> octocrab::Result<u32> {
+    count_all(github, format!("repos/{org}/{repo}/branches")).await
+}
+
+pub async fn members(github: &Github, org: &str) -> octocrab::Result<Members> {
+    let members = count_all(github, format!("orgs/{org}/members")).await?;
+    let public_members = count_all(github, format!("orgs/{org}/public_members")).await?;
+    let teams = match count_all(github, format!("orgs/{org}/teams")).await {
+        Ok(teams) => Some(teams),
+        // Listing teams needs organization membership or the read:org scope
+        Err(octocrab::Error::GitHub { .. }) => None,
+        Err(e) => return Err(e),
+    };
+    Ok(Members {
+        members,
+        public_members,
+        teams,
+    })
+}
+
+pub async fn topic_signals(
+    github: &Github,
+    org: &str,
+    repo: &str,
+) -> octocrab::Result<(Vec<String>, Vec<String>)> {
+    let languages: HashMap<String, u64> = github
+        .get(format!("repos/{org}/{repo}/languages"), None::<&()>)
+        .await?;
+    let mut languages: Vec<(String, u64)> = languages.into_iter().collect();
+    languages.sort_by(|a, b| b.1.cmp(&a.1));
+    let files: Vec<ContentEntry> = match github
+        .get(format!("repos/{org}/{repo}/contents/"), None::<&()>)
+        .await
+    {
+        Ok(files) => files,
+        // Empty repositories have no contents
+        Err(e) if is_not_found(&e) => vec![],
+        Err(e) => return Err(e),
+    };
+    Ok((
+        languages.into_iter().map(|(name, _)| name).collect(),
+        files.into_iter().map(|f| f.name).collect(),
+    ))
+}
+
+pub async fn set_topics(
+    github: &Github,
+    org: &str,
+    repo: &str,
+    topics: &[String],
+) -> octocrab::Result<()> {
+    let _: Value = github
+        .put(
+            format!("repos/{org}/{repo}/topics"),
+            Some(&serde_json::json!({ "names": topics })),
+        )
+        .await?;
+    Ok(())
+}
+
+pub async fn archive_repo(github: &Github, org: &str, repo: &str) -> octocrab::Result<()> {
+    let _: Value = github
+        .patch(
+            format!("repos/{org}/{repo}"),
+            Some(&serde_json::json!({ "archived": true })),
+        )
+        .await?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct IssueRef {
+    number: u64,
+    title: String,
+}
+
+#[derive(Deserialize)]
+struct IssueSearch {
+    items: Vec<IssueRef>,
+}
+
+// Returns the new issue number, or None when an open issue with the title already exists
+pub async fn open_issue_once(
+    github: &Github,
+    org: &str,
+    repo: &str,
+    title: &str,
+    body: &str,
+) -> octocrab::Result<Option<u64>> {
+    // Search by title, as the open issues (and pull requests) may span many pages
+    let query = format!("repo:{org}/{repo} is:issue is:open in:title \"{title}\"");
+    let found: IssueSearch = github
+        .get(
+            "search/issues",
+            Some(&[("q", query.as_str()), ("per_page", "100")]),
+        )
+        .await?;
+    // The search also matches titles that only contain the words
+    if found.items.iter().any(|issue| issue.title == title) {
+        return Ok(None);
+    }
+    let issue: IssueRef = github
+        .post(
+            format!("repos/{org}/{repo}/issues"),
+            Some(&serde_json::json!({ "title": title, "body": body })),
+        )
+        .await?;
+    Ok(Some(issue.number))
+}
+
+pub async fn update_repo(
+    github: &Github,
+    org: &str,
+    repo: &str,
+    fields: &serde_json::Map<String, Value>,
+) -> octocrab::Result<()> {
+    let _: Value = github
+        .patch(format!("repos/{org}/{repo}"), Some(fields))
+        .await?;
+    Ok(())
+}
+
+pub struct RepoDetail {
+    pub languages: Vec<(String, u64)>,
+    pub latest_release: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LatestRelease {
+    tag_name: String,
+    published_at: Option<DateTime<Utc>>,
+}
+
+pub async fn repo_detail(github: &Github, org: &str, repo: &str) -> octocrab::Result<RepoDetail> {
+    let languages: HashMap<String, u64> = github
+        .get(format!("repos/{org}/{repo}/languages"), None::<&()>)
+        .await?;
+    let mut languages: Vec<(String, u64)> = languages.into_iter().collect();
+    languages.sort_by(|a, b| b.1.cmp(&a.1));
+    let latest_release = match github
+        .get::<LatestRelease, _, ()>(format!("repos/{org}/{repo}/releases/latest"), None)
+        .await
+    {
+        Ok(release) => Some(match release.published_at {
+            Some(at) => format!("{} ({})", release.tag_name, at.format("%Y-%m-%d")),
+            None => release.tag_name,
+        }),
+        Err(e) if is_not_found(&e) => None,
+        Err(e) => return Err(e),
+    };
+    Ok(RepoDetail {
+        languages,
+        latest_release,
+    })
+}
@@ -0,0 +1,251 @@
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+
+use crate::alerts::Comparator;
+use crate::columns::Column;
+use crate::format::{Style, Units};
+use crate::stats::RepoStats;
+use crate::watch;
+
+enum Field {
+    Name,
+    Column(Column),
+}
+
+enum Op {
+    Eq,
+    Ne,
+    Compare(Comparator),
+}
+
+enum Value {
+    Number(f64),
+    // Like highlight rules, pushed_at<90d means older than 90 days
+    Age(chrono::Duration),
+    Text(String),
+}
+
+struct Condition {
+    field: Field,
+    op: Op,
+    value: Value,
+}
+
+pub struct Filter {
+    conditions: Vec<Condition>,
+    style: Style,
+}
+
+const OPERATORS: [(&str, Op); 6] = [
+    ("==", Op::Eq),
+    ("!=", Op::Ne),
+    ("<=", Op::Compare(Comparator::Le)),
+    (">=", Op::Compare(Comparator::Ge)),
+    ("<", Op::Compare(Comparator::Lt)),
+    (">", Op::Compare(Comparator::Gt)),
+];
+
+fn parse_value(value: &str) -> Result<Value, String> {
+    let quoted = ['"', '\'']
+        .into_iter()
+        .find(|q| value.len() >= 2 && value.starts_with(*q) && value.ends_with(*q));
+    if let Some(quote) = quoted {
+        return Ok(Value::Text(value.trim_matches(quote).to_string()));
+    }
+    if let Ok(number) = value.parse::<f64>() {
+        return Ok(Value::Number(number));
+    }
+    Ok(match watch::parse_interval(value) {
+        Ok(age) => Value::Age(chrono::Duration::from_std(age).map_err(|e| e.to_string())?),
+        Err(_) => Value::Text(value.to_string()),
+    })
+}
+
+fn parse_condition(clause: &str) -> Result<Condition, String> {
+    let (position, op, kind) = OPERATORS
+        .iter()
+        .filter_map(|(op, kind)| Some((clause.find(op)?, *op, kind)))
+        .min_by_key(|(position, op, _)| (*position, std::cmp::Reverse(op.len())))
+        .ok_or(format!("No comparison in {clause}"))?;
+    let name = clause[..position].trim();
+    let field = match name {
+        "name" | "repository" => Field::Name,
+        _ => Field::Column(
+            Column::from_str(name, true).map_err(|_| format!("Unknown column {name}"))?,
+        ),
+    };
+    let value = parse_value(clause[position + op.len()..].trim())?;
+    let op = match kind {
+        Op::Eq => Op::Eq,
+        Op::Ne => Op::Ne,
+        Op::Compare(comparator) => {
+            if matches!(value, Value::Text(_)) || matches!(field, Field::Name) {
+                return Err(format!("{op} needs a number or an age in {clause}"));
+            }
+            Op::Compare(*comparator)
+        }
+    };
+    Ok(Condition { field, op, value })
+}
+
+impl Filter {
+    // Plain text keeps the substring search; anything with a comparison is an expression
+    pub fn is_expression(text: &str) -> bool {
+        OPERATORS.iter().any(|(op, _)| text.contains(op))
+    }
+
+    pub fn parse(expression: &str, now: DateTime<Utc>) -> Result<Self, String> {
+        let conditions = expression
+            .split("&&")
+            .map(|clause| parse_condition(clause.trim()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            conditions,
+            style: Style::raw(now, Units::default()),
+        })
+    }
+
+    pub fn matches(&self, repo: &RepoStats) -> bool {
+        self.conditions.iter().all(|c| self.check(c, repo))
+    }
+
+    fn check(&self, condition: &Condition, repo: &RepoStats) -> bool {
+        if let Value::Text(text) = &condition.value {
+            let equal = match condition.field {
+                Field::Name => repo.name.eq_ignore_ascii_case(text),
+                Field::Column(Column::Topics) => {
+                    repo.topics.iter().any(|t| t.eq_ignore_ascii_case(text))
+                }
+                Field::Column(column) => column.value(repo, &self.style).eq_ignore_ascii_case(text),
+            };
+            return match condition.op {
+                Op::Ne => !equal,
+                _ => equal,
+            };
+        }
+        let Field::Column(column) = condition.field else {
+            return false;
+        };
+        let value = column.sort_key(repo);
+        let threshold = match condition.value {
+            // sort_key uses -1 for values that were not collected
+            Value::Number(_) if value < 0 => return false,
+            Value::Number(number) => number,
+            Value::Age(age) => (self.style.now - age).timestamp() as f64,
+            Value::Text(_) => return false,
+        };
+        match &condition.op {
+            Op::Eq => value as f64 == threshold,
+            Op::Ne => value as f64 != threshold,
+            Op::Compare(comparator) => comparator.check(value as f64, threshold),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap()
+    }
+
+    fn repo() -> RepoStats {
+        serde_json::from_value(json!({
+            "name": "Anvil",
+            "topics": ["rust", "cli"],
+            "stars": 42,
+            "forks": 7,
+            "license": "MIT License",
+            "followers": 3,
+            "updated_at": "2024-05-20T00:00:00Z",
+            "pushed_at": "2024-01-01T00:00:00Z",
+            "open_issues_count": 5,
+            "size": 1024,
+            "created_at": "2020-01-01T00:00:00Z",
+            "can_push": false,
+        }))
+        .unwrap()
+    }
+
+    fn matches(expression: &str) -> bool {
+        Filter::parse(expression, now()).unwrap().matches(&repo())
+    }
+
+    fn error(expression: &str) -> String {
+        match Filter::parse(expression, now()) {
+            Ok(_) => panic!("{expression} should not parse"),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn the_longest_operator_at_the_first_position_wins() {
+        assert!(matches("stars>=42"));
+        assert!(!matches("stars>42"));
+        assert!(matches("stars<=42"));
+        assert!(!matches("stars<42"));
+        assert!(matches("stars!=41"));
+        assert!(matches("forks==7"));
+    }
+
+    #[test]
+    fn every_condition_has_to_match() {
+        assert!(matches("stars>40 && forks<10"));
+        assert!(!matches("stars>40 && forks>10"));
+        assert!(matches(" stars > 40&&name == anvil "));
+    }
+
+    #[test]
+    fn text_compares_without_case() {
+        assert!(matches("name==ANVIL"));
+        assert!(matches("repository!=hammer"));
+        assert!(matches("license=='mit license'"));
+        assert!(matches("topics==Rust"));
+        assert!(!matches("topics==go"));
+    }
+
+    #[test]
+    fn quoted_numbers_compare_as_text() {
+        assert!(!matches("stars=='042'"));
+        assert!(matches("stars=='42'"));
+    }
+
+    #[test]
+    fn ages_count_back_from_now() {
+        assert!(matches("pushed_at<90d"));
+        assert!(!matches("pushed_at>90d"));
+        assert!(matches("updated_at>30d"));
+    }
+
+    #[test]
+    fn uncollected_values_do_not_match() {
+        assert!(!matches("prs>=0"));
+        assert!(!matches("prs==0"));
+    }
+
+    #[test]
+    fn unknown_columns_are_rejected() {
+        assert_eq!(error("stargazers>1"), "Unknown column stargazers");
+    }
+
+    #[test]
+    fn malformed_expressions_are_rejected() {
+        assert_eq!(error("stars"), "No comparison in stars");
+        assert_eq!(error("stars>10 && "), "No comparison in ");
+        assert_eq!(
+            error("license>mit"),
+            "> needs a number or an age in license>mit"
+        );
+        assert_eq!(error("name<5"), "< needs a number or an age in name<5");
+    }
+
+    #[test]
+    fn only_comparisons_make_an_expression() {
+        assert!(Filter::is_expression("stars>10"));
+        assert!(!Filter::is_expression("anvil"));
+    }
+}
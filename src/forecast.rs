@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use comfy_table::{Cell, Color, Table};
+
+use crate::history::Snapshot;
+use crate::render;
+use crate::stats::SumStats;
+
+pub const HORIZON_DAYS: f64 = 90.0;
+const MIN_POINTS: usize = 3;
+
+struct Fit {
+    slope: f64,
+    intercept: f64,
+    residual_std: f64,
+    mean_x: f64,
+    sxx: f64,
+    n: f64,
+}
+
+fn fit(points: &[(f64, f64)]) -> Option<Fit> {
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    if sxx == 0.0 {
+        return None;
+    }
+    let sxy: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    let slope = sxy / sxx;
+    let intercept = mean_y - slope * mean_x;
+    let sse: f64 = points
+        .iter()
+        .map(|p| (p.1 - (intercept + slope * p.0)).powi(2))
+        .sum();
+    Some(Fit {
+        slope,
+        intercept,
+        residual_std: (sse / (n - 2.0)).sqrt(),
+        mean_x,
+        sxx,
+        n,
+    })
+}
+
+impl Fit {
+    fn predict(&self, x: f64) -> (f64, f64) {
+        let value = self.intercept + self.slope * x;
+        // 95% prediction interval
+        let spread = 1.96
+            * self.residual_std
+            * (1.0 + 1.0 / self.n + (x - self.mean_x).powi(2) / self.sxx).sqrt();
+        (value, spread)
+    }
+}
+
+pub fn table(
+    org: &str,
+    current: &SumStats,
+    history: &[Snapshot],
+    now: DateTime<Utc>,
+) -> Option<Table> {
+    let mut snapshots: Vec<(DateTime<Utc>, &SumStats)> = history
+        .iter()
+        .filter(|s| s.org.eq_ignore_ascii_case(org) && s.collected_at < now)
+        .map(|s| (s.collected_at, &s.stats))
+        .collect();
+    snapshots.push((now, current));
+    if snapshots.len() < MIN_POINTS {
+        return None;
+    }
+    let start = snapshots.iter().map(|s| s.0).min()?;
+    let days = |at: DateTime<Utc>| (at - start).num_seconds() as f64 / 86400.0;
+    let target = days(now) + HORIZON_DAYS;
+
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new(format!("Forecast ({} snapshots)", snapshots.len())).fg(Color::Green),
+        Cell::new("Now").fg(Color::Green),
+        Cell::new(format!("In {HORIZON_DAYS} days")).fg(Color::Green),
+        Cell::new("95% band").fg(Color::Green),
+        Cell::new("Per month").fg(Color::Green),
+    ]);
+    let metrics: [(&str, fn(&SumStats) -> u32); 2] = [
+        ("Stars", |s| s.stars),
+        ("Open issues", |s| s.open_issues_count),
+    ];
+    for (name, metric) in metrics {
+        let points: Vec<(f64, f64)> = snapshots
+            .iter()
+            .map(|(at, stats)| (days(*at), metric(stats) as f64))
+            .collect();
+        let Some(fit) = fit(&points) else {
+            continue;
+        };
+        let (value, spread) = fit.predict(target);
+        table.add_row(vec![
+            name.to_string(),
+            metric(current).to_string(),
+            format!("{:.0}", value.max(0.0)),
+            format!(
+                "{:.0} - {:.0}",
+                (value - spread).max(0.0),
+                (value + spread).max(0.0)
+            ),
+            format!("{:+.1}", fit.slope * 30.0),
+        ]);
+    }
+    Some(table)
+}
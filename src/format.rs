@@ -0,0 +1,263 @@
+use std::sync::OnceLock;
+
+use chrono::{DateTime, FixedOffset, Local, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use crate::cli::Cli;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeUnit {
+    #[default]
+    Binary,
+    Decimal,
+    Raw,
+    Kib,
+    Mib,
+    Gib,
+    Kb,
+    Mb,
+    Gb,
+}
+
+impl SizeUnit {
+    // Bytes per unit and its label for the units that don't scale with the value
+    fn fixed(&self) -> Option<(f64, &'static str)> {
+        match self {
+            SizeUnit::Kib => Some((1024.0, "KiB")),
+            SizeUnit::Mib => Some((1024.0 * 1024.0, "MiB")),
+            SizeUnit::Gib => Some((1024.0 * 1024.0 * 1024.0, "GiB")),
+            SizeUnit::Kb => Some((1e3, "kB")),
+            SizeUnit::Mb => Some((1e6, "MB")),
+            SizeUnit::Gb => Some((1e9, "GB")),
+            SizeUnit::Binary | SizeUnit::Decimal | SizeUnit::Raw => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Rounding {
+    Nearest,
+    Down,
+    Up,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Units {
+    pub size: SizeUnit,
+    pub precision: Option<usize>,
+    pub rounding: Option<Rounding>,
+}
+
+impl Units {
+    // Without explicit settings sizes keep one rounded decimal and ratios stay truncated integers
+    fn round(&self, value: f64, precision: usize, rounding: Rounding) -> String {
+        let precision = self.precision.unwrap_or(precision);
+        let scale = 10f64.powi(precision as i32);
+        let scaled = value * scale;
+        let rounded = match self.rounding.unwrap_or(rounding) {
+            Rounding::Nearest => scaled.round(),
+            Rounding::Down => scaled.floor(),
+            Rounding::Up => scaled.ceil(),
+        } / scale;
+        format!("{rounded:.precision$}")
+    }
+
+    pub fn ratio(&self, value: f64) -> String {
+        self.round(value, 0, Rounding::Down)
+    }
+
+    pub fn decimal(&self, value: f64) -> String {
+        self.round(value, 1, Rounding::Nearest)
+    }
+
+    pub fn human_size(&self, bytes: u64) -> String {
+        if let Some((factor, unit)) = self.size.fixed() {
+            let value = self.round(bytes as f64 / factor, 1, Rounding::Nearest);
+            return format!("{value} {unit}");
+        }
+        let (base, units) = match self.size {
+            SizeUnit::Decimal => (1000.0, ["B", "kB", "MB", "GB", "TB"]),
+            _ => (1024.0, ["B", "KiB", "MiB", "GiB", "TiB"]),
+        };
+        let mut value = bytes as f64;
+        let mut unit = 0;
+        while value >= base && unit < units.len() - 1 {
+            value /= base;
+            unit += 1;
+        }
+        if unit == 0 {
+            format!("{bytes} B")
+        } else {
+            format!(
+                "{} {}",
+                self.round(value, 1, Rounding::Nearest),
+                units[unit]
+            )
+        }
+    }
+
+    // Exports stay numeric in bytes whatever unit the table shows, unless raw KiB were asked for
+    pub fn size_value(&self, kib: u64) -> String {
+        match self.size {
+            SizeUnit::Raw => kib.to_string(),
+            _ => (kib * 1024).to_string(),
+        }
+    }
+}
+
+pub struct Style {
+    pub raw: bool,
+    pub now: DateTime<Utc>,
+    pub max_desc_len: usize,
+    pub units: Units,
+}
+
+impl Style {
+    // Unformatted values for exports, filters and comparisons
+    pub fn raw(now: DateTime<Utc>, units: Units) -> Self {
+        Self {
+            raw: true,
+            now,
+            max_desc_len: usize::MAX,
+            units,
+        }
+    }
+
+    // Display values as asked for with --raw, --max-desc-len and the unit flags
+    pub fn from_args(args: &Cli, now: DateTime<Utc>) -> Self {
+        Self {
+            raw: args.raw,
+            now,
+            max_desc_len: args.max_desc_len,
+            units: args.units(),
+        }
+    }
+
+    pub fn size(&self, kib: u64) -> String {
+        if self.units.size == SizeUnit::Raw {
+            return kib.to_string();
+        }
+        if self.raw {
+            return self.units.size_value(kib);
+        }
+        self.units.human_size(kib * 1024)
+    }
+
+    pub fn time(&self, at: DateTime<Utc>) -> String {
+        if self.raw || has_date_format() {
+            return timestamp(at);
+        }
+        relative_time(at, self.now)
+    }
+
+    pub fn truncate(&self, text: &str) -> String {
+        if text.chars().count() <= self.max_desc_len {
+            return text.to_string();
+        }
+        let mut truncated: String = text
+            .chars()
+            .take(self.max_desc_len.saturating_sub(1))
+            .collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Zone {
+    #[default]
+    Utc,
+    Local,
+    Named(Tz),
+}
+
+impl Zone {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "utc" => Ok(Zone::Utc),
+            "local" => Ok(Zone::Local),
+            _ => name.parse().map(Zone::Named).map_err(|_| {
+                format!("Unknown timezone {name}, expected local, UTC or a name like Europe/Prague")
+            }),
+        }
+    }
+
+    fn offset(&self, at: DateTime<Utc>) -> FixedOffset {
+        let utc = at.naive_utc();
+        match self {
+            Zone::Utc => Utc.fix(),
+            Zone::Local => Local.offset_from_utc_datetime(&utc).fix(),
+            Zone::Named(tz) => tz.offset_from_utc_datetime(&utc).fix(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Dates {
+    pub zone: Zone,
+    pub format: Option<String>,
+}
+
+// Set once from the command line, like the plain table style, so every timestamp agrees
+static DATES: OnceLock<Dates> = OnceLock::new();
+
+pub fn set_dates(dates: Dates) {
+    DATES.set(dates).expect("dates set once ok");
+}
+
+fn dates() -> &'static Dates {
+    DATES.get_or_init(Dates::default)
+}
+
+pub fn local(at: DateTime<Utc>) -> DateTime<FixedOffset> {
+    at.with_timezone(&dates().zone.offset(at))
+}
+
+// Full timestamps in the table with --raw and in exports; UTC ones keep their historic form
+pub fn timestamp(at: DateTime<Utc>) -> String {
+    let dates = dates();
+    match (&dates.format, dates.zone) {
+        (Some(format), _) => local(at).format(format).to_string(),
+        (None, Zone::Utc) => at.to_string(),
+        (None, _) => local(at).format("%Y-%m-%d %H:%M:%S %:z").to_string(),
+    }
+}
+
+pub fn has_date_format() -> bool {
+    dates().format.is_some()
+}
+
+// Dates that may be missing, like the last push of an empty repository
+pub const MISSING: &str = "–";
+
+pub fn day(at: Option<DateTime<Utc>>) -> String {
+    let format = dates().format.as_deref().unwrap_or("%Y-%m-%d");
+    at.map(|at| local(at).format(format).to_string())
+        .unwrap_or(MISSING.to_string())
+}
+
+pub fn relative_time(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now - at;
+    let (value, unit) = if elapsed.num_days() >= 365 {
+        (elapsed.num_days() / 365, "year")
+    } else if elapsed.num_days() >= 30 {
+        (elapsed.num_days() / 30, "month")
+    } else if elapsed.num_days() >= 1 {
+        (elapsed.num_days(), "day")
+    } else if elapsed.num_hours() >= 1 {
+        (elapsed.num_hours(), "hour")
+    } else if elapsed.num_minutes() >= 1 {
+        (elapsed.num_minutes(), "minute")
+    } else {
+        return "just now".to_string();
+    };
+    if value == 1 {
+        format!("1 {unit} ago")
+    } else {
+        format!("{value} {unit}s ago")
+    }
+}
@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+
+use crate::cli::Cli;
+use crate::stats::RepoStats;
+
+pub fn check(args: &Cli, org: &str, repos: &[&RepoStats], now: DateTime<Utc>) -> Vec<String> {
+    let mut failures = vec![];
+    for repo in repos {
+        let issues = repo.open_issues_only().unwrap_or(repo.open_issues_count);
+        if let Some(max) = args.fail_if_open_issues_gt {
+            if issues > max {
+                failures.push(format!("{org}/{}: {issues} open issues", repo.name));
+            }
+        }
+        if let Some(max) = args.fail_if_stale_days_gt {
+            let days = (now - repo.last_push()).num_days();
+            if days > max {
+                failures.push(format!("{org}/{}: no push in {days} days", repo.name));
+            }
+        }
+    }
+    failures
+}
@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::client::Github;
+use crate::scan::{ListedRepo, OrgListing};
+use crate::stats::RepoStats;
+use crate::visibility::Visibility;
+
+pub const DEFAULT_API_URL: &str = "https://gitlab.com/api/v4/";
+const PAGE_SIZE: usize = 100;
+
+#[derive(Deserialize)]
+struct Group {
+    name: String,
+    created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct Project {
+    path: String,
+    description: Option<String>,
+    #[serde(default)]
+    topics: Vec<String>,
+    star_count: u32,
+    forks_count: u32,
+    // Missing when the issue tracker is disabled
+    open_issues_count: Option<u32>,
+    created_at: DateTime<Utc>,
+    last_activity_at: DateTime<Utc>,
+    default_branch: Option<String>,
+    archived: bool,
+    visibility: Option<String>,
+    web_url: String,
+    ssh_url_to_repo: String,
+    http_url_to_repo: String,
+}
+
+impl Project {
+    fn into_listed(self) -> ListedRepo {
+        ListedRepo {
+            name: self.path.clone(),
+            archived: self.archived,
+            disabled: false,
+            stats: Ok(RepoStats {
+                name: self.path,
+                description: self.description.filter(|d| !d.is_empty()),
+                homepage: None,
+                topics: self.topics,
+                stars: self.star_count,
+                forks: self.forks_count,
+                license: None,
+                // Not reported by GitLab; the Followers and Size columns are rejected
+                followers: 0,
+                updated_at: self.last_activity_at,
+                pushed_at: Some(self.last_activity_at),
+                open_issues_count: self.open_issues_count.unwrap_or(0),
+                size: 0,
+                created_at: self.created_at,
+                open_prs: None,
+                traffic: None,
+                can_push: false,
+                releases_last_year: None,
+                community: None,
+                health: None,
+                pushes: None,
+                devcontainer: None,
+                merge: None,
+                actions: None,
+                deployments: None,
+                traceability: None,
+                security: None,
+                default_branch: self.default_branch,
+                ci: None,
+                star_history: None,
+                dependency_updates: None,
+                advisories: None,
+                branch_protected: None,
+                issue_ages: None,
+                status: None,
+                milestones: None,
+                deep: None,
+                owner: None,
+                codeowners: None,
+                review_latency: None,
+                star_velocity: None,
+                dependencies: None,
+                features: None,
+                labels: None,
+                downloads: None,
+                docs: None,
+                growth: None,
+                commit_days: None,
+                custom: BTreeMap::new(),
+                releases_truncated: false,
+                advisories_truncated: false,
+                issue_ages_truncated: false,
+                languages: None,
+                contributors: None,
+                branch_count: None,
+                language: None,
+                visibility: self.visibility,
+                html_url: Some(self.web_url),
+                ssh_url: Some(self.ssh_url_to_repo),
+                clone_url: Some(self.http_url_to_repo),
+            }),
+        }
+    }
+}
+
+pub async fn group_listing(
+    github: &Github,
+    group: &str,
+    visibility: Visibility,
+) -> octocrab::Result<OrgListing> {
+    // Subgroups are addressed by their URL-encoded full path
+    let id = group.replace('/', "%2F");
+    let info: Group = github.get(format!("groups/{id}"), None::<&()>).await?;
+    let mut projects: Vec<Project> = vec![];
+    for page in 1.. {
+        let mut params = vec![
+            ("include_subgroups", "true".to_string()),
+            ("per_page", PAGE_SIZE.to_string()),
+            ("page", page.to_string()),
+        ];
+        if visibility != Visibility::All {
+            params.push(("visibility", visibility.name().to_string()));
+        }
+        let batch: Vec<Project> = github
+            .get(format!("groups/{id}/projects"), Some(&params))
+            .await?;
+        let last = batch.len() < PAGE_SIZE;
+        projects.extend(batch);
+        if last {
+            break;
+        }
+    }
+    let created_at = info
+        .created_at
+        .or_else(|| projects.iter().map(|p| p.created_at).min())
+        .unwrap_or_else(Utc::now);
+    Ok(OrgListing {
+        name: Some(info.name),
+        created_at,
+        repos: projects.into_iter().map(Project::into_listed).collect(),
+    })
+}
@@ -0,0 +1,887 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::client::Github;
+use crate::scan::{ListedRepo, OrgListing};
+use crate::stars;
+use crate::stats::{Dependencies, RepoStats};
+use crate::usage::Usage;
+use crate::visibility::Visibility;
+
+const MAX_PAGE_SIZE: u32 = 100;
+// Connections per repository node: topics, issues, pull requests, releases, languages,
+// mentionable users.
+const NESTED_CONNECTIONS: u32 = 6;
+
+const ORG_QUERY: &str = r#"
+query($org: String!, $first: Int!, $after: String, $privacy: RepositoryPrivacy) {
+  rateLimit { cost remaining }
+  organization(login: $org) {
+    name
+    createdAt
+    repositories(first: $first, after: $after, privacy: $privacy) {
+      pageInfo { hasNextPage endCursor }
+      nodes {
+        name
+        description
+        homepageUrl
+        repositoryTopics(first: 20) { nodes { topic { name } } }
+        stargazerCount
+        forkCount
+        licenseInfo { name }
+        primaryLanguage { name }
+        updatedAt
+        pushedAt
+        createdAt
+        diskUsage
+        isArchived
+        isDisabled
+        visibility
+        viewerPermission
+        defaultBranchRef { name }
+        url
+        sshUrl
+        issues(states: OPEN) { totalCount }
+        pullRequests(states: OPEN) { totalCount }
+        releases(first: 100, orderBy: { field: CREATED_AT, direction: DESC }) {
+          nodes { publishedAt }
+        }
+        languages(first: 10, orderBy: { field: SIZE, direction: DESC }) { nodes { name } }
+        mentionableUsers { totalCount }
+      }
+    }
+  }
+}
+"#;
+
+// Caps --growth at 1000 new stars per repository
+const MAX_GROWTH_PAGES: usize = 10;
+
+const STARGAZERS_QUERY: &str = r#"
+query($org: String!, $repo: String!, $before: String) {
+  repository(owner: $org, name: $repo) {
+    stargazers(last: 100, before: $before, orderBy: { field: STARRED_AT, direction: ASC }) {
+      pageInfo { hasPreviousPage startCursor }
+      edges { starredAt }
+    }
+  }
+}
+"#;
+
+// Newest stargazers first, paging backwards like STARGAZERS_QUERY
+const STARGAZER_LOGINS_QUERY: &str = r#"
+query($org: String!, $repo: String!, $before: String) {
+  repository(owner: $org, name: $repo) {
+    stargazers(last: 100, before: $before) {
+      pageInfo { hasPreviousPage startCursor }
+      nodes { login }
+    }
+  }
+}
+"#;
+
+#[derive(Deserialize)]
+struct Response {
+    data: Option<Data>,
+    errors: Option<Vec<GraphqlError>>,
+}
+
+#[derive(Deserialize)]
+struct GraphqlError {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    message: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Data {
+    rate_limit: Option<RateLimit>,
+    organization: Option<Organization>,
+}
+
+// GitHub reads CODEOWNERS from the first of these locations that exists
+const CODEOWNERS_QUERY: &str = r#"
+query($org: String!, $repo: String!) {
+  repository(owner: $org, name: $repo) {
+    github: object(expression: "HEAD:.github/CODEOWNERS") { ... on Blob { text } }
+    root: object(expression: "HEAD:CODEOWNERS") { ... on Blob { text } }
+    docs: object(expression: "HEAD:docs/CODEOWNERS") { ... on Blob { text } }
+  }
+}
+"#;
+
+#[derive(Deserialize)]
+struct CodeownersResponse {
+    data: Option<CodeownersData>,
+    errors: Option<Vec<GraphqlError>>,
+}
+
+#[derive(Deserialize)]
+struct CodeownersData {
+    repository: Option<CodeownersRepo>,
+}
+
+#[derive(Deserialize)]
+struct CodeownersRepo {
+    github: Option<Blob>,
+    root: Option<Blob>,
+    docs: Option<Blob>,
+}
+
+#[derive(Deserialize)]
+struct Blob {
+    text: Option<String>,
+}
+
+const ENTERPRISE_QUERY: &str = r#"
+query($slug: String!, $after: String) {
+  enterprise(slug: $slug) {
+    organizations(first: 100, after: $after) {
+      pageInfo { hasNextPage endCursor }
+      nodes { login }
+    }
+  }
+}
+"#;
+
+#[derive(Deserialize)]
+struct EnterpriseResponse {
+    data: Option<EnterpriseData>,
+    errors: Option<Vec<GraphqlError>>,
+}
+
+#[derive(Deserialize)]
+struct EnterpriseData {
+    enterprise: Option<Enterprise>,
+}
+
+#[derive(Deserialize)]
+struct Enterprise {
+    organizations: Connection<Login>,
+}
+
+#[derive(Deserialize)]
+struct Login {
+    login: String,
+}
+
+// An organization pins at most six repositories
+const PINNED_QUERY: &str = r#"
+query($org: String!) {
+  organization(login: $org) {
+    pinnedItems(first: 6, types: REPOSITORY) {
+      nodes { ... on Repository { name } }
+    }
+  }
+}
+"#;
+
+#[derive(Deserialize)]
+struct PinnedResponse {
+    data: Option<PinnedData>,
+    errors: Option<Vec<GraphqlError>>,
+}
+
+#[derive(Deserialize)]
+struct PinnedData {
+    organization: Option<PinnedOrg>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PinnedOrg {
+    pinned_items: Connection<PinnedRepo>,
+}
+
+#[derive(Deserialize)]
+struct PinnedRepo {
+    name: String,
+}
+
+const DEPENDENCIES_QUERY: &str = r#"
+query($org: String!, $repo: String!) {
+  repository(owner: $org, name: $repo) {
+    dependencyGraphManifests(first: 100) {
+      totalCount
+      nodes { filename dependenciesCount }
+    }
+  }
+}
+"#;
+
+// Only the first 100 labels are read; repositories rarely have more
+const LABELS_QUERY: &str = r#"
+query($org: String!, $repo: String!) {
+  repository(owner: $org, name: $repo) {
+    labels(first: 100) {
+      nodes { name issues(states: OPEN) { totalCount } }
+    }
+  }
+}
+"#;
+
+// Lockfiles pin the whole tree, so only the other manifests count as direct dependencies
+const LOCKFILES: [&str; 10] = [
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "Cargo.lock",
+    "Gemfile.lock",
+    "poetry.lock",
+    "Pipfile.lock",
+    "composer.lock",
+    "go.sum",
+];
+
+#[derive(Deserialize)]
+struct DependenciesResponse {
+    data: Option<DependenciesData>,
+    errors: Option<Vec<GraphqlError>>,
+}
+
+#[derive(Deserialize)]
+struct DependenciesData {
+    repository: Option<DependenciesRepo>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DependenciesRepo {
+    dependency_graph_manifests: ManifestConnection,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestConnection {
+    total_count: u32,
+    nodes: Vec<Manifest>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    filename: String,
+    dependencies_count: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct LabelsResponse {
+    data: Option<LabelsData>,
+    errors: Option<Vec<GraphqlError>>,
+}
+
+#[derive(Deserialize)]
+struct LabelsData {
+    repository: Option<LabelsRepo>,
+}
+
+#[derive(Deserialize)]
+struct LabelsRepo {
+    labels: LabelConnection,
+}
+
+#[derive(Deserialize)]
+struct LabelConnection {
+    nodes: Vec<Label>,
+}
+
+#[derive(Deserialize)]
+struct Label {
+    name: String,
+    issues: Count,
+}
+
+#[derive(Deserialize)]
+struct StargazerLoginsResponse {
+    data: Option<StargazerLoginsData>,
+    errors: Option<Vec<GraphqlError>>,
+}
+
+#[derive(Deserialize)]
+struct StargazerLoginsData {
+    repository: Option<StargazerLoginsRepo>,
+}
+
+#[derive(Deserialize)]
+struct StargazerLoginsRepo {
+    stargazers: StargazerLogins,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StargazerLogins {
+    page_info: BackwardPageInfo,
+    nodes: Vec<Login>,
+}
+
+#[derive(Deserialize)]
+struct StargazersResponse {
+    data: Option<StargazersData>,
+    errors: Option<Vec<GraphqlError>>,
+}
+
+#[derive(Deserialize)]
+struct StargazersData {
+    repository: Option<StargazersRepo>,
+}
+
+#[derive(Deserialize)]
+struct StargazersRepo {
+    stargazers: Stargazers,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Stargazers {
+    page_info: BackwardPageInfo,
+    edges: Vec<StarEdge>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackwardPageInfo {
+    has_previous_page: bool,
+    start_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StarEdge {
+    starred_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+struct RateLimit {
+    cost: u32,
+    remaining: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Organization {
+    name: Option<String>,
+    created_at: DateTime<Utc>,
+    repositories: Connection<Repo>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Connection<T> {
+    page_info: Option<PageInfo>,
+    nodes: Vec<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfo {
+    has_next_page: bool,
+    end_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Count {
+    #[serde(rename = "totalCount")]
+    total_count: u32,
+}
+
+#[derive(Deserialize)]
+struct Name {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct TopicNode {
+    topic: Name,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReleaseNode {
+    published_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Repo {
+    name: String,
+    description: Option<String>,
+    homepage_url: Option<String>,
+    repository_topics: Connection<TopicNode>,
+    stargazer_count: u32,
+    fork_count: u32,
+    license_info: Option<Name>,
+    primary_language: Option<Name>,
+    updated_at: DateTime<Utc>,
+    pushed_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    disk_usage: Option<u32>,
+    is_archived: bool,
+    is_disabled: bool,
+    visibility: Option<String>,
+    viewer_permission: Option<String>,
+    default_branch_ref: Option<Name>,
+    url: String,
+    ssh_url: String,
+    issues: Count,
+    pull_requests: Count,
+    releases: Connection<ReleaseNode>,
+    languages: Option<Connection<Name>>,
+    mentionable_users: Count,
+}
+
+impl Repo {
+    fn into_listed(self, since: DateTime<Utc>) -> ListedRepo {
+        let stats = Ok(RepoStats {
+            name: self.name.clone(),
+            description: self.description,
+            homepage: self.homepage_url,
+            topics: self
+                .repository_topics
+                .nodes
+                .into_iter()
+                .map(|n| n.topic.name)
+                .collect(),
+            stars: self.stargazer_count,
+            forks: self.fork_count,
+            license: self.license_info.map(|l| l.name),
+            // REST reports watchers_count as the stargazer count
+            followers: self.stargazer_count,
+            updated_at: self.updated_at,
+            pushed_at: self.pushed_at,
+            // REST open_issues_count includes open pull requests
+            open_issues_count: self.issues.total_count + self.pull_requests.total_count,
+            size: self.disk_usage.unwrap_or(0),
+            created_at: self.created_at,
+            open_prs: Some(self.pull_requests.total_count),
+            traffic: None,
+            can_push: matches!(
+                self.viewer_permission.as_deref(),
+                Some("ADMIN" | "MAINTAIN" | "WRITE")
+            ),
+            releases_last_year: Some(
+                self.releases
+                    .nodes
+                    .iter()
+                    .filter(|r| r.published_at.map(|at| at >= since).unwrap_or(false))
+                    .count() as u32,
+            ),
+            community: None,
+            health: None,
+            pushes: None,
+            devcontainer: None,
+            merge: None,
+            actions: None,
+            deployments: None,
+            traceability: None,
+            security: None,
+            default_branch: self.default_branch_ref.map(|b| b.name),
+            ci: None,
+            star_history: None,
+            dependency_updates: None,
+            advisories: None,
+            branch_protected: None,
+            issue_ages: None,
+            status: None,
+            milestones: None,
+            deep: None,
+            owner: None,
+            codeowners: None,
+            review_latency: None,
+            star_velocity: None,
+            dependencies: None,
+            features: None,
+            labels: None,
+            downloads: None,
+            docs: None,
+            growth: None,
+            commit_days: None,
+            custom: BTreeMap::new(),
+            releases_truncated: self.releases.nodes.len() == 100,
+            advisories_truncated: false,
+            issue_ages_truncated: false,
+            languages: self
+                .languages
+                .map(|l| l.nodes.into_iter().map(|n| n.name).collect()),
+            contributors: Some(self.mentionable_users.total_count),
+            branch_count: None,
+            language: self.primary_language.map(|l| l.name),
+            visibility: self.visibility.map(|v| v.to_lowercase()),
+            clone_url: Some(format!("{}.git", self.url)),
+            html_url: Some(self.url),
+            ssh_url: Some(self.ssh_url),
+        });
+        ListedRepo {
+            name: self.name,
+            archived: self.is_archived,
+            disabled: self.is_disabled,
+            stats,
+        }
+    }
+}
+
+pub async fn codeowners(github: &Github, org: &str, repo: &str) -> Result<Option<String>, String> {
+    let response: CodeownersResponse = github
+        .graphql(&json!({
+            "query": CODEOWNERS_QUERY,
+            "variables": { "org": org, "repo": repo },
+        }))
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(errors) = response.errors.filter(|e| !e.is_empty()) {
+        return Err(error_message(errors));
+    }
+    let repository = response
+        .data
+        .and_then(|d| d.repository)
+        .ok_or(format!("repository {org}/{repo} not found"))?;
+    Ok([repository.github, repository.root, repository.docs]
+        .into_iter()
+        .flatten()
+        .find_map(|blob| blob.text))
+}
+
+pub async fn dependencies(github: &Github, org: &str, repo: &str) -> Result<Dependencies, String> {
+    let response: DependenciesResponse = github
+        .graphql(&json!({
+            "query": DEPENDENCIES_QUERY,
+            "variables": { "org": org, "repo": repo },
+        }))
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(errors) = response.errors.filter(|e| !e.is_empty()) {
+        return Err(error_message(errors));
+    }
+    let manifests = response
+        .data
+        .and_then(|d| d.repository)
+        .ok_or(format!("repository {org}/{repo} not found"))?
+        .dependency_graph_manifests;
+    let direct = manifests
+        .nodes
+        .iter()
+        .filter(|manifest| {
+            let name = manifest.filename.rsplit('/').next().unwrap_or_default();
+            !LOCKFILES.contains(&name)
+        })
+        .filter_map(|manifest| manifest.dependencies_count)
+        .sum();
+    Ok(Dependencies {
+        manifests: manifests.total_count,
+        direct,
+    })
+}
+
+// Open issues per label, leaving out labels without any
+pub async fn labels(
+    github: &Github,
+    org: &str,
+    repo: &str,
+) -> Result<BTreeMap<String, u32>, String> {
+    let response: LabelsResponse = github
+        .graphql(&json!({
+            "query": LABELS_QUERY,
+            "variables": { "org": org, "repo": repo },
+        }))
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(errors) = response.errors.filter(|e| !e.is_empty()) {
+        return Err(error_message(errors));
+    }
+    Ok(response
+        .data
+        .and_then(|d| d.repository)
+        .ok_or(format!("repository {org}/{repo} not found"))?
+        .labels
+        .nodes
+        .into_iter()
+        .filter(|label| label.issues.total_count > 0)
+        .map(|label| (label.name, label.issues.total_count))
+        .collect())
+}
+
+pub async fn pinned_repos(github: &Github, org: &str) -> Result<Vec<String>, String> {
+    let response: PinnedResponse = github
+        .graphql(&json!({
+            "query": PINNED_QUERY,
+            "variables": { "org": org },
+        }))
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(errors) = response.errors.filter(|e| !e.is_empty()) {
+        return Err(error_message(errors));
+    }
+    Ok(response
+        .data
+        .and_then(|d| d.organization)
+        .ok_or(format!("organization {org} not found"))?
+        .pinned_items
+        .nodes
+        .into_iter()
+        .map(|repo| repo.name)
+        .collect())
+}
+
+pub async fn enterprise_orgs(github: &Github, slug: &str) -> Result<Vec<String>, String> {
+    let mut orgs = vec![];
+    let mut after: Option<String> = None;
+    loop {
+        let response: EnterpriseResponse = github
+            .graphql(&json!({
+                "query": ENTERPRISE_QUERY,
+                "variables": { "slug": slug, "after": after },
+            }))
+            .await
+            .map_err(|e| e.to_string())?;
+        if let Some(errors) = response.errors.filter(|e| !e.is_empty()) {
+            return Err(error_message(errors));
+        }
+        let connection = response
+            .data
+            .and_then(|d| d.enterprise)
+            .ok_or(format!("enterprise {slug} not found"))?
+            .organizations;
+        orgs.extend(connection.nodes.into_iter().map(|node| node.login));
+        match connection.page_info {
+            Some(page) if page.has_next_page => after = page.end_cursor,
+            _ => return Ok(orgs),
+        }
+    }
+}
+
+fn error_message(errors: Vec<GraphqlError>) -> String {
+    let messages: Vec<String> = errors
+        .into_iter()
+        .map(|e| match e.kind {
+            Some(kind) => format!("{kind}: {}", e.message),
+            None => e.message,
+        })
+        .collect();
+    messages.join("; ")
+}
+
+pub fn estimate_cost(page_size: u32) -> u32 {
+    let requests = 1 + page_size * NESTED_CONNECTIONS;
+    ((requests as f64 / 100.0).round() as u32).max(1)
+}
+
+pub fn page_size(max_cost: Option<u32>) -> u32 {
+    let Some(max_cost) = max_cost else {
+        return MAX_PAGE_SIZE;
+    };
+    (1..=MAX_PAGE_SIZE)
+        .rev()
+        .find(|&size| estimate_cost(size) <= max_cost)
+        .unwrap_or(1)
+}
+
+// Why a listing failed, which decides whether the REST listing can take over
+pub enum ListingError {
+    // The request did not go through, or the token may not run the query
+    Unavailable(String),
+    // The query would cost more points than the rate limit has left
+    Budget(String),
+    // GitHub answered with errors, e.g. for an unknown organization
+    Query(String),
+}
+
+impl fmt::Display for ListingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ListingError::Unavailable(message)
+            | ListingError::Budget(message)
+            | ListingError::Query(message) => f.write_str(message),
+        }
+    }
+}
+
+pub async fn org_listing(
+    github: &Github,
+    org: &str,
+    max_cost: Option<u32>,
+    visibility: Visibility,
+    usage: &Usage,
+) -> Result<OrgListing, ListingError> {
+    let since = Utc::now() - Duration::days(365);
+    let first = page_size(max_cost);
+    let estimated = estimate_cost(first);
+    let mut after: Option<String> = None;
+    let mut listing: Option<OrgListing> = None;
+    loop {
+        if let Some(remaining) = usage.graphql().remaining {
+            if estimated > remaining {
+                return Err(ListingError::Budget(format!(
+                    "query needs ~{estimated} points but only {remaining} remain"
+                )));
+            }
+        }
+        let response: Response = github
+            .graphql(&json!({
+                "query": ORG_QUERY,
+                "variables": {
+                    "org": org,
+                    "first": first,
+                    "after": after,
+                    "privacy": visibility.privacy(),
+                },
+            }))
+            .await
+            .map_err(|e| ListingError::Unavailable(e.to_string()))?;
+        if let Some(rate_limit) = response.data.as_ref().and_then(|d| d.rate_limit.as_ref()) {
+            usage.record_graphql(estimated, rate_limit.cost, rate_limit.remaining);
+        }
+        if let Some(errors) = response.errors.filter(|e| !e.is_empty()) {
+            let scopes = errors
+                .iter()
+                .any(|e| e.kind.as_deref() == Some("INSUFFICIENT_SCOPES"));
+            return Err(match scopes {
+                true => ListingError::Unavailable(error_message(errors)),
+                false => ListingError::Query(error_message(errors)),
+            });
+        }
+        let organization = response
+            .data
+            .and_then(|d| d.organization)
+            .ok_or(ListingError::Query(format!("organization {org} not found")))?;
+        let current = listing.get_or_insert_with(|| OrgListing {
+            name: organization.name.clone(),
+            created_at: organization.created_at,
+            repos: vec![],
+        });
+        current.repos.extend(
+            organization
+                .repositories
+                .nodes
+                .into_iter()
+                .map(|repo| repo.into_listed(since)),
+        );
+        match organization.repositories.page_info {
+            Some(page) if page.has_next_page => after = page.end_cursor,
+            _ => break,
+        }
+    }
+    Ok(listing.expect("listing fetched ok"))
+}
+
+pub async fn star_history(github: &Github, org: &str, repo: &str) -> Result<Vec<u32>, String> {
+    let now = Utc::now();
+    let since = stars::since(now);
+    let mut counts = vec![0; stars::MONTHS];
+    let mut before: Option<String> = None;
+    loop {
+        let response: StargazersResponse = github
+            .graphql(&json!({
+                "query": STARGAZERS_QUERY,
+                "variables": { "org": org, "repo": repo, "before": before },
+            }))
+            .await
+            .map_err(|e| e.to_string())?;
+        if let Some(errors) = response.errors.filter(|e| !e.is_empty()) {
+            return Err(error_message(errors));
+        }
+        let stargazers = response
+            .data
+            .and_then(|d| d.repository)
+            .ok_or(format!("repository {org}/{repo} not found"))?
+            .stargazers;
+        for edge in &stargazers.edges {
+            if let Some(i) = stars::month_index(edge.starred_at, now) {
+                counts[i] += 1;
+            }
+        }
+        // Pages go backwards from the newest star, so stop once a page reaches past the window
+        let reached_start = stargazers
+            .edges
+            .first()
+            .map(|e| e.starred_at < since)
+            .unwrap_or(true);
+        if reached_start || !stargazers.page_info.has_previous_page {
+            break;
+        }
+        before = stargazers.page_info.start_cursor;
+    }
+    Ok(counts)
+}
+
+// Stars given since `since`, walking back from the newest; gives up after MAX_GROWTH_PAGES
+pub async fn stars_since(
+    github: &Github,
+    org: &str,
+    repo: &str,
+    since: DateTime<Utc>,
+) -> Result<(u32, bool), String> {
+    let mut count = 0;
+    let mut before: Option<String> = None;
+    for _ in 0..MAX_GROWTH_PAGES {
+        let response: StargazersResponse = github
+            .graphql(&json!({
+                "query": STARGAZERS_QUERY,
+                "variables": { "org": org, "repo": repo, "before": before },
+            }))
+            .await
+            .map_err(|e| e.to_string())?;
+        if let Some(errors) = response.errors.filter(|e| !e.is_empty()) {
+            return Err(error_message(errors));
+        }
+        let stargazers = response
+            .data
+            .and_then(|d| d.repository)
+            .ok_or(format!("repository {org}/{repo} not found"))?
+            .stargazers;
+        count += stargazers
+            .edges
+            .iter()
+            .filter(|e| e.starred_at >= since)
+            .count() as u32;
+        let reached_start = stargazers
+            .edges
+            .first()
+            .map(|e| e.starred_at < since)
+            .unwrap_or(true);
+        if reached_start || !stargazers.page_info.has_previous_page {
+            return Ok((count, false));
+        }
+        before = stargazers.page_info.start_cursor;
+    }
+    Ok((count, true))
+}
+
+// The most recent stargazers of a repository, at most `limit` of them
+pub async fn stargazer_logins(
+    github: &Github,
+    org: &str,
+    repo: &str,
+    limit: usize,
+) -> Result<Vec<String>, String> {
+    let mut logins = vec![];
+    let mut before: Option<String> = None;
+    while logins.len() < limit {
+        let response: StargazerLoginsResponse = github
+            .graphql(&json!({
+                "query": STARGAZER_LOGINS_QUERY,
+                "variables": { "org": org, "repo": repo, "before": before },
+            }))
+            .await
+            .map_err(|e| e.to_string())?;
+        if let Some(errors) = response.errors.filter(|e| !e.is_empty()) {
+            return Err(error_message(errors));
+        }
+        let stargazers = response
+            .data
+            .and_then(|d| d.repository)
+            .ok_or(format!("repository {org}/{repo} not found"))?
+            .stargazers;
+        logins.extend(stargazers.nodes.into_iter().rev().map(|node| node.login));
+        if !stargazers.page_info.has_previous_page {
+            break;
+        }
+        before = stargazers.page_info.start_cursor;
+    }
+    logins.truncate(limit);
+    Ok(logins)
+}
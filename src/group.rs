@@ -0,0 +1,56 @@
+use std::collections::BTreeMap;
+
+use comfy_table::{Cell, Color, Table};
+
+use crate::format;
+use crate::render;
+use crate::stats::SumStats;
+
+pub struct Groups {
+    key: String,
+    groups: BTreeMap<String, (usize, SumStats)>,
+}
+
+impl Groups {
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            groups: BTreeMap::new(),
+        }
+    }
+
+    pub fn entry(&mut self, group: &str) -> &mut SumStats {
+        let group = if group.is_empty() { "(none)" } else { group };
+        let (count, stats) = self
+            .groups
+            .entry(group.to_string())
+            .or_insert_with(|| (0, SumStats::new()));
+        *count += 1;
+        stats
+    }
+
+    pub fn table(&self) -> Table {
+        let mut table = render::new_table();
+        table.set_header(vec![
+            Cell::new(&self.key).fg(Color::Green),
+            Cell::new("Repositories").fg(Color::Green),
+            Cell::new("Stars").fg(Color::Green),
+            Cell::new("Forks").fg(Color::Green),
+            Cell::new("Open issues").fg(Color::Green),
+            Cell::new("Size").fg(Color::Green),
+            Cell::new("Pushed at").fg(Color::Green),
+        ]);
+        for (group, (count, stats)) in &self.groups {
+            table.add_row(vec![
+                group.clone(),
+                count.to_string(),
+                stats.stars.to_string(),
+                stats.forks.to_string(),
+                stats.open_issues_count.to_string(),
+                stats.size.to_string(),
+                format::timestamp(stats.pushed_at),
+            ]);
+        }
+        table
+    }
+}
@@ -0,0 +1,28 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::history::Snapshot;
+
+// When each organization (lowercase) was last recorded in the history
+pub type LastRuns = HashMap<String, DateTime<Utc>>;
+
+// Organizations without a previous run report the last week
+const DEFAULT_DAYS: i64 = 7;
+
+pub fn last_runs(history: &[Snapshot]) -> LastRuns {
+    let mut runs = LastRuns::new();
+    for snapshot in history {
+        let at = runs
+            .entry(snapshot.org.to_lowercase())
+            .or_insert(snapshot.collected_at);
+        *at = (*at).max(snapshot.collected_at);
+    }
+    runs
+}
+
+pub fn since(runs: &LastRuns, org: &str, now: DateTime<Utc>) -> DateTime<Utc> {
+    runs.get(&org.to_lowercase())
+        .copied()
+        .unwrap_or(now - Duration::days(DEFAULT_DAYS))
+}
@@ -0,0 +1,87 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::stats::RepoStats;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Weights {
+    pub recency: f64,
+    pub issues: f64,
+    pub releases: f64,
+    pub license: f64,
+    pub docs: f64,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self {
+            recency: 30.0,
+            issues: 20.0,
+            releases: 20.0,
+            license: 15.0,
+            docs: 15.0,
+        }
+    }
+}
+
+impl FromStr for Weights {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = Weights::default();
+        for pair in s.split(',').filter(|p| !p.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or(format!("Expected key=value, got {pair}"))?;
+            let value: f64 = value
+                .parse()
+                .map_err(|_| format!("Invalid weight {value} for {key}"))?;
+            match key.trim() {
+                "recency" => weights.recency = value,
+                "issues" => weights.issues = value,
+                "releases" => weights.releases = value,
+                "license" => weights.license = value,
+                "docs" => weights.docs = value,
+                other => return Err(format!("Unknown health weight {other}")),
+            }
+        }
+        Ok(weights)
+    }
+}
+
+fn recency(pushed_at: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+    let days = (now - pushed_at).num_days() as f64;
+    (1.0 - (days - 30.0) / 335.0).clamp(0.0, 1.0)
+}
+
+fn issues(open_issues: u32, stars: u32) -> f64 {
+    let ratio = open_issues as f64 / stars.max(1) as f64;
+    1.0 - ratio.min(1.0)
+}
+
+pub fn score(repo: &RepoStats, weights: &Weights, now: DateTime<Utc>) -> u8 {
+    let releases = (repo.releases_last_year.unwrap_or(0) as f64 / 4.0).min(1.0);
+    let license = if repo.license.is_some() { 1.0 } else { 0.0 };
+    let docs = match &repo.community {
+        Some(community) => {
+            (community.has_readme as u8 as f64 + community.has_contributing as u8 as f64) / 2.0
+        }
+        None => 0.0,
+    };
+    let parts = [
+        (weights.recency, recency(repo.last_push(), now)),
+        (weights.issues, issues(repo.open_issues_count, repo.stars)),
+        (weights.releases, releases),
+        (weights.license, license),
+        (weights.docs, docs),
+    ];
+    let total: f64 = parts.iter().map(|(weight, _)| weight).sum();
+    if total <= 0.0 {
+        return 0;
+    }
+    let weighted: f64 = parts.iter().map(|(weight, value)| weight * value).sum();
+    (weighted / total * 100.0).round() as u8
+}
@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+
+const WEEKS: usize = 53;
+const SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
+const DAY_LABELS: [&str; 7] = ["   ", "Mon", "   ", "Wed", "   ", "Fri", "   "];
+
+// Sunday of the oldest week shown, so the current week is the last column
+fn start(today: NaiveDate) -> NaiveDate {
+    let sunday = today - Duration::days(today.weekday().num_days_from_sunday() as i64);
+    sunday - Duration::weeks(WEEKS as i64 - 1)
+}
+
+fn shade(count: u32, max: u32) -> char {
+    match (count, max) {
+        (0, _) | (_, 0) => SHADES[0],
+        // Round up so a single commit is never shown as none
+        _ => SHADES[(count * 4).div_ceil(max) as usize],
+    }
+}
+
+// Month names above their first Sunday, skipped where the previous name does not fit
+fn month_row(start: NaiveDate) -> String {
+    let mut row = vec![' '; WEEKS + 3];
+    let mut free_from = 0;
+    for week in 0..WEEKS {
+        let sunday = start + Duration::weeks(week as i64);
+        let starts_month = week == 0 || sunday.day() <= 7;
+        if starts_month && week >= free_from {
+            let name = sunday.format("%b").to_string();
+            for (i, c) in name.chars().enumerate() {
+                row[week + i] = c;
+            }
+            free_from = week + name.len() + 1;
+        }
+    }
+    row.into_iter().collect::<String>().trim_end().to_string()
+}
+
+// GitHub-style calendar with a row per weekday and a column per week, darker for more commits
+pub fn render(days: &BTreeMap<NaiveDate, u32>, now: DateTime<Utc>) -> String {
+    let today = now.date_naive();
+    let start = start(today);
+    let in_range = || days.range(start..=today);
+    let max = in_range().map(|(_, count)| *count).max().unwrap_or(0);
+    let total: u32 = in_range().map(|(_, count)| count).sum();
+    let mut lines = vec![format!("    {}", month_row(start))];
+    for (weekday, label) in DAY_LABELS.iter().enumerate() {
+        let cells: String = (0..WEEKS)
+            .map(|week| start + Duration::days((week * 7 + weekday) as i64))
+            .take_while(|day| *day <= today)
+            .map(|day| shade(days.get(&day).copied().unwrap_or(0), max))
+            .collect();
+        lines.push(format!("{label} {cells}"));
+    }
+    let legend: Vec<String> = SHADES.iter().map(char::to_string).collect();
+    lines.push(format!("    Less {} More", legend.join(" ")));
+    let busiest = in_range()
+        .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(day, count)| format!(", busiest day {day} ({count})"))
+        .unwrap_or_default();
+    lines.push(format!("{total} commits in the last 12 months{busiest}"));
+    lines.join("\n")
+}
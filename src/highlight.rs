@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use comfy_table::Color;
+
+use crate::alerts::Comparator;
+use crate::columns::Column;
+use crate::stats::RepoStats;
+use crate::watch;
+
+enum Threshold {
+    Number(f64),
+    // Date columns compare against now minus the age, so pushed_at<90d means older than 90 days
+    Age(chrono::Duration),
+}
+
+pub struct Rule {
+    pub column: Column,
+    comparator: Comparator,
+    threshold: Threshold,
+    pub color: Color,
+}
+
+const COMPARATORS: [(&str, Comparator); 4] = [
+    ("<=", Comparator::Le),
+    (">=", Comparator::Ge),
+    ("<", Comparator::Lt),
+    (">", Comparator::Gt),
+];
+
+fn parse_color(name: &str) -> Result<Color, String> {
+    Ok(match name.to_lowercase().as_str() {
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "white" => Color::White,
+        "grey" | "gray" => Color::Grey,
+        _ => return Err(format!("Unknown highlight color {name}")),
+    })
+}
+
+impl Rule {
+    pub fn parse(rule: &str) -> Result<Self, String> {
+        let invalid =
+            || format!("Invalid highlight rule {rule}, expected e.g. open_issues>100:red");
+        let (condition, color) = rule.rsplit_once(':').ok_or_else(invalid)?;
+        let (position, op, comparator) = COMPARATORS
+            .iter()
+            .filter_map(|(op, comparator)| Some((condition.find(op)?, *op, *comparator)))
+            .min_by_key(|(position, op, _)| (*position, std::cmp::Reverse(op.len())))
+            .ok_or_else(invalid)?;
+        let name = condition[..position].trim();
+        let value = condition[position + op.len()..].trim();
+        let column = Column::from_str(name, true).map_err(|_| format!("Unknown column {name}"))?;
+        let threshold = match value.parse::<f64>() {
+            Ok(number) => Threshold::Number(number),
+            Err(_) => Threshold::Age(
+                chrono::Duration::from_std(watch::parse_interval(value)?)
+                    .map_err(|e| e.to_string())?,
+            ),
+        };
+        Ok(Self {
+            column,
+            comparator,
+            threshold,
+            color: parse_color(color.trim())?,
+        })
+    }
+
+    pub fn matches(&self, repo: &RepoStats, now: DateTime<Utc>) -> bool {
+        let value = self.column.sort_key(repo);
+        let threshold = match self.threshold {
+            // sort_key uses -1 for values that were not collected
+            Threshold::Number(_) if value < 0 => return false,
+            Threshold::Number(number) => number,
+            Threshold::Age(age) => (now - age).timestamp() as f64,
+        };
+        self.comparator.check(value as f64, threshold)
+    }
+}
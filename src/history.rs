@@ -0,0 +1,477 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use age::secrecy::Secret;
+use chrono::{DateTime, Datelike, Duration, Utc};
+use native_tls::TlsConnector;
+use postgres_native_tls::MakeTlsConnector;
+use serde::{Deserialize, Serialize};
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio_postgres::Client;
+
+use crate::stats::{RepoStats, SumStats};
+
+pub const HISTORY_PATH: &str = "out/history.ndjson";
+pub const ENCRYPTED_HISTORY_PATH: &str = "out/history.ndjson.age";
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Retention {
+    pub daily_days: i64,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self { daily_days: 90 }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub collected_at: DateTime<Utc>,
+    pub org: String,
+    pub stats: SumStats,
+    #[serde(default)]
+    pub repos: Vec<RepoSnapshot>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RepoSnapshot {
+    pub name: String,
+    pub stars: u32,
+    pub open_issues: u32,
+    pub created_at: DateTime<Utc>,
+    pub pushed_at: DateTime<Utc>,
+}
+
+impl RepoSnapshot {
+    pub fn from_repo(repo: &RepoStats) -> Self {
+        Self {
+            name: repo.name.clone(),
+            stars: repo.stars,
+            open_issues: repo.open_issues_count,
+            created_at: repo.created_at,
+            pushed_at: repo.last_push(),
+        }
+    }
+}
+
+// Serializes writers sharing one database across users and CI runners.
+const HISTORY_LOCK: i64 = 0x6769_7468_7562;
+
+pub enum Store {
+    File {
+        path: &'static str,
+        passphrase: Option<String>,
+    },
+    Postgres(Client),
+}
+
+fn encrypt(passphrase: &str, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let encryptor = age::Encryptor::with_user_passphrase(Secret::new(passphrase.to_string()));
+    let mut encrypted = vec![];
+    let mut writer = encryptor
+        .wrap_output(&mut encrypted)
+        .map_err(|e| e.to_string())?;
+    writer.write_all(plaintext).map_err(|e| e.to_string())?;
+    writer.finish().map_err(|e| e.to_string())?;
+    Ok(encrypted)
+}
+
+fn decrypt(passphrase: &str, encrypted: &[u8]) -> Result<Vec<u8>, String> {
+    let decryptor = match age::Decryptor::new(encrypted).map_err(|e| e.to_string())? {
+        age::Decryptor::Passphrase(decryptor) => decryptor,
+        _ => return Err("history is not passphrase-encrypted".to_string()),
+    };
+    let mut reader = decryptor
+        .decrypt(&Secret::new(passphrase.to_string()), None)
+        .map_err(|e| format!("Cannot decrypt history: {e}"))?;
+    let mut plaintext = vec![];
+    reader
+        .read_to_end(&mut plaintext)
+        .map_err(|e| e.to_string())?;
+    Ok(plaintext)
+}
+
+pub fn to_lines(snapshots: &[Snapshot]) -> String {
+    let mut content = String::new();
+    for snapshot in snapshots {
+        content.push_str(&serde_json::to_string(snapshot).expect("serialize snapshot ok"));
+        content.push('\n');
+    }
+    content
+}
+
+impl Store {
+    pub async fn open(url: Option<&str>, passphrase: Option<String>) -> Result<Self, String> {
+        let Some(url) = url else {
+            return Ok(Store::File {
+                path: if passphrase.is_some() {
+                    ENCRYPTED_HISTORY_PATH
+                } else {
+                    HISTORY_PATH
+                },
+                passphrase,
+            });
+        };
+        if passphrase.is_some() {
+            return Err("History encryption is only supported for the file store".to_string());
+        }
+        // The connection string's sslmode decides whether TLS is required, preferred or off
+        let connector = TlsConnector::new()
+            .map_err(|e| format!("Cannot set up TLS for the history database: {e}"))?;
+        let (client, connection) = tokio_postgres::connect(url, MakeTlsConnector::new(connector))
+            .await
+            .map_err(|e| format!("Cannot connect to history database: {e}"))?;
+        tokio::spawn(connection);
+        client
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS history_snapshots (
+                    collected_at TIMESTAMPTZ NOT NULL,
+                    org TEXT NOT NULL,
+                    stats TEXT NOT NULL
+                );
+                ALTER TABLE history_snapshots
+                    ADD COLUMN IF NOT EXISTS repos TEXT NOT NULL DEFAULT '[]'",
+            )
+            .await
+            .map_err(|e| format!("Cannot create history table: {e}"))?;
+        Ok(Store::Postgres(client))
+    }
+
+    pub fn describe(&self) -> &str {
+        match self {
+            Store::File { path, .. } => path,
+            Store::Postgres(_) => "history database",
+        }
+    }
+
+    pub async fn load(&self) -> Result<Vec<Snapshot>, String> {
+        match self {
+            Store::File { path, passphrase } => {
+                let content = match fs::read(path).await {
+                    Ok(content) => content,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+                    Err(e) => return Err(format!("Cannot read history {path}: {e}")),
+                };
+                let content = match passphrase {
+                    Some(passphrase) => decrypt(passphrase, &content)?,
+                    None => content,
+                };
+                let content = String::from_utf8(content)
+                    .map_err(|e| format!("Cannot read history {path}: {e}"))?;
+                content
+                    .lines()
+                    .enumerate()
+                    .filter(|(_, line)| !line.trim().is_empty())
+                    .map(|(i, line)| {
+                        serde_json::from_str(line).map_err(|e| {
+                            format!("Corrupt snapshot in history {path} line {}: {e}", i + 1)
+                        })
+                    })
+                    .collect()
+            }
+            Store::Postgres(client) => select(client).await,
+        }
+    }
+
+    pub async fn append(&self, snapshot: &Snapshot) -> Result<(), String> {
+        match self {
+            Store::File {
+                passphrase: Some(_),
+                ..
+            } => {
+                let mut snapshots = self.load().await?;
+                snapshots.push(snapshot.clone());
+                self.rewrite(&snapshots).await
+            }
+            Store::File { path, .. } => {
+                let error = |e: std::io::Error| format!("Cannot write history {path}: {e}");
+                create_parent(path).await.map_err(error)?;
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .await
+                    .map_err(error)?;
+                file.write_all(to_lines(std::slice::from_ref(snapshot)).as_bytes())
+                    .await
+                    .map_err(error)
+            }
+            Store::Postgres(client) => {
+                locked(client, || insert(client, std::slice::from_ref(snapshot))).await
+            }
+        }
+    }
+
+    pub async fn rewrite(&self, snapshots: &[Snapshot]) -> Result<(), String> {
+        match self {
+            Store::File { path, passphrase } => {
+                let content = to_lines(snapshots);
+                let content = match passphrase {
+                    Some(passphrase) => encrypt(passphrase, content.as_bytes())?,
+                    None => content.into_bytes(),
+                };
+                create_parent(path)
+                    .await
+                    .map_err(|e| format!("Cannot write history {path}: {e}"))?;
+                let tmp = format!("{path}.tmp");
+                fs::write(&tmp, content)
+                    .await
+                    .map_err(|e| format!("Cannot write {tmp}: {e}"))?;
+                fs::rename(&tmp, path)
+                    .await
+                    .map_err(|e| format!("Cannot replace {path}: {e}"))
+            }
+            Store::Postgres(client) => locked(client, || replace(client, snapshots)).await,
+        }
+    }
+
+    // Reads, changes and writes back the history as one step under the lock, so
+    // snapshots other runners append meanwhile are not lost. `change` returns None
+    // to leave the history as it is.
+    pub async fn modify<T, F>(&self, change: F) -> Result<T, String>
+    where
+        F: FnOnce(Vec<Snapshot>) -> (Option<Vec<Snapshot>>, T),
+    {
+        match self {
+            Store::File { .. } => {
+                let (snapshots, result) = change(self.load().await?);
+                if let Some(snapshots) = snapshots {
+                    self.rewrite(&snapshots).await?;
+                }
+                Ok(result)
+            }
+            Store::Postgres(client) => {
+                locked(client, move || async move {
+                    let (snapshots, result) = change(select(client).await?);
+                    if let Some(snapshots) = snapshots {
+                        replace(client, &snapshots).await?;
+                    }
+                    Ok(result)
+                })
+                .await
+            }
+        }
+    }
+}
+
+async fn create_parent(path: &str) -> std::io::Result<()> {
+    match Path::new(path).parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => fs::create_dir_all(dir).await,
+        _ => Ok(()),
+    }
+}
+
+async fn select(client: &Client) -> Result<Vec<Snapshot>, String> {
+    let rows = client
+        .query(
+            "SELECT collected_at, org, stats, repos FROM history_snapshots ORDER BY collected_at",
+            &[],
+        )
+        .await
+        .map_err(|e| format!("Cannot read history: {e}"))?;
+    rows.iter()
+        .map(|row| {
+            let org: String = row.get(1);
+            let corrupt =
+                |e: serde_json::Error| format!("Corrupt snapshot of {org} in history: {e}");
+            Ok(Snapshot {
+                collected_at: row.get(0),
+                stats: serde_json::from_str(row.get(2)).map_err(corrupt)?,
+                repos: serde_json::from_str(row.get(3)).map_err(corrupt)?,
+                org,
+            })
+        })
+        .collect()
+}
+
+async fn replace(client: &Client, snapshots: &[Snapshot]) -> Result<(), String> {
+    client
+        .execute("DELETE FROM history_snapshots", &[])
+        .await
+        .map_err(|e| format!("Cannot clear history: {e}"))?;
+    insert(client, snapshots).await
+}
+
+async fn insert(client: &Client, snapshots: &[Snapshot]) -> Result<(), String> {
+    for snapshot in snapshots {
+        let stats = serde_json::to_string(&snapshot.stats).expect("serialize snapshot ok");
+        let repos = serde_json::to_string(&snapshot.repos).expect("serialize snapshot ok");
+        client
+            .execute(
+                "INSERT INTO history_snapshots (collected_at, org, stats, repos) VALUES ($1, $2, $3, $4)",
+                &[&snapshot.collected_at, &snapshot.org, &stats, &repos],
+            )
+            .await
+            .map_err(|e| format!("Cannot write history: {e}"))?;
+    }
+    Ok(())
+}
+
+async fn locked<T, F, Fut>(client: &Client, write: F) -> Result<T, String>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, String>>,
+{
+    let db_error = |e: tokio_postgres::Error| format!("History database error: {e}");
+    client.batch_execute("BEGIN").await.map_err(db_error)?;
+    client
+        .execute("SELECT pg_advisory_xact_lock($1)", &[&HISTORY_LOCK])
+        .await
+        .map_err(db_error)?;
+    match write().await {
+        Ok(result) => {
+            client.batch_execute("COMMIT").await.map_err(db_error)?;
+            Ok(result)
+        }
+        Err(e) => {
+            client.batch_execute("ROLLBACK").await.map_err(db_error)?;
+            Err(e)
+        }
+    }
+}
+
+pub fn compact(
+    snapshots: Vec<Snapshot>,
+    retention: &Retention,
+    now: DateTime<Utc>,
+) -> Vec<Snapshot> {
+    let cutoff = now - Duration::days(retention.daily_days);
+    let mut kept: BTreeMap<(String, String), Snapshot> = BTreeMap::new();
+    for snapshot in snapshots {
+        let bucket = if snapshot.collected_at >= cutoff {
+            snapshot.collected_at.format("%Y-%m-%d").to_string()
+        } else {
+            let week = snapshot.collected_at.iso_week();
+            format!("{}-W{:02}", week.year(), week.week())
+        };
+        let key = (snapshot.org.to_lowercase(), bucket);
+        match kept.get(&key) {
+            Some(existing) if existing.collected_at >= snapshot.collected_at => {}
+            _ => {
+                kept.insert(key, snapshot);
+            }
+        }
+    }
+    let mut kept: Vec<Snapshot> = kept.into_values().collect();
+    kept.sort_by_key(|s| s.collected_at);
+    kept
+}
+
+pub fn latest_before<'a>(
+    history: &'a [Snapshot],
+    org: &str,
+    before: DateTime<Utc>,
+) -> Option<&'a Snapshot> {
+    history
+        .iter()
+        .filter(|s| s.org.eq_ignore_ascii_case(org) && s.collected_at < before)
+        .max_by_key(|s| s.collected_at)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn at(month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, month, day, hour, 0, 0).unwrap()
+    }
+
+    fn snapshot(org: &str, collected_at: DateTime<Utc>) -> Snapshot {
+        Snapshot {
+            collected_at,
+            org: org.to_string(),
+            stats: SumStats::new(),
+            repos: vec![],
+        }
+    }
+
+    fn kept(snapshots: Vec<Snapshot>, daily_days: i64) -> Vec<(String, DateTime<Utc>)> {
+        compact(snapshots, &Retention { daily_days }, at(6, 30, 12))
+            .into_iter()
+            .map(|s| (s.org, s.collected_at))
+            .collect()
+    }
+
+    #[test]
+    fn keeps_the_latest_snapshot_per_day_within_retention() {
+        let kept = kept(
+            vec![
+                snapshot("acme", at(6, 20, 8)),
+                snapshot("acme", at(6, 20, 18)),
+                snapshot("acme", at(6, 20, 9)),
+                snapshot("acme", at(6, 21, 8)),
+            ],
+            30,
+        );
+
+        assert_eq!(
+            kept,
+            [
+                ("acme".to_string(), at(6, 20, 18)),
+                ("acme".to_string(), at(6, 21, 8)),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_the_latest_snapshot_per_iso_week_before_retention() {
+        // 2024-04-01 is a Monday and 2024-04-08 the Monday after
+        let kept = kept(
+            vec![
+                snapshot("acme", at(4, 1, 8)),
+                snapshot("acme", at(4, 7, 8)),
+                snapshot("acme", at(4, 3, 8)),
+                snapshot("acme", at(4, 8, 8)),
+            ],
+            30,
+        );
+
+        assert_eq!(
+            kept,
+            [
+                ("acme".to_string(), at(4, 7, 8)),
+                ("acme".to_string(), at(4, 8, 8)),
+            ]
+        );
+    }
+
+    #[test]
+    fn buckets_organizations_apart_ignoring_case() {
+        let kept = kept(
+            vec![
+                snapshot("acme", at(6, 20, 8)),
+                snapshot("Acme", at(6, 20, 9)),
+                snapshot("globex", at(6, 20, 7)),
+            ],
+            30,
+        );
+
+        assert_eq!(
+            kept,
+            [
+                ("globex".to_string(), at(6, 20, 7)),
+                ("Acme".to_string(), at(6, 20, 9)),
+            ]
+        );
+    }
+
+    #[test]
+    fn snapshots_at_the_cutoff_are_kept_daily() {
+        let kept = kept(
+            vec![
+                snapshot("acme", at(6, 20, 12)),
+                snapshot("acme", at(6, 19, 12)),
+            ],
+            10,
+        );
+
+        assert_eq!(kept.len(), 2);
+    }
+}
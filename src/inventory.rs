@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use crate::diagnostics::Kind;
+use crate::fetch;
+use crate::scan::Context;
+
+#[derive(Serialize, Deserialize)]
+pub struct InventoryRepo {
+    pub id: u64,
+    pub name: String,
+    pub full_name: String,
+    pub html_url: String,
+    pub ssh_url: String,
+    pub clone_url: String,
+    #[serde(default)]
+    pub visibility: Option<String>,
+    pub private: bool,
+    pub archived: bool,
+    pub fork: bool,
+    pub default_branch: Option<String>,
+}
+
+pub async fn run(ctx: &Context, orgs: &[String]) -> Result<(), String> {
+    for org_name in orgs {
+        let repos = match fetch::inventory(&ctx.github, org_name).await {
+            Ok(repos) => repos,
+            Err(e) => {
+                ctx.diagnostics
+                    .emit(Kind::OrgNotFound, Some(org_name), None, &e.to_string());
+                continue;
+            }
+        };
+        for repo in repos
+            .iter()
+            .filter(|repo| !ctx.args.is_excluded(org_name, &repo.name))
+        {
+            println!(
+                "{}",
+                serde_json::to_string(repo).expect("serialize inventory ok")
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use std::collections::BTreeMap;
+
+use comfy_table::{Cell, Color, Table};
+
+use crate::render;
+
+// Labels by open issues, most used first
+pub fn ranked(labels: &BTreeMap<String, u32>) -> Vec<(&String, &u32)> {
+    let mut ranked: Vec<(&String, &u32)> = labels.iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    ranked
+}
+
+pub fn top(labels: &BTreeMap<String, u32>, n: usize) -> String {
+    ranked(labels)
+        .into_iter()
+        .take(n)
+        .map(|(label, count)| format!("{label} {count}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn table(labels: &BTreeMap<String, u32>) -> Table {
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Label").fg(Color::Green),
+        Cell::new("Open issues").fg(Color::Green),
+    ]);
+    for (label, count) in ranked(labels) {
+        table.add_row(vec![Cell::new(label), Cell::new(count)]);
+    }
+    table
+}
@@ -0,0 +1,37 @@
+use comfy_table::{Cell, Color, Table};
+
+use crate::columns::{self, Column};
+use crate::format::Style;
+use crate::render;
+use crate::stats::RepoStats;
+
+pub fn table(
+    mut repos: Vec<(String, RepoStats)>,
+    n: usize,
+    keys: &[Column],
+    columns: &[Column],
+    style: &Style,
+) -> Table {
+    repos.sort_by(|(a_org, a), (b_org, b)| {
+        columns::compare(keys, a, b).then_with(|| a_org.cmp(b_org))
+    });
+
+    let mut table = render::new_table();
+    let mut header = vec![
+        Cell::new("#").fg(Color::Green),
+        Cell::new("Organization").fg(Color::Green),
+        Cell::new("Repository").fg(Color::Green),
+    ];
+    header.extend(
+        columns
+            .iter()
+            .map(|column| Cell::new(column.title()).fg(Color::Green)),
+    );
+    table.set_header(header);
+    for (rank, (org, repo)) in repos.iter().take(n).enumerate() {
+        let mut row = vec![(rank + 1).to_string(), org.clone(), repo.name.clone()];
+        row.extend(columns.iter().map(|column| column.value(repo, style)));
+        table.add_row(row);
+    }
+    table
+}
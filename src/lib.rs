@@ -0,0 +1,86 @@
+pub(crate) mod advisories;
+pub(crate) mod agg;
+pub mod alerts;
+pub(crate) mod anonymize;
+pub mod archive;
+pub(crate) mod audit;
+pub(crate) mod baseline;
+pub mod batch;
+pub(crate) mod brief;
+pub(crate) mod card;
+pub mod chart;
+pub mod cli;
+pub mod client;
+pub(crate) mod codeowners;
+pub(crate) mod cohorts;
+pub mod columns;
+pub(crate) mod community;
+pub mod compare;
+pub mod completions;
+pub mod config;
+pub(crate) mod date_filter;
+pub(crate) mod deep;
+pub mod diagnostics;
+pub mod digest;
+pub mod discover;
+pub(crate) mod docs;
+pub mod dump;
+pub(crate) mod enrich;
+pub mod estimate;
+pub mod failures;
+pub mod fetch;
+pub(crate) mod filter;
+pub mod forecast;
+pub mod format;
+pub mod gate;
+pub mod gitlab;
+pub mod graphql;
+pub(crate) mod group;
+pub mod growth;
+pub(crate) mod health;
+pub(crate) mod heatmap;
+pub(crate) mod highlight;
+pub mod history;
+pub mod inventory;
+pub(crate) mod labels;
+pub mod leaderboard;
+pub mod licenses;
+pub(crate) mod merge;
+pub(crate) mod metadata;
+pub mod metrics;
+pub(crate) mod milestones;
+pub mod movers;
+pub(crate) mod ndjson;
+pub(crate) mod out_dir;
+pub mod overlap;
+pub mod perf;
+pub mod period;
+pub(crate) mod policy;
+pub mod provider;
+pub(crate) mod raw;
+pub mod remediation;
+pub mod render;
+pub mod resume;
+pub mod scan;
+pub mod schema;
+pub mod scopes;
+pub mod serve;
+pub(crate) mod shard;
+pub mod sink;
+pub(crate) mod sizes;
+pub mod snapshot;
+pub(crate) mod stars;
+pub mod stats;
+pub(crate) mod status;
+pub mod summary;
+pub(crate) mod template;
+pub mod topics;
+pub(crate) mod traceability;
+pub mod tui;
+pub mod usage;
+pub mod velocity;
+pub mod verify;
+pub(crate) mod visibility;
+pub mod warm;
+pub mod watch;
+pub(crate) mod xlsx;
@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use comfy_table::{Cell, Color, Table};
+
+use crate::diagnostics::Kind;
+use crate::fetch;
+use crate::render;
+use crate::scan::{Context, OrgReport};
+
+pub const NOTICE: &str = "NOTICE";
+
+pub struct Notice {
+    pub repo: String,
+    pub license: String,
+    pub text: String,
+}
+
+pub const NO_LICENSE: &str = "none";
+
+pub fn table(licenses: &BTreeMap<String, u32>) -> Table {
+    let mut licenses: Vec<(&String, &u32)> = licenses.iter().collect();
+    licenses.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("License").fg(Color::Green),
+        Cell::new("Repositories").fg(Color::Green),
+    ]);
+    for (license, count) in licenses {
+        let color = if license == NO_LICENSE {
+            Color::Yellow
+        } else {
+            Color::Reset
+        };
+        table.add_row(vec![Cell::new(license).fg(color), Cell::new(count)]);
+    }
+    table
+}
+
+// Each license lands in DIR/org/repo/ under its original file name
+pub async fn collect(ctx: &Context, report: &OrgReport, dir: &Path) -> Result<Vec<Notice>, String> {
+    let mut notices = vec![];
+    for (repo, _) in &report.rows {
+        let owner = repo.owner.as_deref().unwrap_or(&report.org);
+        let file = match fetch::license_file(&ctx.github, owner, &repo.name).await {
+            Ok(Some(file)) => file,
+            Ok(None) => continue,
+            Err(e) => {
+                ctx.diagnostics.emit(
+                    Kind::DegradedMetric,
+                    Some(&report.org),
+                    Some(&repo.name),
+                    &format!("license file unavailable: {e}"),
+                );
+                continue;
+            }
+        };
+        let repo_dir = dir.join(&report.org).join(&repo.name);
+        std::fs::create_dir_all(&repo_dir)
+            .map_err(|e| format!("Cannot create {}: {e}", repo_dir.display()))?;
+        let name = Path::new(&file.path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or("LICENSE".to_string());
+        let path = repo_dir.join(name);
+        std::fs::write(&path, &file.text)
+            .map_err(|e| format!("Cannot write {}: {e}", path.display()))?;
+        notices.push(Notice {
+            repo: format!("{owner}/{}", repo.name),
+            license: file
+                .spdx_id
+                .or(repo.license.clone())
+                .unwrap_or(NO_LICENSE.to_string()),
+            text: file.text,
+        });
+    }
+    Ok(notices)
+}
+
+pub fn write_notice(dir: &Path, notices: &[Notice]) -> Result<(), String> {
+    let mut content = String::new();
+    content.push_str("This product includes software from the following repositories.\n");
+    for notice in notices {
+        content.push_str(&format!(
+            "\n{}\n\n{} ({})\n\n{}\n",
+            "=".repeat(78),
+            notice.repo,
+            notice.license,
+            notice.text.trim_end()
+        ));
+    }
+    let path = dir.join(NOTICE);
+    std::fs::write(&path, content).map_err(|e| format!("Cannot write {}: {e}", path.display()))
+}
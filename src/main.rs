@@ -1,173 +1,804 @@
-use chrono::{DateTime, Datelike, NaiveDateTime, Utc};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use clap::{arg, Parser};
-use comfy_table::{Cell, Color, ContentArrangement, Table};
-use octocrab;
-use octocrab::params::repos::Type;
-use tokio::fs::File;
-use tokio::io::AsyncWriteExt;
+use chrono::{DateTime, Utc};
 
-#[derive(Parser, Debug)]
-struct Cli {
-    orgs: Vec<String>,
+use clap::Parser;
+use octocrab::Octocrab;
+use tracing_subscriber::EnvFilter;
+
+use github_star_crawler::cli::{Cli, Command, HistoryAction, PerfAction, Provider, SnapshotAction};
+use github_star_crawler::client::Github;
+use github_star_crawler::columns::Column;
+use github_star_crawler::config::Config;
+use github_star_crawler::diagnostics::Kind;
+use github_star_crawler::failures::{Failure, Reason};
+use github_star_crawler::format::Style;
+use github_star_crawler::history::{RepoSnapshot, Snapshot, Store};
+use github_star_crawler::scan::Context;
+use github_star_crawler::stats::{RepoStats, SumStats};
+use github_star_crawler::summary::RunSummary;
+use github_star_crawler::{
+    alerts, archive, batch, chart, cli, compare, completions, digest, discover, dump, estimate,
+    failures, forecast, format, gate, gitlab, graphql, growth, history, inventory, leaderboard,
+    licenses, movers, overlap, perf, period, remediation, render, resume, scan, schema, scopes,
+    serve, sink, snapshot, topics, tui, usage, velocity, verify, warm, watch,
+};
 
-    #[arg(
-        short,
-        long,
-        value_name = "Take only n latest repositories for compactness",
-        default_value_t = usize::MAX
-    )]
-    latest_n: usize,
+// Like timeout(1), so schedulers can tell a deadline from a failure
+const TIMEOUT_EXIT_CODE: i32 = 124;
+
+#[tokio::main]
+async fn main() -> Result<(), String> {
+    let started_at = Utc::now();
+    let started = Instant::now();
+    let mut args = Cli::parse();
+    match &args.command {
+        Some(Command::Completions { shell }) => {
+            completions::print(*shell);
+            return Ok(());
+        }
+        Some(Command::Man) => return completions::print_man_page(),
+        _ => {}
+    }
+    let config = Config::load(args.config.as_deref())?;
+    args.merge(&config);
+    if let Some(Command::Warm { cache, .. }) = &args.command {
+        args.incremental = Some(cache.clone());
+    }
+    refuse_plaintext(&args, &config)?;
+    let deadline = parse_deadline(started, args.timeout.as_deref())?;
+    render::set_plain(args.plain());
+    render::set_max_width(args.max_width);
+    format::set_dates(format::Dates {
+        zone: match &args.timezone {
+            Some(name) => format::Zone::parse(name)?,
+            None => format::Zone::Utc,
+        },
+        format: args.date_format.clone(),
+    });
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_target(false)
+        .with_env_filter(
+            EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| EnvFilter::new(if args.quiet { "error" } else { "warn" })),
+        )
+        .init();
+    let orgs = match &args.command {
+        Some(
+            Command::Tui { orgs }
+            | Command::Digest { orgs, .. }
+            | Command::Chart { orgs, .. }
+            | Command::Serve { orgs, .. }
+            | Command::SuggestTopics { orgs, .. }
+            | Command::Apply { orgs, .. }
+            | Command::ArchiveCandidates { orgs, .. }
+            | Command::Audit { orgs, .. }
+            | Command::Inventory { orgs }
+            | Command::Warm { orgs, .. },
+        ) if !orgs.is_empty() => orgs,
+        _ => &args.orgs,
+    };
+    let orgs = cli::normalize_orgs(
+        config.expand_orgs(&cli::read_orgs(orgs, args.orgs_file.as_deref())?)?,
+        args.provider == Provider::Gitlab,
+    );
+
+    let store = bounded(
+        deadline,
+        Store::open(config.history_url.as_deref(), config.history_passphrase()?),
+    )
+    .await?;
+    if let Some(Command::History {
+        action: HistoryAction::Compact { daily_days },
+    }) = &args.command
+    {
+        let mut retention = config.retention;
+        if let Some(days) = daily_days {
+            retention.daily_days = *days;
+        }
+        let (before, after) = store
+            .modify(|snapshots| {
+                let before = snapshots.len();
+                let compacted = history::compact(snapshots, &retention, Utc::now());
+                let after = compacted.len();
+                (Some(compacted), (before, after))
+            })
+            .await?;
+        println!(
+            "Compacted {}: {before} -> {after} snapshots",
+            store.describe()
+        );
+        return Ok(());
+    }
+
+    match &args.command {
+        Some(Command::Snapshot {
+            action: SnapshotAction::Export { out, dump },
+        }) => {
+            let snapshots = store.load().await?;
+            snapshot::export(out, &snapshots, dump.as_deref(), Utc::now())?;
+            println!(
+                "Exported {} snapshots from {} to {}",
+                snapshots.len(),
+                store.describe(),
+                out.display()
+            );
+            return Ok(());
+        }
+        Some(Command::Snapshot {
+            action: SnapshotAction::Import { bundle, dump_out },
+        }) => {
+            let imported = snapshot::import(bundle)?;
+            let total = imported.snapshots.len();
+            let added = store
+                .modify(|snapshots| {
+                    let (merged, added) = snapshot::merge(snapshots, imported.snapshots);
+                    ((added > 0).then_some(merged), added)
+                })
+                .await?;
+            println!(
+                "Imported {added} snapshots into {} ({} already present)",
+                store.describe(),
+                total - added
+            );
+            if let Some(dump) = imported.dump {
+                std::fs::write(dump_out, dump)
+                    .map_err(|e| format!("Cannot write dump {}: {e}", dump_out.display()))?;
+                println!("Extracted dump to {}", dump_out.display());
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    if args.schema {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&schema::describe()).expect("serialize schema ok")
+        );
+        return Ok(());
+    }
+    if let Some(Command::Perf {
+        action: PerfAction::Report { last },
+    }) = &args.command
+    {
+        println!("{}", perf::report(&perf::load()?, *last));
+        return Ok(());
+    }
+    if let Some(Command::Digest { since, .. }) = &args.command {
+        let since =
+            chrono::Duration::from_std(watch::parse_interval(since)?).map_err(|e| e.to_string())?;
+        let history = store.load().await?;
+        print!("{}", digest::render(&history, &orgs, since, Utc::now()));
+        return Ok(());
+    }
+    if let Some(Command::Chart {
+        kind, output, top, ..
+    }) = &args.command
+    {
+        chart::render(output, *kind, &store.load().await?, &orgs, *top)?;
+        println!("Wrote {}", output.display());
+        return Ok(());
+    }
+
+    let tokens = config.tokens(&args.token)?;
+    let api_url = match (&args.api_url, args.provider) {
+        (Some(url), _) => Some(format!("{}/", url.trim_end_matches('/'))),
+        (None, Provider::Gitlab) => Some(gitlab::DEFAULT_API_URL.to_string()),
+        (None, Provider::Github) => None,
+    };
+    let request_timeout = match &args.request_timeout {
+        Some(timeout) => Some(watch::parse_interval(timeout)?),
+        None => None,
+    };
+    let builder = || {
+        let builder = match &api_url {
+            Some(url) => Octocrab::builder().base_url(url.as_str())?,
+            None => Octocrab::builder(),
+        }
+        .set_connect_timeout(request_timeout)
+        .set_read_timeout(request_timeout);
+        Ok::<_, octocrab::Error>(match &args.user_agent {
+            Some(agent) => builder.add_header(
+                "user-agent".parse().expect("user-agent header name ok"),
+                agent.clone(),
+            ),
+            None => builder,
+        })
+    };
+    let clients = if tokens.is_empty() {
+        vec![builder()
+            .and_then(|b| b.build())
+            .map_err(|e| e.to_string())?]
+    } else {
+        tokens
+            .into_iter()
+            .map(|token| builder().and_then(|b| b.personal_token(token).build()))
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?
+    };
+    let github = Github::new(
+        clients,
+        args.retries,
+        Duration::from_millis(args.retry_delay),
+        args.parallelism,
+    );
+    if let Some(budget) = args.request_budget {
+        github.limit_budget(budget);
+    }
+    if let Some(path) = &args.request_log {
+        github.log_requests(path)?;
+    }
+    let github = Arc::new(github);
+    if let Some(Command::Batch { jobs }) = &args.command {
+        return run_batch(
+            github,
+            &batch::load(jobs)?,
+            &config,
+            &store,
+            started_at,
+            started,
+            deadline,
+        )
+        .await;
+    }
+    let mut ctx = Context::new(github, args)?;
+    if ctx.args.check_scopes {
+        scopes::check(&mut ctx).await?;
+    }
+    if ctx.columns.contains(&Column::StarVelocity) {
+        ctx.star_baselines =
+            velocity::baselines(&bounded(deadline, store.load()).await?, Utc::now());
+    }
+    if ctx.enrichments.growth {
+        ctx.last_runs = growth::last_runs(&bounded(deadline, store.load()).await?);
+    }
+    let args = &ctx.args;
+    let orgs = match (&ctx.offline, &args.command) {
+        (Some(offline), _) if orgs.is_empty() => offline.iter().map(|o| o.org.clone()).collect(),
+        (
+            _,
+            Some(Command::Discover {
+                query,
+                max_orgs,
+                repos,
+            }),
+        ) => {
+            let discovered =
+                discover::orgs(&ctx.github, query.as_deref(), repos, *max_orgs).await?;
+            println!("Discovered organizations: {}", discovered.join(", "));
+            discovered
+        }
+        _ => orgs,
+    };
+    let orgs = if args.enterprise.is_some() || args.all_orgs {
+        let mut orgs = orgs;
+        let mut enumerated = vec![];
+        if let Some(slug) = &args.enterprise {
+            enumerated.extend(graphql::enterprise_orgs(&ctx.github, slug).await?);
+        }
+        if args.all_orgs {
+            enumerated.extend(discover::visible_orgs(&ctx.github).await?);
+        }
+        for org in enumerated {
+            if !orgs.iter().any(|o| o.eq_ignore_ascii_case(&org)) {
+                orgs.push(org);
+            }
+        }
+        tracing::info!("Enumerated {} organizations", orgs.len());
+        orgs
+    } else {
+        orgs
+    };
+
+    if args.verify {
+        verify::run(&ctx, &orgs, args.verify_sample).await?;
+        return ctx.diagnostics.check_orgs();
+    }
+    if let Some(Command::CompareRepos { left, right }) = &args.command {
+        return compare::run(&ctx, left, right).await;
+    }
+    if let Some(Command::Overlap {
+        left,
+        right,
+        repos,
+        sample,
+    }) = &args.command
+    {
+        return overlap::run(&ctx, left, right, *repos, *sample).await;
+    }
+    if let Some(Command::Warm { cache, .. }) = &args.command {
+        warm::run(&ctx, &orgs, cache).await?;
+        return ctx.diagnostics.check_orgs();
+    }
+    if let Some(Command::Inventory { .. }) = &args.command {
+        inventory::run(&ctx, &orgs).await?;
+        return ctx.diagnostics.check_orgs();
+    }
+    if let Some(Command::Tui { .. }) = &args.command {
+        return tui::run(&ctx, &orgs, store.load().await?).await;
+    }
+    if let Some(Command::Serve {
+        listen, interval, ..
+    }) = &args.command
+    {
+        let interval = watch::parse_interval(interval)?;
+        return serve::run(&ctx, &orgs, listen, interval).await;
+    }
+    if let Some(Command::SuggestTopics { apply, .. }) = &args.command {
+        topics::run(&ctx, &orgs, *apply).await?;
+        return ctx.diagnostics.check_orgs();
+    }
+    if let Some(Command::ArchiveCandidates {
+        stale_days,
+        topic,
+        issue,
+        ..
+    }) = &args.command
+    {
+        let actions = archive::Actions {
+            stale_days: *stale_days,
+            topic: *topic,
+            issue: *issue,
+        };
+        archive::run(&ctx, &orgs, &actions).await?;
+        return ctx.diagnostics.check_orgs();
+    }
+    if let Some(Command::Audit {
+        months,
+        commands,
+        apply,
+        yes,
+        ..
+    }) = &args.command
+    {
+        archive::audit(&ctx, &orgs, *months, *commands, *apply, *yes).await?;
+        return ctx.diagnostics.check_orgs();
+    }
+    if let Some(Command::Apply { yes, .. }) = &args.command {
+        remediation::run(&ctx, &orgs, &config.remediation, *yes).await?;
+        return ctx.diagnostics.check_orgs();
+    }
+    if let Some(interval) = &args.watch {
+        let interval = watch::parse_interval(interval)?;
+        watch::run(&ctx, &orgs, interval).await;
+        return Ok(());
+    }
+
+    report(ctx, orgs, &config, &store, started_at, started, deadline).await
 }
 
-struct SumStats {
-    stars: u32,
-    forks: u32,
-    followers: u32,
-    updated_at: DateTime<Utc>,
-    pushed_at: DateTime<Utc>,
-    open_issues_count: u32,
-    size: u32,
+// Jobs run one after another on the same client, so they share its rate limit handling,
+// token rotation and request budget instead of competing for them
+async fn run_batch(
+    github: Arc<Github>,
+    jobs: &[batch::Job],
+    config: &Config,
+    store: &Store,
+    started_at: DateTime<Utc>,
+    started: Instant,
+    deadline: Option<tokio::time::Instant>,
+) -> Result<(), String> {
+    let mut failed = vec![];
+    for job in jobs {
+        tracing::info!("Running job {}", job.name);
+        let result = async {
+            let mut args = job.cli()?;
+            args.merge(config);
+            refuse_plaintext(&args, config)?;
+            let orgs = cli::normalize_orgs(
+                config.expand_orgs(&cli::read_orgs(&args.orgs, args.orgs_file.as_deref())?)?,
+                args.provider == Provider::Gitlab,
+            );
+            // A job's own --timeout replaces the one of the batch
+            let deadline = parse_deadline(started, args.timeout.as_deref())?.or(deadline);
+            let mut ctx = Context::new(github.clone(), args)?;
+            if ctx.columns.contains(&Column::StarVelocity) {
+                ctx.star_baselines =
+                    velocity::baselines(&bounded(deadline, store.load()).await?, Utc::now());
+            }
+            if ctx.enrichments.growth {
+                ctx.last_runs = growth::last_runs(&bounded(deadline, store.load()).await?);
+            }
+            report(ctx, orgs, config, store, started_at, started, deadline).await
+        }
+        .await;
+        if let Err(e) = result {
+            tracing::error!("Job {} failed: {e}", job.name);
+            failed.push(job.name.as_str());
+        }
+    }
+    if !failed.is_empty() {
+        return Err(format!(
+            "{} of {} jobs failed: {}",
+            failed.len(),
+            jobs.len(),
+            failed.join(", ")
+        ));
+    }
+    Ok(())
 }
 
-impl SumStats {
-    pub fn new() -> Self {
-        Self {
-            stars: 0,
-            forks: 0,
-            followers: 0,
-            updated_at: DateTime::<Utc>::from_utc(NaiveDateTime::MIN, Utc),
-            pushed_at: DateTime::<Utc>::from_utc(NaiveDateTime::MIN, Utc),
-            open_issues_count: 0,
-            size: 0,
-        }
-    }
-
-    pub fn update(
-        &mut self,
-        stars: u32,
-        forks: u32,
-        followers: u32,
-        updated_at: DateTime<Utc>,
-        pushed_at: DateTime<Utc>,
-        open_issues_count: u32,
-        size: u32,
-    ) {
-        self.stars += stars;
-        self.forks += forks;
-        self.followers += followers;
-        self.updated_at = self.updated_at.max(updated_at);
-        self.pushed_at = self.pushed_at.max(pushed_at);
-        self.open_issues_count += open_issues_count;
-        self.size += size;
+// Measured from the start of the run, so batch jobs share one deadline
+// Dumps, the incremental cache and raw API files are written as plain JSON, so with an
+// encrypted history they would leave readable what the history keeps encrypted
+fn refuse_plaintext(args: &Cli, config: &Config) -> Result<(), String> {
+    if config.history_passphrase_env.is_none() {
+        return Ok(());
     }
+    let flag = if args.dump_raw.is_some() {
+        "--dump-raw"
+    } else if args.incremental.is_some() {
+        "--incremental"
+    } else if args.raw_json.is_some() {
+        "--raw-json"
+    } else {
+        return Ok(());
+    };
+    Err(format!(
+        "{flag} writes unencrypted files; it cannot be used while history_passphrase_env is set"
+    ))
 }
 
-#[tokio::main]
-async fn main() -> Result<(), String> {
-    let args = Cli::parse();
-
-    let github = octocrab::instance();
-
-    let mut out_file = File::create("out/org_stats.csv")
-        .await
-        .expect("open csv file ok");
-
-    out_file.write_all(b"real_org_name,org_created_at,stars,forks,followers,updated_at,pushed_at,open_issues_count,size\n").await.expect("csv file write ok");
-
-    for org_name in args.orgs {
-        let org = github.orgs(&org_name);
-        match org.get().await {
-            Ok(org_info) => {
-                let mut org_repos = org
-                    .list_repos()
-                    .repo_type(Type::Public)
-                    .send()
-                    .await
-                    .expect("find repos")
-                    .items;
-                org_repos.sort_by(|a, b| b.pushed_at.unwrap().cmp(&a.pushed_at.unwrap()));
-
-                let mut sum_stats = SumStats::new();
-                let mut table = Table::new();
-                table.set_content_arrangement(ContentArrangement::DynamicFullWidth);
-                table.add_row(vec![
-                    Cell::new("Repository").fg(Color::Green),
-                    Cell::new("Stars").fg(Color::Green),
-                    Cell::new("Forks").fg(Color::Green),
-                    Cell::new("License").fg(Color::Green),
-                    Cell::new("Followers").fg(Color::Green),
-                    Cell::new("Updated at").fg(Color::Green),
-                    Cell::new("Pushed at").fg(Color::Green),
-                    Cell::new("Open issues").fg(Color::Green),
-                    Cell::new("Size").fg(Color::Green),
-                    Cell::new("Created").fg(Color::Green),
-                ]);
-                for repo in org_repos.into_iter().take(args.latest_n) {
-                    if repo.archived.unwrap() {
-                        continue;
+fn parse_deadline(
+    started: Instant,
+    timeout: Option<&str>,
+) -> Result<Option<tokio::time::Instant>, String> {
+    match timeout {
+        Some(timeout) => Ok(Some(tokio::time::Instant::from_std(
+            started + watch::parse_interval(timeout)?,
+        ))),
+        None => Ok(None),
+    }
+}
+
+// Runs a step outside the scans (history, estimates) against --timeout; with no
+// organization finished there is nothing to write, so the run just ends
+async fn bounded<T>(
+    deadline: Option<tokio::time::Instant>,
+    step: impl Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    tokio::select! {
+        result = step => result,
+        _ = expired(deadline) => {
+            tracing::error!("Timed out before the report started");
+            std::process::exit(TIMEOUT_EXIT_CODE);
+        }
+    }
+}
+
+// Never resolves without --timeout
+async fn expired(deadline: Option<tokio::time::Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+// One report run over the organizations: tables, exports, history and the exit checks
+async fn report(
+    ctx: Context,
+    orgs: Vec<String>,
+    config: &Config,
+    store: &Store,
+    started_at: DateTime<Utc>,
+    started: Instant,
+    deadline: Option<tokio::time::Instant>,
+) -> Result<(), String> {
+    let args = &ctx.args;
+    if args.estimate && ctx.offline.is_none() {
+        bounded(
+            deadline,
+            estimate::check(&ctx, &orgs, args.estimate_threshold),
+        )
+        .await?;
+    }
+
+    let history = bounded(deadline, store.load()).await?;
+    let now = Utc::now();
+    let units = args.units();
+    let style = Style::from_args(args, now);
+    let mut policy_violations = 0;
+    let mut unlicensed: Vec<String> = vec![];
+    let mut gate_failures: Vec<String> = vec![];
+    let mut alert_state = alerts::State::load(alerts::STATE_PATH);
+    let mut transitions = vec![];
+    let mut leaderboard_repos: Vec<(String, RepoStats)> = vec![];
+    let mut notices = vec![];
+    let mut run = RunSummary::default();
+    let mut grand_total = SumStats::new();
+    let mut failures: Vec<Failure> = vec![];
+    let mut regressions: Vec<String> = vec![];
+    let columns = &ctx.columns;
+    let owns_stdout = args.format.is_some_and(|format| format.owns_stdout());
+    let mut sinks = sink::open(&ctx, now, &history)?;
+    let mut resume = match &args.resume {
+        Some(path) => Some(resume::State::load(path)?),
+        None => None,
+    };
+    if let Some(state) = resume.as_ref().filter(|s| !s.completed.is_empty()) {
+        tracing::info!(
+            "Resuming, skipping {} completed organizations",
+            state.completed.len()
+        );
+        run.orgs = state.completed.len() as u32;
+        run.repos = state.repos;
+        grand_total = state.total.clone();
+    }
+    // Ctrl-C stops after the organizations written so far; the outputs are still finished
+    let interrupt = tokio::signal::ctrl_c();
+    tokio::pin!(interrupt);
+    let mut interrupted_at = None;
+    let mut timed_out = false;
+
+    for (index, org_name) in orgs.iter().cloned().enumerate() {
+        if resume.as_ref().is_some_and(|s| s.is_done(&org_name)) {
+            continue;
+        }
+        let scanned = tokio::select! {
+            scanned = scan::scan_org(&ctx, &org_name, now) => Some(scanned),
+            _ = &mut interrupt => None,
+            _ = expired(deadline) => {
+                timed_out = true;
+                None
+            }
+        };
+        let Some(scanned) = scanned else {
+            interrupted_at = Some(index);
+            break;
+        };
+        match scanned {
+            Ok(report) => {
+                run.orgs += 1;
+                run.repos += report.rows.len() as u32;
+                for (repo, _) in &report.rows {
+                    if !(args.skip_empty && repo.is_empty()) {
+                        grand_total.update(repo);
                     }
-                    let stars_n = repo.stargazers_count.unwrap();
-                    let forks_n = repo.forks_count.unwrap();
-                    table.add_row(vec![
-                        repo.name,
-                        stars_n.to_string(),
-                        forks_n.to_string(),
-                        repo.license.map(|l| l.name).unwrap_or("".to_string()),
-                        repo.watchers_count.unwrap().to_string(),
-                        repo.updated_at.unwrap().to_string(),
-                        repo.pushed_at.unwrap().to_string(),
-                        repo.open_issues_count.unwrap().to_string(),
-                        repo.size.unwrap().to_string(),
-                        repo.created_at.unwrap().year().to_string(),
-                    ]);
-                    sum_stats.update(
-                        stars_n,
-                        forks_n,
-                        repo.watchers_count.unwrap(),
-                        repo.updated_at.unwrap(),
-                        repo.pushed_at.unwrap(),
-                        repo.open_issues_count.unwrap(),
-                        repo.size.unwrap(),
+                }
+                for sink in sinks.iter_mut() {
+                    sink.org(&ctx, &report)?;
+                }
+                if let (Some(state), Some(path)) = (resume.as_mut(), &args.resume) {
+                    state.completed.push(org_name.clone());
+                    state.repos = run.repos;
+                    state.total = grand_total.clone();
+                    state.save(path)?;
+                }
+                if let Some(violations) = &report.violations {
+                    policy_violations += violations.count();
+                }
+                if args.require_license {
+                    unlicensed.extend(
+                        report
+                            .rows
+                            .iter()
+                            .filter(|(repo, _)| repo.license.is_none())
+                            .map(|(repo, _)| format!("{org_name}/{}", repo.name)),
+                    );
+                }
+                if let Some(n) = args.movers {
+                    let previous = history::latest_before(&history, &org_name, now);
+                    let repos: Vec<&RepoStats> = report.rows.iter().map(|(repo, _)| repo).collect();
+                    if let Some(table) = previous.and_then(|p| movers::table(&repos, p, n)) {
+                        println!("{table}");
+                    }
+                }
+                if args.forecast {
+                    if let Some(table) = forecast::table(&org_name, &report.stats, &history, now) {
+                        println!("{table}");
+                    }
+                }
+                if !config.alerts.is_empty() {
+                    transitions.extend(alert_state.evaluate(
+                        &config.alerts,
+                        &config.silences,
+                        &org_name,
+                        &report.stats,
+                        &history,
+                        now,
+                    )?);
+                }
+                if let Some(period) = args.period {
+                    let period_report =
+                        period::report(period, &org_name, &report.stats, &history, now);
+                    println!("{period_report}");
+                }
+                if let Some(baseline) = &ctx.baseline {
+                    match baseline.table(&report.name, &report.stats, columns, &units) {
+                        Some(table) => println!("{table}"),
+                        None => println!("No baseline row for {}", report.name),
+                    }
+                    regressions.extend(baseline.regressions(
+                        &ctx.regressions,
+                        &report.name,
+                        &report.stats,
+                        &units,
+                    ));
+                }
+                if let Some(dir) = &args.collect_licenses {
+                    notices.extend(licenses::collect(&ctx, &report, dir).await?);
+                }
+                let repos: Vec<&RepoStats> = report.rows.iter().map(|(repo, _)| repo).collect();
+                gate_failures.extend(gate::check(args, &org_name, &repos, now));
+                // Replayed dumps would record stale data as a fresh snapshot, and a
+                // shard, the pinned or the filtered repositories only part of the organization
+                if ctx.offline.is_none()
+                    && ctx.shard.is_none()
+                    && !args.pinned_only
+                    && !args.filters_repos()
+                {
+                    let snapshot = Snapshot {
+                        collected_at: now,
+                        org: org_name.clone(),
+                        stats: report.stats,
+                        repos: report
+                            .rows
+                            .iter()
+                            .map(|(repo, _)| RepoSnapshot::from_repo(repo))
+                            .collect(),
+                    };
+                    // The organization is written but missing from the history, so the
+                    // run ends as timed out after it
+                    tokio::select! {
+                        saved = store.append(&snapshot) => saved?,
+                        _ = expired(deadline) => {
+                            timed_out = true;
+                            interrupted_at = Some(index + 1);
+                            break;
+                        }
+                    }
+                }
+                if args.leaderboard.is_some() {
+                    leaderboard_repos.extend(
+                        report
+                            .rows
+                            .into_iter()
+                            .map(|(repo, _)| (org_name.clone(), repo)),
                     );
                 }
-                let real_org_name = org_info.name.unwrap_or(org_name);
-                let org_created_at = org_info.created_at.unwrap().year();
-                table.set_header(vec![
-                    Cell::new(format!("{} [{}]", real_org_name, org_created_at,)).fg(Color::Green),
-                    Cell::new(format!("Sum: {}", sum_stats.stars)),
-                    Cell::new(format!("Sum: {}", sum_stats.forks)),
-                    Cell::new(""),
-                    Cell::new(format!("Sum: {}", sum_stats.followers)),
-                    Cell::new(format!("Latest: {}", sum_stats.updated_at)),
-                    Cell::new(format!("Latest: {}", sum_stats.pushed_at)),
-                    Cell::new(format!("Sum: {}", sum_stats.open_issues_count)),
-                    Cell::new(format!("Sum: {}", sum_stats.size)),
-                ]);
-                println!("{table}");
-                out_file
-                    .write_all(
-                        format!(
-                            "{},{},{},{},{},{},{},{},{}\n",
-                            real_org_name,
-                            org_created_at,
-                            sum_stats.stars,
-                            sum_stats.forks,
-                            sum_stats.followers,
-                            sum_stats.updated_at,
-                            sum_stats.pushed_at,
-                            sum_stats.open_issues_count,
-                            sum_stats.size
-                        )
-                        .as_bytes(),
-                    )
-                    .await
-                    .expect("write csv row ok");
             }
             Err(e) => {
-                println!("Organization {org_name} not found {e}");
+                run.failed_orgs += 1;
+                let failure = Failure::new(&org_name, e.to_string());
+                let kind = match failure.reason {
+                    Reason::NotFound => Kind::OrgNotFound,
+                    _ => Kind::OrgFailed,
+                };
+                ctx.diagnostics
+                    .emit(kind, Some(&org_name), None, &failure.message);
+                for sink in sinks.iter_mut() {
+                    sink.failed(&ctx, &failure)?;
+                }
+                failures.push(failure);
             }
         }
     }
+    if let Some(index) = interrupted_at {
+        tracing::warn!(
+            "{}, {} organizations completed, not scanned: {}",
+            if timed_out {
+                "Timed out"
+            } else {
+                "Interrupted"
+            },
+            run.orgs,
+            orgs[index..].join(", ")
+        );
+        match &args.resume {
+            Some(path) => tracing::warn!("Continue with --resume {}", path.display()),
+            None => tracing::warn!("Pass --resume FILE to be able to continue an interrupted scan"),
+        }
+    } else if let Some(path) = &args.resume {
+        resume::clear(path)?;
+    }
+    // A resumed scan writes the total once every organization is in
+    if run.orgs > 1 && interrupted_at.is_none() {
+        for sink in sinks.iter_mut() {
+            sink.total(&ctx, run.orgs, run.repos, &grand_total)?;
+        }
+    }
+    run.metrics_skipped = ctx.diagnostics.degraded();
+    run.api_calls = ctx.github.requests();
+    run.warnings = ctx.diagnostics.warnings();
+    run.finish(ctx.enrichments.count());
+    if let (Some(path), Some(dump)) = (&args.dump_raw, &ctx.dump) {
+        dump::save(path, &dump.lock().expect("dump lock ok"))?;
+    }
+    if let (Some(path), Some(dump), Some(cache)) = (&args.incremental, &ctx.dump, &ctx.cache) {
+        dump::update_cache(path, &dump.lock().expect("dump lock ok"), cache)?;
+    }
+    if let Some(dir) = &args.collect_licenses {
+        licenses::write_notice(dir, &notices)?;
+    }
+    if let Some(n) = args.leaderboard {
+        let keys = match args.sort.is_empty() {
+            true => vec![Column::Stars],
+            false => args.sort.clone(),
+        };
+        println!(
+            "{}",
+            leaderboard::table(leaderboard_repos, n, &keys, columns, &style)
+        );
+    }
+    if !config.alerts.is_empty() {
+        for transition in &transitions {
+            let kind = if transition.firing {
+                Kind::AlertFiring
+            } else {
+                Kind::AlertResolved
+            };
+            ctx.diagnostics.emit(
+                kind,
+                Some(&transition.org),
+                None,
+                &format!("{} (value {})", transition.rule, transition.value),
+            );
+        }
+        if !transitions.is_empty() {
+            println!("{}", alerts::table(&transitions));
+        }
+        alert_state.save(alerts::STATE_PATH)?;
+    }
+    if ctx.usage.graphql().queries > 0 && !owns_stdout && !args.quiet {
+        println!("{}", ctx.usage.table());
+    }
+    if ctx.github.tokens() > 1 && !owns_stdout && !args.quiet {
+        println!("{}", usage::token_table(&ctx.github.token_usage().await));
+    }
+    run.save()?;
+    if args.perf_stats {
+        perf::append(&perf::Run::new(
+            &ctx,
+            started_at,
+            started.elapsed(),
+            run.orgs,
+            run.repos,
+        ))?;
+    }
+    for sink in sinks.iter_mut() {
+        sink.finish(&run)?;
+    }
+    if !failures.is_empty() && !owns_stdout && !args.quiet {
+        println!("{}", failures::table(&failures));
+    }
+    if let (true, Some(index)) = (timed_out, interrupted_at) {
+        tracing::error!("Timed out with {} organizations left", orgs.len() - index);
+        std::process::exit(TIMEOUT_EXIT_CODE);
+    }
+    if let Some(index) = interrupted_at {
+        return Err(format!(
+            "Interrupted with {} organizations left",
+            orgs.len() - index
+        ));
+    }
+    ctx.diagnostics.check_orgs()?;
+    if policy_violations > 0 {
+        return Err(format!("{policy_violations} repository policy violations"));
+    }
+    if !unlicensed.is_empty() {
+        return Err(format!(
+            "{} repositories without a license: {}",
+            unlicensed.len(),
+            unlicensed.join(", ")
+        ));
+    }
+    if !gate_failures.is_empty() {
+        return Err(format!(
+            "{} hygiene threshold violations: {}",
+            gate_failures.len(),
+            gate_failures.join(", ")
+        ));
+    }
+    if !regressions.is_empty() {
+        return Err(format!(
+            "{} regressions against the baseline: {}",
+            regressions.len(),
+            regressions.join(", ")
+        ));
+    }
     Ok(())
 }
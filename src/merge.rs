@@ -0,0 +1,21 @@
+use std::collections::BTreeMap;
+
+use comfy_table::{Cell, Color, Table};
+
+use crate::render;
+
+pub fn table(configs: &BTreeMap<String, u32>) -> Table {
+    let mut configs: Vec<(&String, &u32)> = configs.iter().collect();
+    configs.sort_by(|a, b| b.1.cmp(a.1));
+
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Merge settings").fg(Color::Green),
+        Cell::new("Repositories").fg(Color::Green),
+    ]);
+    for (i, (config, count)) in configs.into_iter().enumerate() {
+        let color = if i == 0 { Color::Reset } else { Color::Yellow };
+        table.add_row(vec![Cell::new(config).fg(color), Cell::new(count)]);
+    }
+    table
+}
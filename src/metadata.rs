@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde_json::Value;
+
+const KEY_COLUMNS: [&str; 3] = ["repo", "repository", "name"];
+
+pub struct Metadata {
+    pub columns: Vec<String>,
+    rows: HashMap<String, HashMap<String, String>>,
+}
+
+impl Metadata {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let is_json = path
+            .extension()
+            .map(|ext| ext.eq_ignore_ascii_case("json"))
+            .unwrap_or(false);
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read metadata file {}: {e}", path.display()))?;
+        if is_json {
+            Self::from_json(&content)
+        } else {
+            Self::from_csv(&content)
+        }
+    }
+
+    fn from_csv(content: &str) -> Result<Self, String> {
+        let mut reader = csv::Reader::from_reader(content.as_bytes());
+        let headers = reader
+            .headers()
+            .map_err(|e| format!("Invalid metadata CSV header: {e}"))?
+            .clone();
+        let key_index = headers
+            .iter()
+            .position(|h| KEY_COLUMNS.contains(&h.to_lowercase().as_str()))
+            .ok_or("Metadata CSV needs a repo, repository or name column")?;
+
+        let mut metadata = Self {
+            columns: headers
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != key_index)
+                .map(|(_, h)| h.to_string())
+                .collect(),
+            rows: HashMap::new(),
+        };
+        for record in reader.records() {
+            let record = record.map_err(|e| format!("Invalid metadata CSV row: {e}"))?;
+            let fields = headers
+                .iter()
+                .zip(record.iter())
+                .enumerate()
+                .filter(|(i, _)| *i != key_index)
+                .map(|(_, (h, v))| (h.to_string(), v.to_string()))
+                .collect();
+            metadata
+                .rows
+                .insert(record[key_index].to_lowercase(), fields);
+        }
+        Ok(metadata)
+    }
+
+    fn from_json(content: &str) -> Result<Self, String> {
+        let value: Value =
+            serde_json::from_str(content).map_err(|e| format!("Invalid metadata JSON: {e}"))?;
+        let entries: Vec<(String, &serde_json::Map<String, Value>)> = match &value {
+            Value::Array(items) => items
+                .iter()
+                .filter_map(|item| {
+                    let object = item.as_object()?;
+                    let key = KEY_COLUMNS
+                        .iter()
+                        .find_map(|k| object.get(*k).and_then(Value::as_str))?;
+                    Some((key.to_string(), object))
+                })
+                .collect(),
+            Value::Object(map) => map
+                .iter()
+                .filter_map(|(key, item)| Some((key.clone(), item.as_object()?)))
+                .collect(),
+            _ => return Err("Metadata JSON must be an array or an object".to_string()),
+        };
+
+        let mut metadata = Self {
+            columns: vec![],
+            rows: HashMap::new(),
+        };
+        for (key, object) in entries {
+            let mut fields = HashMap::new();
+            for (column, value) in object {
+                if KEY_COLUMNS.contains(&column.as_str()) {
+                    continue;
+                }
+                if !metadata.columns.contains(column) {
+                    metadata.columns.push(column.clone());
+                }
+                let value = match value {
+                    Value::String(s) => s.clone(),
+                    Value::Null => "".to_string(),
+                    other => other.to_string(),
+                };
+                fields.insert(column.clone(), value);
+            }
+            metadata.rows.insert(key.to_lowercase(), fields);
+        }
+        Ok(metadata)
+    }
+
+    pub fn get(&self, org: &str, repo: &str) -> Option<&HashMap<String, String>> {
+        self.rows
+            .get(&format!("{org}/{repo}").to_lowercase())
+            .or_else(|| self.rows.get(&repo.to_lowercase()))
+    }
+
+    pub fn values(&self, org: &str, repo: &str) -> Vec<String> {
+        let fields = self.get(org, repo);
+        self.columns
+            .iter()
+            .map(|column| {
+                fields
+                    .and_then(|f| f.get(column))
+                    .cloned()
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+
+use crate::agg;
+use crate::columns::{self, CellValue, Column};
+use crate::format::Style;
+use crate::scan::{Context, OrgReport};
+use crate::stats::{RepoStats, SumStats};
+
+// One report cell, a column of a repository or a total: the text shown in tables,
+// the unformatted text of CSV files and the typed value of xlsx cells
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Metric {
+    pub column: Column,
+    pub text: String,
+    pub raw: String,
+    pub value: CellValue,
+    pub approximate: bool,
+}
+
+impl Metric {
+    pub fn repo(column: Column, repo: &RepoStats, style: &Style) -> Self {
+        Self {
+            column,
+            text: column.value(repo, style),
+            raw: column.value(repo, &Style::raw(style.now, style.units)),
+            value: column.typed_value(repo, style),
+            approximate: column.is_approximate(repo),
+        }
+    }
+
+    pub fn total(column: Column, stats: &SumStats, style: &Style) -> Self {
+        Self {
+            column,
+            text: column.summary(stats, style),
+            raw: column.csv_value(stats, &style.units),
+            value: column.typed_summary(stats, &style.units),
+            approximate: false,
+        }
+    }
+}
+
+// The collected fields of a repository in `stats`, and its report cells in `metrics`
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RepoMetrics {
+    pub stats: RepoStats,
+    pub metrics: Vec<Metric>,
+    pub urls: [String; 3],
+}
+
+impl RepoMetrics {
+    pub fn new(repo: &RepoStats, columns: &[Column], style: &Style) -> Self {
+        Self {
+            stats: repo.clone(),
+            metrics: columns
+                .iter()
+                .map(|column| Metric::repo(*column, repo, style))
+                .collect(),
+            urls: repo.urls().map(str::to_string),
+        }
+    }
+
+    // The export fields of the approximate metrics, joined for a single cell
+    pub fn approximate_fields(&self) -> String {
+        self.metrics
+            .iter()
+            .filter(|metric| metric.approximate)
+            .map(|metric| metric.column.csv_field().unwrap_or(metric.column.title()))
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+}
+
+// The organization totals in `stats`, and their report cells in `totals`
+#[derive(Clone, Serialize, Deserialize)]
+pub struct OrgMetrics {
+    pub org: String,
+    pub name: String,
+    pub created_year: i32,
+    pub stats: SumStats,
+    pub repos: Vec<RepoMetrics>,
+    pub totals: Vec<Metric>,
+}
+
+impl OrgMetrics {
+    // Totals follow --agg where it is set, and are approximate when any repository is
+    pub fn new(ctx: &Context, report: &OrgReport, columns: &[Column], style: &Style) -> Self {
+        let stats: Vec<&RepoStats> = report.rows.iter().map(|(repo, _)| repo).collect();
+        let repos: Vec<RepoMetrics> = stats
+            .iter()
+            .map(|repo| RepoMetrics::new(repo, columns, style))
+            .collect();
+        let totals = columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let mut total = Metric::total(*column, &report.stats, style);
+                if let Some(agg) = agg::find(&ctx.aggregations, *column) {
+                    total.text = agg::summary(*column, agg, &stats, style);
+                    total.raw =
+                        agg::value(*column, agg, &stats, &Style::raw(style.now, style.units));
+                    total.value = columns::typed(total.raw.clone());
+                }
+                total.approximate = repos.iter().any(|repo| repo.metrics[i].approximate);
+                total
+            })
+            .collect();
+        Self {
+            org: report.org.clone(),
+            name: report.name.clone(),
+            created_year: report.created_year,
+            stats: report.stats.clone(),
+            repos,
+            totals,
+        }
+    }
+}
+
+pub fn totals(stats: &SumStats, columns: &[Column], style: &Style) -> Vec<Metric> {
+    columns
+        .iter()
+        .map(|column| Metric::total(*column, stats, style))
+        .collect()
+}
@@ -0,0 +1,40 @@
+use comfy_table::{Cell, Color, Table};
+
+use crate::format::Style;
+use crate::render;
+use crate::scan::OrgReport;
+
+pub fn table(report: &OrgReport, style: &Style) -> Table {
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Repository").fg(Color::Green),
+        Cell::new("Milestone").fg(Color::Green),
+        Cell::new("Due").fg(Color::Green),
+        Cell::new("Open issues").fg(Color::Green),
+        Cell::new("Closed issues").fg(Color::Green),
+        Cell::new("Complete").fg(Color::Green),
+    ]);
+    for (repo, _) in &report.rows {
+        for milestone in repo.milestones.iter().flatten() {
+            let overdue = milestone.due_on.is_some_and(|due| due < style.now);
+            let due = milestone
+                .due_on
+                .map(|due| due.format("%Y-%m-%d").to_string())
+                .unwrap_or_default();
+            table.add_row(vec![
+                Cell::new(&repo.name),
+                Cell::new(&milestone.title),
+                Cell::new(due).fg(if overdue { Color::Red } else { Color::Reset }),
+                Cell::new(milestone.open_issues),
+                Cell::new(milestone.closed_issues),
+                Cell::new(
+                    milestone
+                        .completion()
+                        .map(|pct| format!("{}%", style.units.ratio(pct)))
+                        .unwrap_or_default(),
+                ),
+            ]);
+        }
+    }
+    table
+}
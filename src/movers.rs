@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use comfy_table::{Cell, Color, Table};
+
+use crate::history::{RepoSnapshot, Snapshot};
+use crate::period::percent_change;
+use crate::render;
+use crate::stats::RepoStats;
+
+struct Mover<'a> {
+    repo: &'a RepoStats,
+    previous: &'a RepoSnapshot,
+    stars: i64,
+    issues: i64,
+}
+
+pub fn table(repos: &[&RepoStats], previous: &Snapshot, n: usize) -> Option<Table> {
+    let before: HashMap<&str, &RepoSnapshot> = previous
+        .repos
+        .iter()
+        .map(|r| (r.name.as_str(), r))
+        .collect();
+    let mut movers: Vec<Mover> = repos
+        .iter()
+        .filter_map(|repo| {
+            let previous = *before.get(repo.name.as_str())?;
+            Some(Mover {
+                repo,
+                previous,
+                stars: repo.stars as i64 - previous.stars as i64,
+                issues: repo.open_issues_count as i64 - previous.open_issues as i64,
+            })
+        })
+        .filter(|m| m.stars != 0 || m.issues != 0)
+        .collect();
+    if movers.is_empty() {
+        return None;
+    }
+    movers.sort_by(|a, b| {
+        b.stars
+            .abs()
+            .cmp(&a.stars.abs())
+            .then_with(|| b.issues.abs().cmp(&a.issues.abs()))
+            .then_with(|| a.repo.name.cmp(&b.repo.name))
+    });
+
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new(format!(
+            "Movers since {}",
+            previous.collected_at.format("%Y-%m-%d %H:%M")
+        ))
+        .fg(Color::Green),
+        Cell::new("Stars").fg(Color::Green),
+        Cell::new("Change").fg(Color::Green),
+        Cell::new("Open issues").fg(Color::Green),
+        Cell::new("Change").fg(Color::Green),
+    ]);
+    for mover in movers.into_iter().take(n) {
+        table.add_row(vec![
+            mover.repo.name.clone(),
+            mover.repo.stars.to_string(),
+            format!(
+                "{:+} ({})",
+                mover.stars,
+                percent_change(mover.repo.stars.into(), mover.previous.stars.into())
+            ),
+            mover.repo.open_issues_count.to_string(),
+            format!(
+                "{:+} ({})",
+                mover.issues,
+                percent_change(
+                    mover.repo.open_issues_count.into(),
+                    mover.previous.open_issues.into(),
+                )
+            ),
+        ]);
+    }
+    Some(table)
+}
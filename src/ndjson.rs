@@ -0,0 +1,71 @@
+use serde::Serialize;
+use serde_json::json;
+
+use crate::failures::Reason;
+use crate::metrics::{Metric, RepoMetrics};
+use crate::scan::OrgReport;
+use crate::schema::SCHEMA_VERSION;
+use crate::stats::SumStats;
+use crate::summary::RunSummary;
+
+fn emit(record: impl Serialize) {
+    println!(
+        "{}",
+        serde_json::to_string(&record).expect("serialize ndjson record ok")
+    );
+}
+
+pub fn repo(org: &str, repo: &RepoMetrics) {
+    emit(json!({
+        "type": "repo",
+        "schema_version": SCHEMA_VERSION,
+        "org": org,
+        "repo": repo.stats,
+        "metrics": repo.metrics,
+    }));
+}
+
+pub fn org(report: &OrgReport, totals: &[Metric]) {
+    emit(json!({
+        "type": "org",
+        "schema_version": SCHEMA_VERSION,
+        "org": report.org,
+        "name": report.name,
+        "created_year": report.created_year,
+        "repos": report.rows.len(),
+        "unavailable": report.unavailable.len(),
+        "members": report.members,
+        "profile": report.profile,
+        "stats": report.stats,
+        "totals": totals,
+    }));
+}
+
+pub fn failure(org: &str, reason: Reason, error: &str) {
+    emit(json!({
+        "type": "error",
+        "schema_version": SCHEMA_VERSION,
+        "org": org,
+        "reason": reason,
+        "error": error,
+    }));
+}
+
+pub fn total(orgs: u32, repos: u32, stats: &SumStats, totals: &[Metric]) {
+    emit(json!({
+        "type": "total",
+        "schema_version": SCHEMA_VERSION,
+        "orgs": orgs,
+        "repos": repos,
+        "stats": stats,
+        "totals": totals,
+    }));
+}
+
+pub fn run(run: &RunSummary) {
+    emit(json!({
+        "type": "run",
+        "schema_version": SCHEMA_VERSION,
+        "summary": run,
+    }));
+}
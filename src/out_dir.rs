@@ -0,0 +1,51 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::columns::Column;
+use crate::metrics::RepoMetrics;
+use crate::stats::{APPROXIMATE_FIELD, URL_FIELDS};
+
+pub fn org_path(dir: &Path, org: &str, extension: &str) -> PathBuf {
+    dir.join(format!("{org}.{extension}"))
+}
+
+pub fn write_csv(
+    path: &Path,
+    repos: &[RepoMetrics],
+    columns: &[Column],
+    custom: &[String],
+    delimiter: u8,
+    bom: bool,
+) -> Result<(), String> {
+    let error = |e: &dyn std::fmt::Display| format!("Cannot write {}: {e}", path.display());
+    let mut file = File::create(path).map_err(|e| error(&e))?;
+    if bom {
+        file.write_all("\u{feff}".as_bytes())
+            .map_err(|e| error(&e))?;
+    }
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(file);
+    let header = ["repository"]
+        .into_iter()
+        .chain(columns.iter().map(|c| c.csv_field().unwrap_or(c.title())))
+        .chain(custom.iter().map(String::as_str))
+        .chain(URL_FIELDS)
+        .chain([APPROXIMATE_FIELD]);
+    writer.write_record(header).map_err(|e| error(&e))?;
+    for repo in repos {
+        let row = [repo.stats.name.clone()]
+            .into_iter()
+            .chain(repo.metrics.iter().map(|metric| metric.raw.clone()))
+            .chain(
+                custom
+                    .iter()
+                    .map(|name| repo.stats.custom.get(name).cloned().unwrap_or_default()),
+            )
+            .chain(repo.urls.iter().cloned())
+            .chain([repo.approximate_fields()]);
+        writer.write_record(row).map_err(|e| error(&e))?;
+    }
+    writer.flush().map_err(|e| error(&e))
+}
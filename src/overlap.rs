@@ -0,0 +1,89 @@
+use std::collections::HashSet;
+
+use comfy_table::{Cell, Color};
+
+use crate::graphql;
+use crate::render;
+use crate::scan::{self, Context};
+
+// Stargazers are sampled from the most starred repositories, newest stars first
+async fn sample(
+    ctx: &Context,
+    org: &str,
+    repos: usize,
+    size: usize,
+) -> Result<HashSet<String>, String> {
+    let listing = scan::rest_listing(ctx, org)
+        .await
+        .map_err(|e| format!("Cannot list {org}: {e}"))?;
+    let mut starred: Vec<(String, u32)> = listing
+        .repos
+        .into_iter()
+        .filter_map(|repo| repo.stats.ok())
+        .filter(|stats| stats.stars > 0)
+        .map(|stats| (stats.name, stats.stars))
+        .collect();
+    starred.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    starred.truncate(repos);
+    let mut logins = HashSet::new();
+    for (index, (name, _)) in starred.iter().enumerate() {
+        // Repositories with fewer stargazers leave their share to the next ones
+        let remaining = starred.len() - index;
+        let share = (size.saturating_sub(logins.len()) + remaining - 1) / remaining;
+        if share == 0 {
+            break;
+        }
+        for login in graphql::stargazer_logins(&ctx.github, org, name, share).await? {
+            logins.insert(login);
+        }
+    }
+    Ok(logins)
+}
+
+fn percent(part: usize, whole: usize) -> String {
+    match whole {
+        0 => "-".to_string(),
+        _ => format!("{:.1}%", part as f64 / whole as f64 * 100.0),
+    }
+}
+
+pub async fn run(
+    ctx: &Context,
+    left: &str,
+    right: &str,
+    repos: usize,
+    size: usize,
+) -> Result<(), String> {
+    let (left_sample, right_sample) = futures::join!(
+        sample(ctx, left, repos, size),
+        sample(ctx, right, repos, size)
+    );
+    let (left_sample, right_sample) = (left_sample?, right_sample?);
+    let shared = left_sample.intersection(&right_sample).count();
+    let union = left_sample.len() + right_sample.len() - shared;
+
+    let mut table = render::new_table();
+    table.set_header(
+        [
+            "Organization",
+            "Sampled stargazers",
+            "Also starring the other",
+            "Overlap",
+        ]
+        .map(|title| Cell::new(title).fg(Color::Green)),
+    );
+    for (org, sample) in [(left, &left_sample), (right, &right_sample)] {
+        table.add_row(vec![
+            Cell::new(org),
+            Cell::new(sample.len()),
+            Cell::new(shared),
+            Cell::new(percent(shared, sample.len())),
+        ]);
+    }
+    println!("{table}");
+    println!(
+        "{shared} shared stargazers, {} of both audiences combined",
+        percent(shared, union)
+    );
+    Ok(())
+}
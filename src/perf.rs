@@ -0,0 +1,142 @@
+use std::io::Write;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use comfy_table::{Cell, Color, Table};
+use serde::{Deserialize, Serialize};
+
+use crate::render;
+use crate::scan::Context;
+
+pub const PERF_PATH: &str = "out/perf.ndjson";
+
+#[derive(Serialize, Deserialize)]
+pub struct Run {
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub orgs: u32,
+    pub repos: u32,
+    pub api_calls: u32,
+    #[serde(default)]
+    pub graphql_points: u32,
+    #[serde(default)]
+    pub cache_hits: u32,
+    #[serde(default)]
+    pub cache_misses: u32,
+    #[serde(default)]
+    pub enrichments: u32,
+    #[serde(default)]
+    pub parallelism: usize,
+}
+
+impl Run {
+    pub fn new(
+        ctx: &Context,
+        started_at: DateTime<Utc>,
+        elapsed: Duration,
+        orgs: u32,
+        repos: u32,
+    ) -> Self {
+        let (cache_hits, cache_misses) = ctx.usage.cache();
+        Self {
+            started_at,
+            duration_ms: elapsed.as_millis() as u64,
+            orgs,
+            repos,
+            api_calls: ctx.github.requests(),
+            graphql_points: ctx.usage.graphql().consumed,
+            cache_hits,
+            cache_misses,
+            enrichments: ctx.enrichments.count(),
+            parallelism: ctx.args.parallelism,
+        }
+    }
+
+    fn cache_rate(&self) -> Option<f64> {
+        let lookups = self.cache_hits + self.cache_misses;
+        (lookups > 0).then(|| self.cache_hits as f64 / lookups as f64 * 100.0)
+    }
+
+    fn calls_per_repo(&self) -> f64 {
+        self.api_calls as f64 / self.repos.max(1) as f64
+    }
+}
+
+pub fn append(run: &Run) -> Result<(), String> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(PERF_PATH)
+        .map_err(|e| format!("Cannot open {PERF_PATH}: {e}"))?;
+    let mut line = serde_json::to_string(run).expect("serialize perf run ok");
+    line.push('\n');
+    file.write_all(line.as_bytes())
+        .map_err(|e| format!("Cannot write {PERF_PATH}: {e}"))
+}
+
+pub fn load() -> Result<Vec<Run>, String> {
+    let content = std::fs::read_to_string(PERF_PATH).map_err(|e| {
+        format!("Cannot read {PERF_PATH}: {e}; record runs with --perf-stats first")
+    })?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+fn row(label: String, duration_ms: f64, run: &Run) -> Vec<String> {
+    vec![
+        label,
+        format!("{:.1}s", duration_ms / 1000.0),
+        run.orgs.to_string(),
+        run.repos.to_string(),
+        run.api_calls.to_string(),
+        format!("{:.1}", run.calls_per_repo()),
+        run.cache_rate()
+            .map(|rate| format!("{rate:.0}%"))
+            .unwrap_or("-".to_string()),
+        run.enrichments.to_string(),
+        run.parallelism.to_string(),
+    ]
+}
+
+pub fn report(runs: &[Run], last: usize) -> Table {
+    let mut table = render::new_table();
+    table.set_header(
+        [
+            "Run",
+            "Duration",
+            "Orgs",
+            "Repos",
+            "API calls",
+            "Calls/repo",
+            "Cache hits",
+            "Enrichments",
+            "Parallelism",
+        ]
+        .map(|title| Cell::new(title).fg(Color::Green)),
+    );
+    let shown = &runs[runs.len().saturating_sub(last)..];
+    for run in shown {
+        let label = run.started_at.format("%Y-%m-%d %H:%M").to_string();
+        table.add_row(row(label, run.duration_ms as f64, run));
+    }
+    if shown.len() > 1 {
+        let n = shown.len() as u32;
+        let average = Run {
+            started_at: Utc::now(),
+            duration_ms: 0,
+            orgs: shown.iter().map(|r| r.orgs).sum::<u32>() / n,
+            repos: shown.iter().map(|r| r.repos).sum::<u32>() / n,
+            api_calls: shown.iter().map(|r| r.api_calls).sum::<u32>() / n,
+            graphql_points: 0,
+            cache_hits: shown.iter().map(|r| r.cache_hits).sum(),
+            cache_misses: shown.iter().map(|r| r.cache_misses).sum(),
+            enrichments: shown.iter().map(|r| r.enrichments).sum::<u32>() / n,
+            parallelism: shown.iter().map(|r| r.parallelism).sum::<usize>() / n as usize,
+        };
+        let duration = shown.iter().map(|r| r.duration_ms as f64).sum::<f64>() / n as f64;
+        table.add_row(row("Average".to_string(), duration, &average));
+    }
+    table
+}
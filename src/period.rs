@@ -0,0 +1,93 @@
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use clap::ValueEnum;
+use comfy_table::{Cell, Color, Table};
+use serde::Deserialize;
+
+use crate::history::{self, Snapshot};
+use crate::render;
+use crate::stats::SumStats;
+
+#[derive(Clone, Copy, Debug, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    Quarterly,
+}
+
+impl Period {
+    pub fn start_of(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Period::Quarterly => {
+                let month = at.month0() / 3 * 3 + 1;
+                Utc.with_ymd_and_hms(at.year(), month, 1, 0, 0, 0).unwrap()
+            }
+        }
+    }
+
+    pub fn label(&self, at: DateTime<Utc>) -> String {
+        match self {
+            Period::Quarterly => format!("Q{} {}", at.month0() / 3 + 1, at.year()),
+        }
+    }
+}
+
+pub fn percent_change(current: u64, previous: u64) -> String {
+    if previous == 0 {
+        return "n/a".to_string();
+    }
+    let change = (current as f64 - previous as f64) / previous as f64 * 100.0;
+    format!("{:+.1}%", change)
+}
+
+pub fn report(
+    period: Period,
+    org: &str,
+    current: &SumStats,
+    history: &[Snapshot],
+    now: DateTime<Utc>,
+) -> Table {
+    let previous = history::latest_before(history, org, period.start_of(now));
+
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Metric").fg(Color::Green),
+        Cell::new(period.label(now)).fg(Color::Green),
+        Cell::new(
+            previous
+                .map(|s| period.label(s.collected_at))
+                .unwrap_or("Previous".to_string()),
+        )
+        .fg(Color::Green),
+        Cell::new("Change").fg(Color::Green),
+    ]);
+
+    let metrics: [(&str, fn(&SumStats) -> u64); 5] = [
+        ("Stars", |s| s.stars as u64),
+        ("Forks", |s| s.forks as u64),
+        ("Followers", |s| s.followers as u64),
+        ("Open issues", |s| s.open_issues_count as u64),
+        ("Size", |s| s.size),
+    ];
+    for (name, metric) in metrics {
+        let current_value = metric(current);
+        match previous {
+            Some(snapshot) => {
+                let previous_value = metric(&snapshot.stats);
+                table.add_row(vec![
+                    name.to_string(),
+                    current_value.to_string(),
+                    previous_value.to_string(),
+                    percent_change(current_value, previous_value),
+                ]);
+            }
+            None => {
+                table.add_row(vec![
+                    name.to_string(),
+                    current_value.to_string(),
+                    "".to_string(),
+                    "n/a".to_string(),
+                ]);
+            }
+        }
+    }
+    table
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use comfy_table::{Cell, Color, Table};
+use serde::Deserialize;
+
+use crate::render;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OrgPolicy {
+    pub expected: Vec<String>,
+    pub denied: Vec<String>,
+}
+
+pub struct Policy {
+    orgs: HashMap<String, OrgPolicy>,
+}
+
+pub struct Violations {
+    pub unexpected: Vec<String>,
+    pub missing: Vec<String>,
+    pub denied: Vec<String>,
+}
+
+pub fn matches(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let name = name.to_lowercase();
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        if i == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.is_empty()
+}
+
+impl Policy {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read policy file {}: {e}", path.display()))?;
+        let orgs: HashMap<String, OrgPolicy> = toml::from_str(&content)
+            .map_err(|e| format!("Invalid policy file {}: {e}", path.display()))?;
+        Ok(Self {
+            orgs: orgs
+                .into_iter()
+                .map(|(org, policy)| (org.to_lowercase(), policy))
+                .collect(),
+        })
+    }
+
+    pub fn check(&self, org: &str, repos: &[String]) -> Option<Violations> {
+        let policy = self.orgs.get(&org.to_lowercase())?;
+        let unexpected = if policy.expected.is_empty() {
+            vec![]
+        } else {
+            repos
+                .iter()
+                .filter(|repo| !policy.expected.iter().any(|p| matches(p, repo)))
+                .cloned()
+                .collect()
+        };
+        let missing = policy
+            .expected
+            .iter()
+            .filter(|p| !p.contains('*') && !repos.iter().any(|repo| matches(p, repo)))
+            .cloned()
+            .collect();
+        let denied = repos
+            .iter()
+            .filter(|repo| policy.denied.iter().any(|p| matches(p, repo)))
+            .cloned()
+            .collect();
+        Some(Violations {
+            unexpected,
+            missing,
+            denied,
+        })
+    }
+}
+
+impl Violations {
+    pub fn count(&self) -> usize {
+        self.unexpected.len() + self.missing.len() + self.denied.len()
+    }
+
+    pub fn table(&self) -> Table {
+        let mut table = render::new_table();
+        table.set_header(vec![
+            Cell::new("Policy").fg(Color::Green),
+            Cell::new("Repository").fg(Color::Green),
+        ]);
+        for (status, repos, color) in [
+            ("Unexpected", &self.unexpected, Color::Yellow),
+            ("Missing", &self.missing, Color::Red),
+            ("Denied", &self.denied, Color::Red),
+        ] {
+            for repo in repos {
+                table.add_row(vec![Cell::new(status).fg(color), Cell::new(repo)]);
+            }
+        }
+        table
+    }
+}
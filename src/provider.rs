@@ -0,0 +1,18 @@
+use futures::future::BoxFuture;
+
+use crate::stats::RepoStats;
+
+// An enrichment defined outside the crate, filling one extra column per repository.
+// Push it onto `Context::providers` before scanning; its values land in `RepoStats::custom`
+// and follow the metadata columns in the table and xlsx sheets.
+pub trait MetricProvider: Send + Sync {
+    // The column title and the key in `RepoStats::custom`
+    fn name(&self) -> &str;
+
+    // None leaves the cell empty, errors are reported as degraded metrics
+    fn enrich<'a>(
+        &'a self,
+        org: &'a str,
+        repo: &'a RepoStats,
+    ) -> BoxFuture<'a, Result<Option<String>, String>>;
+}
@@ -0,0 +1,41 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::diagnostics::Kind;
+use crate::scan::Context;
+
+// The API models as octocrab parsed them, for fields the report doesn't surface
+pub fn org(ctx: &Context, org: &str, model: &impl Serialize) {
+    write(ctx, org, Path::new("org.json"), model);
+}
+
+pub fn repo(ctx: &Context, org: &str, name: &str, model: &impl Serialize) {
+    write(
+        ctx,
+        org,
+        &Path::new("repos").join(format!("{name}.json")),
+        model,
+    );
+}
+
+fn write(ctx: &Context, org: &str, file: &Path, model: &impl Serialize) {
+    let Some(dir) = &ctx.args.raw_json else {
+        return;
+    };
+    let path = dir.join(org).join(file);
+    let content = serde_json::to_string_pretty(model).expect("serialize raw model ok");
+    let written = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, content));
+    // A missing raw file should not cost the report
+    if let Err(e) = written {
+        ctx.diagnostics.emit(
+            Kind::DegradedMetric,
+            Some(org),
+            None,
+            &format!("Cannot write {}: {e}", path.display()),
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use comfy_table::{Cell, Color};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::diagnostics::Kind;
+use crate::fetch;
+use crate::render;
+use crate::scan::{self, Context};
+use crate::stats::RepoStats;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Remediation {
+    pub description: Option<String>,
+    pub homepage: Option<String>,
+    pub topics: Vec<String>,
+    pub settings: BTreeMap<String, Value>,
+}
+
+pub struct Plan {
+    pub fields: Map<String, Value>,
+    pub topics: Option<Vec<String>>,
+}
+
+impl Plan {
+    fn describe(&self) -> String {
+        let mut changes: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(key, value)| format!("{key} = {value}"))
+            .collect();
+        if let Some(topics) = &self.topics {
+            changes.push(format!("topics = {}", topics.join(", ")));
+        }
+        changes.join("\n")
+    }
+}
+
+fn fill(template: &str, repo: &str) -> String {
+    template.replace("{repo}", repo)
+}
+
+fn is_blank(value: &Option<String>) -> bool {
+    value
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or_default()
+        .is_empty()
+}
+
+impl Remediation {
+    pub fn plan(&self, repo: &RepoStats) -> Option<Plan> {
+        let mut fields = Map::new();
+        if let Some(description) = self
+            .description
+            .as_deref()
+            .filter(|_| is_blank(&repo.description))
+        {
+            fields.insert(
+                "description".to_string(),
+                fill(description, &repo.name).into(),
+            );
+        }
+        if let Some(homepage) = self
+            .homepage
+            .as_deref()
+            .filter(|_| is_blank(&repo.homepage))
+        {
+            fields.insert("homepage".to_string(), fill(homepage, &repo.name).into());
+        }
+        let topics =
+            (repo.topics.is_empty() && !self.topics.is_empty()).then(|| self.topics.clone());
+        if fields.is_empty() && topics.is_none() {
+            return None;
+        }
+        for (key, value) in &self.settings {
+            fields.insert(key.clone(), value.clone());
+        }
+        Some(Plan { fields, topics })
+    }
+}
+
+pub async fn run(
+    ctx: &Context,
+    orgs: &[String],
+    remediation: &Remediation,
+    yes: bool,
+) -> Result<(), String> {
+    let now = Utc::now();
+    let mut failures = 0;
+    for org_name in orgs {
+        let report = match scan::scan_org(ctx, org_name, now).await {
+            Ok(report) => report,
+            Err(e) => {
+                ctx.diagnostics
+                    .emit(Kind::OrgNotFound, Some(org_name), None, &e.to_string());
+                continue;
+            }
+        };
+        let mut table = render::new_table();
+        table.set_header(vec![
+            Cell::new(&report.name).fg(Color::Green),
+            Cell::new("Changes").fg(Color::Green),
+            Cell::new("Result").fg(Color::Green),
+        ]);
+        for (repo, _) in &report.rows {
+            let Some(plan) = remediation.plan(repo) else {
+                continue;
+            };
+            let result = if !yes {
+                Cell::new("planned")
+            } else {
+                match apply(ctx, org_name, &repo.name, &plan).await {
+                    Ok(()) => Cell::new("applied").fg(Color::Green),
+                    Err(e) => {
+                        failures += 1;
+                        Cell::new(e).fg(Color::Red)
+                    }
+                }
+            };
+            table.add_row(vec![
+                Cell::new(&repo.name),
+                Cell::new(plan.describe()),
+                result,
+            ]);
+        }
+        println!("{table}");
+    }
+    if !yes {
+        println!("Dry run, pass --yes to apply the changes");
+    }
+    if failures > 0 {
+        return Err(format!("{failures} repositories could not be updated"));
+    }
+    Ok(())
+}
+
+async fn apply(ctx: &Context, org: &str, repo: &str, plan: &Plan) -> Result<(), String> {
+    if !plan.fields.is_empty() {
+        fetch::update_repo(&ctx.github, org, repo, &plan.fields)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    if let Some(topics) = &plan.topics {
+        fetch::set_topics(&ctx.github, org, repo, topics)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+
+use chrono::{DateTime, Utc};
+use comfy_table::presets::ASCII_MARKDOWN;
+use comfy_table::{Attribute, Cell, Color, ColumnConstraint, ContentArrangement, Table, Width};
+
+use crate::advisories;
+use crate::audit;
+use crate::codeowners;
+use crate::cohorts;
+use crate::columns::{Column, APPROXIMATE_MARK, APPROXIMATE_NOTE};
+use crate::community;
+use crate::deep;
+use crate::docs;
+use crate::format::Style;
+use crate::heatmap;
+use crate::labels;
+use crate::licenses;
+use crate::merge;
+use crate::metrics::{self, Metric, OrgMetrics, RepoMetrics};
+use crate::milestones;
+use crate::scan::{Context, OrgReport};
+use crate::sizes;
+use crate::stars;
+use crate::stats::{RepoStats, SumStats};
+use crate::topics;
+use crate::traceability;
+use crate::visibility;
+
+// Rows of the previous watch refresh, raw and relative to when the watch started, so
+// relative times and cut descriptions only count as changes when the data changed
+pub struct Previous {
+    now: DateTime<Utc>,
+    rows: HashMap<(String, String), Vec<String>>,
+}
+
+impl Previous {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now,
+            rows: HashMap::new(),
+        }
+    }
+
+    fn values(&self, ctx: &Context, repo: &RepoStats, extra: &[String]) -> Vec<String> {
+        row_values(ctx, repo, extra, &Style::raw(self.now, ctx.args.units()))
+    }
+}
+
+static PLAIN: AtomicBool = AtomicBool::new(false);
+static MAX_WIDTH: AtomicU16 = AtomicU16::new(DEFAULT_WIDTH);
+
+// Used when the width is not given and stdout is not a terminal
+const DEFAULT_WIDTH: u16 = 120;
+
+pub fn set_plain(plain: bool) {
+    PLAIN.store(plain, Ordering::Relaxed);
+}
+
+pub fn set_max_width(width: Option<u16>) {
+    let width = width
+        .or_else(|| Table::new().width())
+        .unwrap_or(DEFAULT_WIDTH);
+    MAX_WIDTH.store(width, Ordering::Relaxed);
+}
+
+// Every table is created here so the plain style reaches all of them
+pub fn new_table() -> Table {
+    let mut table = Table::new();
+    // A fixed width wraps the same way in a terminal, a pipe or a CI log
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_width(MAX_WIDTH.load(Ordering::Relaxed));
+    if PLAIN.load(Ordering::Relaxed) {
+        table.load_preset(ASCII_MARKDOWN);
+        table.force_no_tty();
+    }
+    table
+}
+
+pub fn row_values(ctx: &Context, repo: &RepoStats, extra: &[String], style: &Style) -> Vec<String> {
+    cells(&RepoMetrics::new(repo, &ctx.columns, style), extra)
+}
+
+fn cells(repo: &RepoMetrics, extra: &[String]) -> Vec<String> {
+    let mut row = vec![repo.stats.name.clone()];
+    row.extend(repo.metrics.iter().map(text));
+    row.extend(extra.iter().cloned());
+    row
+}
+
+fn text(metric: &Metric) -> String {
+    match metric.approximate {
+        true => format!("{}{APPROXIMATE_MARK}", metric.text),
+        false => metric.text.clone(),
+    }
+}
+
+pub fn snapshot(ctx: &Context, report: &OrgReport, previous: &mut Previous) {
+    for (repo, extra) in &report.rows {
+        let values = previous.values(ctx, repo, extra);
+        previous
+            .rows
+            .insert((report.org.clone(), repo.name.clone()), values);
+    }
+}
+
+pub fn repo_table(
+    ctx: &Context,
+    report: &OrgReport,
+    style: &Style,
+    previous: Option<&Previous>,
+) -> Table {
+    let mut table = new_table();
+    let mut header_row = vec![Cell::new("Repository").fg(Color::Green)];
+    header_row.extend(
+        ctx.columns
+            .iter()
+            .map(|column| Cell::new(column.title()).fg(Color::Green)),
+    );
+    header_row.extend(
+        ctx.metadata_columns
+            .iter()
+            .map(|column| Cell::new(column).fg(Color::Green)),
+    );
+    header_row.extend(
+        ctx.providers
+            .iter()
+            .map(|provider| Cell::new(provider.name()).fg(Color::Green)),
+    );
+    table.add_row(header_row);
+    let metrics = OrgMetrics::new(ctx, report, &ctx.columns, style);
+    for ((repo, extra), repo_metrics) in report.rows.iter().zip(&metrics.repos) {
+        let values = cells(repo_metrics, extra);
+        let before = previous.map(|p| {
+            let key = (report.org.clone(), repo.name.clone());
+            (p.rows.get(&key), p.values(ctx, repo, extra))
+        });
+        let row: Vec<Cell> = values
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let changed = match &before {
+                    Some((Some(before), current)) => before.get(i) != current.get(i),
+                    Some((None, _)) => true,
+                    None => false,
+                };
+                let mut cell = Cell::new(&value);
+                let column = i.checked_sub(1).and_then(|c| ctx.columns.get(c));
+                if let (Some(Column::Status), Some(status)) = (column, repo.status) {
+                    cell = cell.fg(status.color());
+                }
+                if let (Some(Column::Dependencies), Some(dependencies)) =
+                    (column, &repo.dependencies)
+                {
+                    if dependencies.manifests == 0 {
+                        cell = cell.fg(Color::Red);
+                    }
+                }
+                if let (Some(Column::Branches), Some(count)) = (column, repo.branch_count) {
+                    if count > ctx.args.branch_threshold() {
+                        cell = cell.fg(Color::Red);
+                    }
+                }
+                if let Some(rule) = ctx
+                    .highlights
+                    .iter()
+                    .rev()
+                    .find(|rule| Some(&rule.column) == column && rule.matches(repo, style.now))
+                {
+                    cell = cell.fg(rule.color);
+                }
+                if changed {
+                    cell.fg(Color::Yellow).add_attribute(Attribute::Bold)
+                } else {
+                    cell
+                }
+            })
+            .collect();
+        table.add_row(row);
+    }
+    let mut title = format!("{} [{}]", report.name, report.created_year);
+    if let Some(profile) = &report.profile {
+        title = format!("{title}\n{}", profile.describe());
+    }
+    if let Some(members) = &report.members {
+        title = format!("{title}\n{}", members.describe());
+    }
+    let mut header = vec![Cell::new(title).fg(Color::Green)];
+    header.extend(metrics.totals.iter().map(|total| {
+        let summary = text(total);
+        let delta = ctx.baseline.as_ref().and_then(|baseline| {
+            baseline.delta(&report.name, total.column, &report.stats, &ctx.args.units())
+        });
+        match delta {
+            Some(delta) => {
+                let cell = Cell::new(format!("{summary}\n{}", delta.arrow()));
+                match delta.color(total.column) {
+                    Some(color) => cell.fg(color),
+                    None => cell,
+                }
+            }
+            None => Cell::new(summary),
+        }
+    }));
+    table.set_header(header);
+    for (index, column) in ctx.columns.iter().enumerate() {
+        if let (Some(width), Some(table_column)) = (column.max_width(), table.column_mut(index + 1))
+        {
+            table_column.set_constraint(ColumnConstraint::UpperBoundary(Width::Fixed(width)));
+        }
+    }
+    table
+}
+
+pub fn total_table(ctx: &Context, stats: &SumStats, orgs: u32, repos: u32, style: &Style) -> Table {
+    let mut table = new_table();
+    let mut header = vec![Cell::new("Total").fg(Color::Green)];
+    header.extend(
+        ctx.columns
+            .iter()
+            .map(|column| Cell::new(column.title()).fg(Color::Green)),
+    );
+    table.set_header(header);
+    let mut row = vec![format!("{orgs} organizations\n{repos} repositories")];
+    row.extend(metrics::totals(stats, &ctx.columns, style).iter().map(text));
+    table.add_row(row);
+    table
+}
+
+pub fn print_report(ctx: &Context, report: &OrgReport, style: &Style, previous: Option<&Previous>) {
+    println!("{}", repo_table(ctx, report, style, previous));
+    if report
+        .rows
+        .iter()
+        .any(|(repo, _)| ctx.columns.iter().any(|c| c.is_approximate(repo)))
+    {
+        println!("{APPROXIMATE_NOTE}");
+    }
+    if !report.unavailable.is_empty() {
+        let mut unavailable_table = new_table();
+        unavailable_table.set_header(vec![
+            Cell::new("Unavailable repository").fg(Color::Yellow),
+            Cell::new("Reason").fg(Color::Yellow),
+        ]);
+        for (name, reason) in &report.unavailable {
+            unavailable_table.add_row(vec![name, reason]);
+        }
+        println!("{unavailable_table}");
+    }
+    if ctx.enrichments.templates && report.stats.community.checked > 0 {
+        println!("{}", community::table(&report.stats.community));
+    }
+    if ctx.args.licenses || ctx.args.require_license {
+        println!("{}", licenses::table(&report.stats.licenses));
+    }
+    if ctx.args.cohorts {
+        println!("{}", cohorts::table(report));
+    }
+    if ctx.args.topics_summary {
+        println!("{}", topics::summary(&report.stats.topics));
+    }
+    if report.stats.visibilities.len() > 1 {
+        println!("{}", visibility::table(&report.stats.visibilities));
+    }
+    if report.stats.docs.checked > 0 {
+        println!("{}", docs::table(&report.stats.docs));
+    }
+    if !report.stats.labels.is_empty() {
+        println!("{}", labels::table(&report.stats.labels));
+    }
+    if report.stats.merge_configs.len() > 1 {
+        println!("{}", merge::table(&report.stats.merge_configs));
+    }
+    if !report.stats.star_history.is_empty() {
+        println!("{}", stars::table(&report.stats.star_history, style.now));
+    }
+    if ctx.args.heatmap {
+        println!("{}", heatmap::render(&report.stats.commit_days, style.now));
+    }
+    if report.stats.size > 0
+        && (ctx.columns.contains(&Column::Size) || ctx.args.size_threshold.is_some())
+    {
+        println!("{}", sizes::table(report, style, ctx.args.size_threshold));
+    }
+    if report.rows.iter().any(|(repo, _)| repo.deep.is_some()) {
+        println!("{}", deep::largest_files_table(report, style));
+    }
+    if ctx.columns.contains(&Column::Churn) {
+        println!("{}", deep::hotspots_table(report));
+        if let Some(table) = deep::overlap_table(report) {
+            println!("{table}");
+        }
+    }
+    if report.stats.codeowners_checked > 0 {
+        println!(
+            "{}",
+            codeowners::table(
+                &report.stats.code_owners,
+                report.stats.codeowners_present,
+                report.stats.codeowners_checked
+            )
+        );
+    }
+    if report.stats.milestones_open > 0 {
+        println!("{}", milestones::table(report, style));
+    }
+    if !report.stats.advisories.is_empty() {
+        println!("{}", advisories::table(report));
+        println!("{}", advisories::yearly_table(&report.stats.advisories));
+    }
+    if report.stats.traceability_anomalies > 0 {
+        println!("{}", traceability::table(report));
+    }
+    if let Some(events) = &report.audit {
+        if events.is_empty() {
+            println!("No notable audit log events");
+        } else {
+            println!("{}", audit::table(events));
+        }
+    }
+    if let Some(groups) = &report.groups {
+        println!("{}", groups.table());
+    }
+    if let Some(violations) = &report.violations {
+        if violations.count() > 0 {
+            println!("{}", violations.table());
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::stats::SumStats;
+
+// Progress of an interrupted scan, saved after every organization
+#[derive(Serialize, Deserialize)]
+pub struct State {
+    pub completed: Vec<String>,
+    pub repos: u32,
+    pub total: SumStats,
+}
+
+impl State {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self {
+                completed: vec![],
+                repos: 0,
+                total: SumStats::new(),
+            });
+        }
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read resume state {}: {e}", path.display()))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid resume state {}: {e}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self).expect("serialize resume state ok");
+        std::fs::write(path, content)
+            .map_err(|e| format!("Cannot write resume state {}: {e}", path.display()))
+    }
+
+    pub fn is_done(&self, org: &str) -> bool {
+        self.completed.iter().any(|o| o.eq_ignore_ascii_case(org))
+    }
+}
+
+// A finished scan leaves nothing to resume
+pub fn clear(path: &Path) -> Result<(), String> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!(
+            "Cannot remove resume state {}: {e}",
+            path.display()
+        )),
+        _ => Ok(()),
+    }
+}
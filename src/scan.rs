@@ -0,0 +1,722 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Datelike, Utc};
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::agg::{self, Agg};
+use crate::anonymize;
+use crate::baseline::{Baseline, Regression};
+use crate::cli::{Cli, Command, OutputFormat, Provider};
+use crate::client::Github;
+use crate::columns::{self, Column};
+use crate::date_filter::DateFilter;
+use crate::deep::Analyzer;
+use crate::diagnostics::{Diagnostics, Kind};
+use crate::dump::{self, DumpedOrg};
+use crate::enrich::{self, Enrichments};
+use crate::fetch;
+use crate::format::Style;
+use crate::gitlab;
+use crate::graphql;
+use crate::group::Groups;
+use crate::growth::{self, LastRuns};
+use crate::health::{self, Weights};
+use crate::highlight;
+use crate::metadata::Metadata;
+use crate::metrics::RepoMetrics;
+use crate::ndjson;
+use crate::policy::{Policy, Violations};
+use crate::provider::MetricProvider;
+use crate::raw;
+use crate::render;
+use crate::shard::Shard;
+use crate::stats::{AuditEvent, Members, OrgProfile, RepoStats, SumStats};
+use crate::status::{self, Status};
+use crate::usage::Usage;
+use crate::velocity::{self, Baselines};
+use crate::watch;
+
+const STREAM_INTERVAL: Duration = Duration::from_millis(500);
+
+pub struct Context {
+    pub github: Arc<Github>,
+    pub args: Cli,
+    pub columns: Vec<Column>,
+    pub enrichments: Enrichments,
+    pub health_weights: Weights,
+    pub metadata: Option<Metadata>,
+    pub metadata_columns: Vec<String>,
+    pub group_index: Option<usize>,
+    pub policy: Option<Policy>,
+    pub diagnostics: Diagnostics,
+    pub usage: Usage,
+    pub audit_window: Option<chrono::Duration>,
+    pub offline: Option<Vec<DumpedOrg>>,
+    pub dump: Option<Mutex<Vec<DumpedOrg>>>,
+    pub status_days: [i64; 3],
+    pub highlights: Vec<highlight::Rule>,
+    pub shard: Option<Shard>,
+    pub deep: Option<Analyzer>,
+    pub cache: Option<Vec<DumpedOrg>>,
+    pub date_filter: DateFilter,
+    pub aggregations: Vec<(Column, Agg)>,
+    pub star_baselines: Baselines,
+    pub last_runs: LastRuns,
+    // Enrichments registered by library users, shown after the metadata columns
+    pub providers: Vec<Box<dyn MetricProvider>>,
+    pub baseline: Option<Baseline>,
+    pub regressions: Vec<Regression>,
+}
+
+pub struct OrgReport {
+    pub org: String,
+    pub name: String,
+    pub created_year: i32,
+    pub rows: Vec<(RepoStats, Vec<String>)>,
+    pub unavailable: Vec<(String, String)>,
+    pub stats: SumStats,
+    pub groups: Option<Groups>,
+    pub violations: Option<Violations>,
+    pub members: Option<Members>,
+    pub audit: Option<Vec<AuditEvent>>,
+    pub profile: Option<OrgProfile>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ListedRepo {
+    pub name: String,
+    pub archived: bool,
+    pub disabled: bool,
+    pub stats: Result<RepoStats, String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct OrgListing {
+    pub name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub repos: Vec<ListedRepo>,
+}
+
+impl Context {
+    pub fn new(github: Arc<Github>, args: Cli) -> Result<Self, String> {
+        let diagnostics = match &args.diagnostics {
+            Some(path) => Diagnostics::open(path)?,
+            None => Diagnostics::default(),
+        }
+        .allow_failures(args.allow_failures);
+        let metadata = match &args.metadata {
+            Some(path) => Some(Metadata::load(path)?),
+            None => None,
+        };
+        let metadata_columns = metadata
+            .as_ref()
+            .map(|m| m.columns.clone())
+            .unwrap_or_default();
+        let group_index = match &args.group_by {
+            Some(group_by) => Some(
+                metadata_columns
+                    .iter()
+                    .position(|c| c == group_by)
+                    .ok_or(format!("Unknown metadata column {group_by}"))?,
+            ),
+            None => None,
+        };
+        let policy = match &args.policy {
+            Some(path) => Some(Policy::load(path)?),
+            None => None,
+        };
+        let audit_window = match &args.audit_log {
+            Some(window) => Some(
+                chrono::Duration::from_std(watch::parse_interval(window)?)
+                    .map_err(|e| e.to_string())?,
+            ),
+            None => None,
+        };
+        let offline = if args.from_file.is_empty() {
+            None
+        } else {
+            Some(dump::load_all(&args.from_file)?)
+        };
+        let cache = match &args.incremental {
+            Some(path) if path.exists() => Some(dump::load(path)?),
+            Some(_) => Some(vec![]),
+            None => None,
+        };
+        let churn_since = match &args.churn_window {
+            Some(window) => Some(
+                Utc::now()
+                    - chrono::Duration::from_std(watch::parse_interval(window)?)
+                        .map_err(|e| e.to_string())?,
+            ),
+            None => None,
+        };
+        let date_filter = DateFilter::from_args(&args, Utc::now())?;
+        let shard = args.shard.as_deref().map(Shard::parse).transpose()?;
+        let status_days = status::parse_days(&args.status_days)?;
+        let highlights = args
+            .highlight
+            .iter()
+            .map(|rule| highlight::Rule::parse(rule))
+            .collect::<Result<_, _>>()?;
+        let columns = columns::resolve(&args);
+        let baseline = args
+            .baseline
+            .as_deref()
+            .map(|path| Baseline::load(path, args.delimiter))
+            .transpose()?;
+        let regressions: Vec<Regression> = args
+            .fail_on_regression
+            .iter()
+            .map(|rule| Regression::parse(rule))
+            .collect::<Result<_, _>>()?;
+        if let Some(rule) = regressions.iter().find(|r| !columns.contains(&r.column)) {
+            return Err(format!(
+                "--fail-on-regression {} needs that column in the report",
+                rule.column.csv_field().unwrap_or_default()
+            ));
+        }
+        let aggregations = agg::parse(&args.agg)?;
+        let enrichments = Enrichments::from_columns(&columns);
+        if args.provider == Provider::Gitlab {
+            let github_only = enrichments.count() > 0
+                || args.graphql
+                || args.members
+                || args.pinned_only
+                || args.org_profile
+                || args.audit_log.is_some()
+                || matches!(
+                    args.command,
+                    Some(
+                        Command::SuggestTopics { .. }
+                            | Command::Apply { .. }
+                            | Command::ArchiveCandidates { .. }
+                            | Command::Discover { .. }
+                            | Command::Overlap { .. }
+                            | Command::Inventory { .. }
+                    )
+                );
+            if let Some(column) = columns.iter().find(|c| columns::NOT_ON_GITLAB.contains(c)) {
+                return Err(format!(
+                    "--columns {} is not available for GitLab groups",
+                    column.csv_field().unwrap_or(column.title())
+                ));
+            }
+            if github_only {
+                return Err("GitLab groups support only the listing columns (stars, forks, issues, dates, description, topics) and the report, tui, serve, digest and watch modes".to_string());
+            }
+        }
+        Ok(Self {
+            github,
+            enrichments,
+            health_weights: args.health_weights.clone().unwrap_or_default(),
+            columns,
+            metadata,
+            metadata_columns,
+            group_index,
+            policy,
+            diagnostics,
+            usage: Usage::default(),
+            audit_window,
+            offline,
+            dump: (args.dump_raw.is_some() || args.incremental.is_some())
+                .then(|| Mutex::new(vec![])),
+            cache,
+            date_filter,
+            aggregations,
+            star_baselines: Baselines::new(),
+            last_runs: LastRuns::new(),
+            providers: vec![],
+            baseline,
+            regressions,
+            status_days,
+            highlights,
+            shard,
+            deep: args
+                .deep
+                .then(|| Analyzer::new(args.deep_concurrency, args.deep_max_size, churn_since)),
+            args,
+        })
+    }
+}
+
+// Repositories of a virtual organization are fetched one by one from their own owners
+async fn virtual_listing(ctx: &Context, org_name: &str, repos: &[String]) -> OrgListing {
+    let github = &ctx.github;
+    let mut listed = vec![];
+    for full_name in repos {
+        let Some((owner, name)) = full_name.split_once('/') else {
+            listed.push(ListedRepo {
+                name: full_name.clone(),
+                archived: false,
+                disabled: false,
+                stats: Err("expected owner/name".to_string()),
+            });
+            continue;
+        };
+        let repo = github
+            .retry(|| async move { github.octocrab().repos(owner, name).get().await })
+            .await;
+        if let Ok(repo) = &repo {
+            raw::repo(ctx, org_name, &repo.name, repo);
+        }
+        listed.push(match repo {
+            Ok(repo) => ListedRepo {
+                name: repo.name.clone(),
+                archived: repo.archived.unwrap_or(false),
+                disabled: repo.disabled.unwrap_or(false),
+                stats: RepoStats::from_repo(repo).map(|stats| RepoStats {
+                    owner: Some(owner.to_string()),
+                    ..stats
+                }),
+            },
+            Err(e) => ListedRepo {
+                name: name.to_string(),
+                archived: false,
+                disabled: false,
+                stats: Err(format!("{full_name}: {e}")),
+            },
+        });
+    }
+    let created_at = listed
+        .iter()
+        .filter_map(|repo| repo.stats.as_ref().ok())
+        .map(|stats| stats.created_at)
+        .min()
+        .unwrap_or_else(Utc::now);
+    OrgListing {
+        name: Some(org_name.to_string()),
+        created_at,
+        repos: listed,
+    }
+}
+
+pub async fn rest_listing(ctx: &Context, org_name: &str) -> octocrab::Result<OrgListing> {
+    let github = &ctx.github;
+    let org_info = github
+        .retry(|| async move { github.octocrab().orgs(org_name).get().await })
+        .await?;
+    raw::org(ctx, org_name, &org_info);
+    let org_repos = fetch::org_repos(github, org_name, ctx.args.visibility).await?;
+    for repo in &org_repos {
+        raw::repo(ctx, org_name, &repo.name, repo);
+    }
+    let repos: Vec<ListedRepo> = org_repos
+        .into_iter()
+        .map(|repo| ListedRepo {
+            name: repo.name.clone(),
+            archived: repo.archived.unwrap_or(false),
+            disabled: repo.disabled.unwrap_or(false),
+            stats: RepoStats::from_repo(repo),
+        })
+        .collect();
+    // Like a virtual organization, dated by its oldest repository when GitHub leaves it out
+    let created_at = org_info.created_at.unwrap_or_else(|| {
+        repos
+            .iter()
+            .filter_map(|repo| repo.stats.as_ref().ok())
+            .map(|stats| stats.created_at)
+            .min()
+            .unwrap_or_else(Utc::now)
+    });
+    Ok(OrgListing {
+        name: org_info.name,
+        created_at,
+        repos,
+    })
+}
+
+pub async fn scan_org(
+    ctx: &Context,
+    org_name: &str,
+    now: DateTime<Utc>,
+) -> Result<OrgReport, String> {
+    let offline = match &ctx.offline {
+        Some(orgs) => Some(
+            orgs.iter()
+                .find(|o| o.org.eq_ignore_ascii_case(org_name))
+                .cloned()
+                .ok_or(format!("organization {org_name} is not in the dump file"))?,
+        ),
+        None => None,
+    };
+    let is_virtual = ctx.args.virtual_orgs.contains_key(org_name);
+    let listing = if let Some(offline) = &offline {
+        offline.listing.clone()
+    } else if let Some(repos) = ctx.args.virtual_orgs.get(org_name) {
+        virtual_listing(ctx, org_name, repos).await
+    } else if ctx.args.provider == Provider::Gitlab {
+        gitlab::group_listing(&ctx.github, org_name, ctx.args.visibility)
+            .await
+            .map_err(|e| e.to_string())?
+    } else if ctx.args.graphql {
+        match graphql::org_listing(
+            &ctx.github,
+            org_name,
+            ctx.args.graphql_max_cost,
+            ctx.args.visibility,
+            &ctx.usage,
+        )
+        .await
+        {
+            Ok(listing) => listing,
+            Err(graphql::ListingError::Query(e)) => return Err(e),
+            Err(e) => {
+                if let graphql::ListingError::Budget(_) = e {
+                    tracing::warn!(
+                        org = org_name,
+                        "GraphQL budget exceeded, falling back to REST: {e}"
+                    );
+                }
+                ctx.diagnostics.emit(
+                    Kind::DegradedMetric,
+                    Some(org_name),
+                    None,
+                    &format!("GraphQL listing failed, falling back to REST: {e}"),
+                );
+                rest_listing(ctx, org_name)
+                    .await
+                    .map_err(|e| e.to_string())?
+            }
+        }
+    } else {
+        rest_listing(ctx, org_name)
+            .await
+            .map_err(|e| e.to_string())?
+    };
+    let members = if let Some(offline) = &offline {
+        offline.members.clone()
+    } else if ctx.args.members && !is_virtual {
+        match fetch::members(&ctx.github, org_name).await {
+            Ok(members) => Some(members),
+            Err(e) => {
+                ctx.diagnostics.emit(
+                    Kind::DegradedMetric,
+                    Some(org_name),
+                    None,
+                    &format!("member counts unavailable: {e}"),
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let profile = if let Some(offline) = &offline {
+        offline.profile.clone()
+    } else if ctx.args.org_profile && !is_virtual {
+        match fetch::org_profile(&ctx.github, org_name).await {
+            Ok(profile) => Some(profile),
+            Err(e) => {
+                ctx.diagnostics.emit(
+                    Kind::DegradedMetric,
+                    Some(org_name),
+                    None,
+                    &format!("organization profile unavailable: {e}"),
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let audit = if let Some(offline) = &offline {
+        offline.audit.clone()
+    } else if let Some(window) = ctx.audit_window.filter(|_| !is_virtual) {
+        match fetch::audit_events(&ctx.github, org_name, now - window).await {
+            Ok(events) => Some(events),
+            Err(e) => {
+                ctx.diagnostics.emit(
+                    Kind::DegradedMetric,
+                    Some(org_name),
+                    None,
+                    &format!("audit log unavailable: {e}"),
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+    // Virtual organizations are a hand-picked list already
+    let pinned = if let Some(offline) = &offline {
+        offline.pinned.clone()
+    } else if ctx.args.pinned_only && !is_virtual {
+        Some(
+            graphql::pinned_repos(&ctx.github, org_name)
+                .await
+                .map_err(|e| format!("pinned repositories unavailable: {e}"))?,
+        )
+    } else {
+        None
+    };
+    if ctx.args.pinned_only && pinned.is_none() && !is_virtual {
+        return Err(format!(
+            "the dump has no pinned repositories of {org_name}, save it with --pinned-only"
+        ));
+    }
+    let mut org_repos = listing.repos;
+    let violations = ctx.policy.as_ref().and_then(|policy| {
+        let names: Vec<String> = org_repos.iter().map(|r| r.name.clone()).collect();
+        policy.check(org_name, &names)
+    });
+    org_repos.sort_by_key(|r| std::cmp::Reverse(r.stats.as_ref().ok().and_then(|s| s.pushed_at)));
+
+    let mut stats = SumStats::new();
+    let mut unavailable: Vec<(String, String)> = vec![];
+    let mut rows: Vec<(RepoStats, Vec<String>)> = vec![];
+    let mut groups = ctx.args.group_by.as_deref().map(Groups::new);
+    let mut listed: Vec<RepoStats> = vec![];
+    for repo in org_repos
+        .into_iter()
+        .filter(|repo| {
+            let excluded = ctx.args.is_excluded(org_name, &repo.name);
+            if excluded {
+                ctx.diagnostics.emit(
+                    Kind::RepoSkipped,
+                    Some(org_name),
+                    Some(&repo.name),
+                    "excluded",
+                );
+            }
+            !excluded
+        })
+        .filter(|repo| {
+            ctx.shard
+                .is_none_or(|shard| shard.contains(org_name, &repo.name))
+        })
+        .filter(|repo| {
+            pinned
+                .as_ref()
+                .filter(|_| ctx.args.pinned_only)
+                .is_none_or(|names| names.contains(&repo.name))
+        })
+        // Listings already ask for it, but dumps and virtual organizations hold every visibility
+        .filter(|repo| match &repo.stats {
+            Ok(stats) => ctx.args.visibility.matches(stats.visibility.as_deref()),
+            Err(_) => true,
+        })
+        .take(ctx.args.latest_n.unwrap_or(usize::MAX))
+    {
+        if repo.archived {
+            ctx.diagnostics.emit(
+                Kind::RepoSkipped,
+                Some(org_name),
+                Some(&repo.name),
+                "archived",
+            );
+            continue;
+        }
+        if repo.disabled {
+            ctx.diagnostics.emit(
+                Kind::RepoSkipped,
+                Some(org_name),
+                Some(&repo.name),
+                "disabled",
+            );
+            unavailable.push((repo.name, "disabled (DMCA or ToS)".to_string()));
+            continue;
+        }
+        match repo.stats {
+            Ok(stats) if !ctx.date_filter.matches(&stats) => {
+                ctx.diagnostics.emit(
+                    Kind::RepoSkipped,
+                    Some(org_name),
+                    Some(&repo.name),
+                    "outside the date range",
+                );
+            }
+            Ok(stats) => listed.push(stats),
+            Err(reason) => {
+                ctx.diagnostics
+                    .emit(Kind::RepoSkipped, Some(org_name), Some(&repo.name), &reason);
+                unavailable.push((repo.name, reason));
+            }
+        }
+    }
+
+    let is_offline = offline.is_some();
+    let cached = ctx
+        .cache
+        .iter()
+        .flatten()
+        .find(|o| o.org.eq_ignore_ascii_case(org_name));
+    let listed: Vec<RepoStats> = listed
+        .into_iter()
+        .map(|mut repo| {
+            let hit = cached
+                .and_then(|o| o.listing.repos.iter().find(|r| r.name == repo.name))
+                .and_then(|r| r.stats.as_ref().ok())
+                .filter(|c| repo.is_unchanged(c));
+            if let Some(cached) = hit {
+                repo.reuse_enrichments(cached);
+            }
+            if ctx.cache.is_some() {
+                ctx.usage.record_cache(hit.is_some());
+            }
+            repo
+        })
+        .collect();
+    if !is_offline && ctx.enrichments.count() > 0 {
+        // Stop short of the rate limit instead of letting every remaining call fail
+        if let Some(remaining) = ctx.github.remaining().await {
+            ctx.github
+                .set_window_budget(ctx.github.requests().saturating_add(remaining));
+            // An upper bound, cache hits skip their push-derived enrichments
+            let needed = listed.len() as u32 * ctx.enrichments.count();
+            if needed > remaining {
+                ctx.diagnostics.emit(
+                    Kind::DegradedMetric,
+                    Some(org_name),
+                    None,
+                    &format!("about {needed} enrichment requests needed but only {remaining} left in the rate limit"),
+                );
+            }
+        }
+    }
+    let style = Style::from_args(&ctx.args, now);
+    let raw_style = Style::raw(now, ctx.args.units());
+    let mut pending: Vec<String> = vec![];
+    let mut flushed_at = Instant::now();
+    let mut enriched = stream::iter(listed)
+        // Cache hits only carry the push-derived enrichments, so the rest is still fetched
+        .map(|mut repo| async move {
+            if !is_offline {
+                let owner = repo.owner.clone().unwrap_or(org_name.to_string());
+                enrich::enrich(
+                    &ctx.github,
+                    &owner,
+                    &mut repo,
+                    &ctx.enrichments,
+                    &ctx.diagnostics,
+                )
+                .await;
+                if ctx.enrichments.growth {
+                    let since = growth::since(&ctx.last_runs, org_name, now);
+                    enrich::growth(&ctx.github, &owner, &mut repo, since, &ctx.diagnostics).await;
+                }
+                enrich::custom(&ctx.providers, &owner, &mut repo, &ctx.diagnostics).await;
+                if let Some(deep) = ctx.deep.as_ref().filter(|_| repo.deep.is_none()) {
+                    deep.analyze(&owner, &mut repo, &ctx.diagnostics).await;
+                }
+            }
+            repo
+        })
+        .buffered(ctx.github.concurrency());
+    while let Some(mut repo) = enriched.next().await {
+        if ctx.enrichments.health {
+            repo.health = Some(health::score(&repo, &ctx.health_weights, now));
+        }
+        if ctx.columns.contains(&Column::StarVelocity) {
+            repo.star_velocity = Some(velocity::stars_per_month(
+                &repo,
+                org_name,
+                &ctx.star_baselines,
+                now,
+            ));
+        }
+        if ctx.columns.contains(&Column::Status) {
+            repo.status = Some(Status::classify(repo.last_push(), now, &ctx.status_days));
+        }
+        let mut extra = match &ctx.metadata {
+            Some(metadata) => {
+                if metadata.get(org_name, &repo.name).is_none() {
+                    ctx.diagnostics.emit(
+                        Kind::MetadataMissing,
+                        Some(org_name),
+                        Some(&repo.name),
+                        "no entry in metadata file",
+                    );
+                }
+                metadata.values(org_name, &repo.name)
+            }
+            None => vec![],
+        };
+        extra.extend(ctx.providers.iter().map(|provider| {
+            repo.custom
+                .get(provider.name())
+                .cloned()
+                .unwrap_or_default()
+        }));
+        if !(ctx.args.skip_empty && repo.is_empty()) {
+            if let (Some(groups), Some(index)) = (groups.as_mut(), ctx.group_index) {
+                groups.entry(&extra[index]).update(&repo);
+            }
+            stats.update(&repo);
+        }
+        if ctx.args.format == Some(OutputFormat::Ndjson) {
+            match ctx.args.anonymize {
+                true => {
+                    let anonymized = anonymize::repo(ctx, org_name, &repo);
+                    ndjson::repo(
+                        &anonymize::org(ctx, org_name),
+                        &RepoMetrics::new(&anonymized, &ctx.columns, &raw_style),
+                    )
+                }
+                false => ndjson::repo(org_name, &RepoMetrics::new(&repo, &ctx.columns, &raw_style)),
+            }
+        }
+        if ctx.args.stream_rows {
+            pending.push(format!(
+                "{org_name}/{}",
+                render::row_values(ctx, &repo, &extra, &style).join("  ")
+            ));
+            if flushed_at.elapsed() >= STREAM_INTERVAL {
+                println!("{}", pending.join("\n"));
+                pending.clear();
+                flushed_at = Instant::now();
+            }
+        }
+        rows.push((repo, extra));
+    }
+    if !pending.is_empty() {
+        println!("{}", pending.join("\n"));
+    }
+    if !ctx.args.sort.is_empty() {
+        rows.sort_by(|(a, _), (b, _)| columns::compare(&ctx.args.sort, a, b));
+    }
+    if let Some(dump) = &ctx.dump {
+        let repos = rows
+            .iter()
+            .map(|(repo, _)| ListedRepo {
+                name: repo.name.clone(),
+                archived: false,
+                disabled: false,
+                stats: Ok(repo.clone()),
+            })
+            .chain(unavailable.iter().map(|(name, reason)| ListedRepo {
+                name: name.clone(),
+                archived: false,
+                disabled: false,
+                stats: Err(reason.clone()),
+            }))
+            .collect();
+        dump.lock().expect("dump lock ok").push(DumpedOrg {
+            org: org_name.to_string(),
+            listing: OrgListing {
+                name: listing.name.clone(),
+                created_at: listing.created_at,
+                repos,
+            },
+            members: members.clone(),
+            audit: audit.clone(),
+            profile: profile.clone(),
+            pinned: pinned.clone(),
+        });
+    }
+
+    Ok(OrgReport {
+        org: org_name.to_string(),
+        name: listing.name.unwrap_or(org_name.to_string()),
+        created_year: listing.created_at.year(),
+        rows,
+        unavailable,
+        stats,
+        groups,
+        violations,
+        members,
+        audit,
+        profile,
+    })
+}
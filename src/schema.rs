@@ -0,0 +1,84 @@
+use clap::ValueEnum;
+use serde_json::{json, Value};
+
+use crate::columns::Column;
+use crate::stats::{OrgProfile, APPROXIMATE_FIELD, URL_FIELDS};
+
+// Bump when an export field is renamed, removed or changes meaning; new columns don't need it
+pub const SCHEMA_VERSION: u32 = 3;
+
+// Exports follow the declaration order of Column, whatever order --columns used
+pub fn export_columns(columns: &[Column]) -> Vec<Column> {
+    Column::value_variants()
+        .iter()
+        .filter(|column| columns.contains(column))
+        .copied()
+        .collect()
+}
+
+fn column_name(column: &Column) -> String {
+    column
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+fn field(name: &str, when: Option<String>) -> Value {
+    json!({ "name": name, "when": when })
+}
+
+pub fn describe() -> Value {
+    let org_csv: Vec<Value> = [
+        field("schema_version", None),
+        field("collected_at", Some("--append".to_string())),
+        field("real_org_name", None),
+        field("org_created_at", None),
+    ]
+    .into_iter()
+    .chain(
+        ["members", "public_members", "teams"]
+            .map(|name| field(name, Some("--members".to_string()))),
+    )
+    .chain(OrgProfile::CSV_FIELDS.map(|name| field(name, Some("--org-profile".to_string()))))
+    .chain(Column::value_variants().iter().filter_map(|column| {
+        column
+            .csv_field()
+            .map(|name| field(name, Some(format!("--columns {}", column_name(column)))))
+    }))
+    .chain([field("error", None)])
+    .collect();
+    let repo_csv: Vec<Value> = [field("repository", None)]
+        .into_iter()
+        .chain(Column::value_variants().iter().map(|column| {
+            field(
+                column.csv_field().unwrap_or(column.title()),
+                Some(format!("--columns {}", column_name(column))),
+            )
+        }))
+        .chain(URL_FIELDS.map(|name| field(name, None)))
+        .chain([field(APPROXIMATE_FIELD, None)])
+        .collect();
+    json!({
+        "schema_version": SCHEMA_VERSION,
+        "csv": { "organizations": org_csv, "repositories": repo_csv },
+        "ndjson": {
+            "repo": ["type", "schema_version", "org", "repo", "metrics"],
+            "org": [
+                "type",
+                "schema_version",
+                "org",
+                "name",
+                "created_year",
+                "repos",
+                "unavailable",
+                "members",
+                "profile",
+                "stats",
+                "totals",
+            ],
+            "error": ["type", "schema_version", "org", "reason", "error"],
+            "total": ["type", "schema_version", "orgs", "repos", "stats", "totals"],
+            "run": ["type", "schema_version", "summary"],
+        },
+    })
+}
@@ -0,0 +1,45 @@
+use crate::scan::Context;
+
+// Any of the listed scopes grants access; broader scopes include the narrower ones
+const TRAFFIC: [&str; 1] = ["repo"];
+const SECURITY: [&str; 2] = ["security_events", "repo"];
+const MEMBERS: [&str; 3] = ["read:org", "write:org", "admin:org"];
+const AUDIT_LOG: [&str; 2] = ["read:audit_log", "admin:org"];
+
+fn missing(scopes: &[String], required: &[&str], feature: &str) -> bool {
+    if required.iter().any(|r| scopes.iter().any(|s| s == r)) {
+        return false;
+    }
+    tracing::warn!(
+        "{feature} disabled, the token lacks the {} scope",
+        required.join(" or ")
+    );
+    true
+}
+
+// Turns off enrichments the token cannot serve, so a long scan does not fail or degrade halfway
+pub async fn check(ctx: &mut Context) -> Result<(), String> {
+    let scopes = match ctx.github.scopes().await {
+        Ok(Some(scopes)) => scopes,
+        Ok(None) => {
+            tracing::warn!(
+                "token scopes cannot be inspected (no token, or a fine-grained or app token), enrichments are not checked"
+            );
+            return Ok(());
+        }
+        Err(e) => return Err(format!("Cannot check token scopes: {e}")),
+    };
+    if ctx.enrichments.traffic && missing(&scopes, &TRAFFIC, "traffic") {
+        ctx.enrichments.traffic = false;
+    }
+    if ctx.enrichments.security && missing(&scopes, &SECURITY, "security alerts") {
+        ctx.enrichments.security = false;
+    }
+    if ctx.args.members && missing(&scopes, &MEMBERS, "--members") {
+        ctx.args.members = false;
+    }
+    if ctx.audit_window.is_some() && missing(&scopes, &AUDIT_LOG, "--audit-log") {
+        ctx.audit_window = None;
+    }
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::RwLock;
+
+use crate::diagnostics::Kind;
+use crate::scan::{self, Context, OrgReport};
+use crate::stats::RepoStats;
+
+fn label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, samples: &[(String, f64)]) {
+    writeln!(out, "# HELP {name} {help}").expect("write metrics ok");
+    writeln!(out, "# TYPE {name} gauge").expect("write metrics ok");
+    for (labels, value) in samples {
+        if labels.is_empty() {
+            writeln!(out, "{name} {value}").expect("write metrics ok");
+        } else {
+            writeln!(out, "{name}{{{labels}}} {value}").expect("write metrics ok");
+        }
+    }
+}
+
+pub fn render(reports: &[OrgReport]) -> String {
+    let org_labels = |r: &OrgReport| format!("org=\"{}\"", label(&r.org));
+    let org_gauges: [(&str, &str, fn(&OrgReport) -> f64); 5] = [
+        (
+            "github_org_repositories",
+            "Public repositories scanned",
+            |r| r.rows.len() as f64,
+        ),
+        ("github_org_stars", "Stars across repositories", |r| {
+            r.stats.stars as f64
+        }),
+        ("github_org_forks", "Forks across repositories", |r| {
+            r.stats.forks as f64
+        }),
+        (
+            "github_org_open_issues",
+            "Open issues and pull requests across repositories",
+            |r| r.stats.open_issues_count as f64,
+        ),
+        ("github_org_size_kib", "Repository size in KiB", |r| {
+            r.stats.size as f64
+        }),
+    ];
+
+    let mut out = String::new();
+    for (name, help, value) in org_gauges {
+        let samples: Vec<(String, f64)> =
+            reports.iter().map(|r| (org_labels(r), value(r))).collect();
+        gauge(&mut out, name, help, &samples);
+    }
+    let repo_gauges: [(&str, &str, fn(&RepoStats) -> f64); 3] = [
+        ("github_repo_stars", "Repository stars", |r| r.stars as f64),
+        ("github_repo_forks", "Repository forks", |r| r.forks as f64),
+        (
+            "github_repo_open_issues",
+            "Repository open issues and pull requests",
+            |r| r.open_issues_count as f64,
+        ),
+    ];
+    for (name, help, value) in repo_gauges {
+        let samples: Vec<(String, f64)> = reports
+            .iter()
+            .flat_map(|report| {
+                report.rows.iter().map(move |(repo, _)| {
+                    (
+                        format!("{},repo=\"{}\"", org_labels(report), label(&repo.name)),
+                        value(repo),
+                    )
+                })
+            })
+            .collect();
+        gauge(&mut out, name, help, &samples);
+    }
+    gauge(
+        &mut out,
+        "github_repo_info_last_refresh_timestamp_seconds",
+        "Unix time of the last completed refresh",
+        &[(String::new(), Utc::now().timestamp() as f64)],
+    );
+    out
+}
+
+async fn respond(stream: TcpStream, metrics: Arc<RwLock<String>>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let (status, body) = if path == "/metrics" {
+        ("200 OK", metrics.read().await.clone())
+    } else {
+        ("404 Not Found", "Not found, try /metrics\n".to_string())
+    };
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    reader.get_mut().write_all(response.as_bytes()).await
+}
+
+pub async fn run(
+    ctx: &Context,
+    orgs: &[String],
+    listen: &str,
+    interval: Duration,
+) -> Result<(), String> {
+    let listener = TcpListener::bind(listen)
+        .await
+        .map_err(|e| format!("Cannot listen on {listen}: {e}"))?;
+    println!("Serving metrics on http://{listen}/metrics");
+    let metrics = Arc::new(RwLock::new(String::new()));
+    let shared = metrics.clone();
+    tokio::spawn(async move {
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                continue;
+            };
+            let metrics = shared.clone();
+            tokio::spawn(async move {
+                let _ = respond(stream, metrics).await;
+            });
+        }
+    });
+
+    loop {
+        let now = Utc::now();
+        let mut reports = vec![];
+        for org_name in orgs {
+            match scan::scan_org(ctx, org_name, now).await {
+                Ok(report) => reports.push(report),
+                Err(e) => {
+                    ctx.diagnostics
+                        .emit(Kind::OrgNotFound, Some(org_name), None, &e.to_string());
+                }
+            }
+        }
+        *metrics.write().await = render(&reports);
+        tokio::time::sleep(interval).await;
+    }
+}
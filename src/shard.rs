@@ -0,0 +1,81 @@
+#[derive(Clone, Copy, Debug)]
+pub struct Shard {
+    index: u64,
+    count: u64,
+}
+
+impl Shard {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid shard {spec}, expected N/M with 1 <= N <= M, e.g. 3/8");
+        let (index, count) = spec.split_once('/').ok_or_else(invalid)?;
+        let index: u64 = index.trim().parse().map_err(|_| invalid())?;
+        let count: u64 = count.trim().parse().map_err(|_| invalid())?;
+        if index == 0 || index > count {
+            return Err(invalid());
+        }
+        Ok(Self { index, count })
+    }
+
+    // FNV-1a rather than the std hasher, whose output may change between Rust releases
+    // and would then split the repositories differently on runners built apart
+    pub fn contains(&self, org: &str, repo: &str) -> bool {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in format!("{org}/{repo}").to_lowercase().bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        hash % self.count == self.index - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shard_of(count: u64, org: &str, repo: &str) -> Vec<u64> {
+        (1..=count)
+            .filter(|index| {
+                Shard::parse(&format!("{index}/{count}"))
+                    .unwrap()
+                    .contains(org, repo)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_an_index_out_of_a_count() {
+        let shard = Shard::parse(" 3 / 8 ").unwrap();
+
+        assert_eq!((shard.index, shard.count), (3, 8));
+    }
+
+    #[test]
+    fn rejects_malformed_shards() {
+        for spec in [
+            "", "3", "3/", "/8", "a/8", "3/b", "-1/8", "0/8", "9/8", "1/0",
+        ] {
+            assert!(Shard::parse(spec).is_err(), "{spec} should not parse");
+        }
+    }
+
+    #[test]
+    fn every_repository_falls_into_exactly_one_shard() {
+        for repo in ["anvil", "rocket", "magnet", "tnt", "roadrunner"] {
+            assert_eq!(shard_of(8, "acme", repo).len(), 1, "{repo}");
+        }
+        assert_eq!(shard_of(1, "acme", "anvil"), [1]);
+    }
+
+    // Runners built apart have to agree on the split
+    #[test]
+    fn the_split_is_stable() {
+        assert_eq!(shard_of(8, "acme", "anvil"), [7]);
+        assert_eq!(shard_of(8, "acme", "rocket"), [1]);
+        assert_eq!(shard_of(8, "acme", "magnet"), [3]);
+    }
+
+    #[test]
+    fn names_are_compared_without_case() {
+        assert_eq!(shard_of(8, "ACME", "Anvil"), shard_of(8, "acme", "anvil"));
+    }
+}
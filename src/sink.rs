@@ -0,0 +1,693 @@
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+use crate::anonymize;
+use crate::brief::{self, Flavor};
+use crate::card;
+use crate::cli::{CsvSchema, OutputFormat};
+use crate::columns::Column;
+use crate::failures::Failure;
+use crate::format::{self, Style};
+use crate::history::Snapshot;
+use crate::metrics::{self, Metric, OrgMetrics};
+use crate::ndjson;
+use crate::out_dir;
+use crate::render;
+use crate::scan::{Context, OrgReport};
+use crate::schema::{self, SCHEMA_VERSION};
+use crate::stats::{OrgProfile, RepoStats, SumStats};
+use crate::summary::RunSummary;
+use crate::template::Template;
+use crate::xlsx::{self, XlsxExport};
+
+const CSV_PATH: &str = "out/org_stats.csv";
+const XLSX_PATH: &str = "out/org_stats.xlsx";
+const TOTAL_ROW: &str = "TOTAL";
+const V1_FIELDS: [&str; 9] = [
+    "real_org_name",
+    "org_created_at",
+    "stars",
+    "forks",
+    "followers",
+    "updated_at",
+    "pushed_at",
+    "open_issues_count",
+    "size",
+];
+
+pub trait Sink {
+    fn org(&mut self, ctx: &Context, report: &OrgReport) -> Result<(), String>;
+    fn failed(&mut self, ctx: &Context, failure: &Failure) -> Result<(), String>;
+    fn total(
+        &mut self,
+        ctx: &Context,
+        orgs: u32,
+        repos: u32,
+        stats: &SumStats,
+    ) -> Result<(), String>;
+    fn finish(&mut self, run: &RunSummary) -> Result<(), String>;
+}
+
+// The terminal table accompanies the file formats; ndjson owns stdout on its own
+pub fn open(
+    ctx: &Context,
+    now: DateTime<Utc>,
+    history: &[Snapshot],
+) -> Result<Vec<Box<dyn Sink>>, String> {
+    let args = &ctx.args;
+    if let Some(dir) = &args.out_dir {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Cannot create {}: {e}", dir.display()))?;
+    }
+    let table = || -> Box<dyn Sink> {
+        Box::new(TableSink {
+            style: Style::from_args(args, now),
+        })
+    };
+    let mut sinks: Vec<Box<dyn Sink>> = match args.format.unwrap_or_default() {
+        OutputFormat::Csv => vec![table(), Box::new(CsvSink::open(ctx, now)?)],
+        OutputFormat::Xlsx => vec![table(), Box::new(XlsxSink::new(ctx, now)?)],
+        OutputFormat::Ndjson => vec![Box::new(NdjsonSink {
+            raw_style: Style::raw(now, args.units()),
+        })],
+        OutputFormat::Slack => vec![Box::new(BriefSink::new(now, history, Flavor::Slack))],
+        OutputFormat::TextSummary => {
+            vec![Box::new(BriefSink::new(now, history, Flavor::Text))]
+        }
+    };
+    if let Some(path) = &args.template {
+        sinks.push(Box::new(TemplateSink::open(
+            path,
+            args.template_out.clone(),
+            Style::from_args(args, now),
+        )?));
+    }
+    // A template printed to stdout takes the place of the tables
+    let replaces_table = args.template.is_some() && args.template_out.is_none();
+    if (args.quiet || replaces_table) && !args.format.unwrap_or_default().owns_stdout() {
+        sinks.remove(0);
+    }
+    Ok(sinks)
+}
+
+// Anonymized exports keep only the reason, the message can name the organization
+fn failure_fields(ctx: &Context, failure: &Failure) -> (String, String) {
+    match ctx.args.anonymize {
+        true => (
+            anonymize::org(ctx, &failure.org),
+            failure.reason.label().to_string(),
+        ),
+        false => (failure.org.clone(), failure.describe()),
+    }
+}
+
+fn repos(report: &OrgReport) -> Vec<&RepoStats> {
+    report.rows.iter().map(|(repo, _)| repo).collect()
+}
+
+struct TableSink {
+    style: Style,
+}
+
+impl Sink for TableSink {
+    fn org(&mut self, ctx: &Context, report: &OrgReport) -> Result<(), String> {
+        if ctx.args.summary_only {
+            println!("{}", card::card(report, &self.style));
+            return Ok(());
+        }
+        render::print_report(ctx, report, &self.style, None);
+        Ok(())
+    }
+
+    // Failures are listed together once the scan is over
+    fn failed(&mut self, _ctx: &Context, _failure: &Failure) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn total(
+        &mut self,
+        ctx: &Context,
+        orgs: u32,
+        repos: u32,
+        stats: &SumStats,
+    ) -> Result<(), String> {
+        println!(
+            "{}",
+            render::total_table(ctx, stats, orgs, repos, &self.style)
+        );
+        Ok(())
+    }
+
+    fn finish(&mut self, run: &RunSummary) -> Result<(), String> {
+        println!("{}", run.table());
+        Ok(())
+    }
+}
+
+struct CsvSink {
+    writer: csv::Writer<File>,
+    width: usize,
+    schema: CsvSchema,
+    now: DateTime<Utc>,
+    raw_style: Style,
+}
+
+// The first record of a CSV file, without the byte order mark of --bom
+fn existing_header(path: &Path, delimiter: u8) -> csv::Result<Vec<String>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .from_path(path)?;
+    let header = reader.records().next().transpose()?.unwrap_or_default();
+    Ok(header
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match i {
+            0 => field.trim_start_matches('\u{feff}').to_string(),
+            _ => field.to_string(),
+        })
+        .collect())
+}
+
+impl CsvSink {
+    fn open(ctx: &Context, now: DateTime<Utc>) -> Result<Self, String> {
+        let args = &ctx.args;
+        if !args.delimiter.is_ascii() {
+            return Err(format!("CSV delimiter {} is not ASCII", args.delimiter));
+        }
+        let path = args
+            .out_dir
+            .as_ref()
+            .map_or(PathBuf::from(CSV_PATH), |d| d.join("index.csv"));
+        let error = |e: &dyn std::fmt::Display| format!("Cannot write {}: {e}", path.display());
+        // A resumed scan adds the remaining organizations to the rows written before the interruption
+        let append = args.append || args.resume.as_deref().is_some_and(Path::exists);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(&path)
+            .map_err(|e| error(&e))?;
+        let is_empty = file.metadata().map_err(|e| error(&e))?.len() == 0;
+        if is_empty && args.bom {
+            file.write_all("\u{feff}".as_bytes())
+                .map_err(|e| error(&e))?;
+        }
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(args.delimiter as u8)
+            .from_writer(file);
+        let schema = args.csv_schema.unwrap_or_default();
+        let csv_fields: Vec<&str> = ["schema_version"]
+            .into_iter()
+            .chain(args.append.then_some("collected_at"))
+            .chain(["real_org_name", "org_created_at"])
+            .chain(
+                args.members
+                    .then_some(["members", "public_members", "teams"])
+                    .into_iter()
+                    .flatten(),
+            )
+            .chain(
+                args.org_profile
+                    .then_some(OrgProfile::CSV_FIELDS)
+                    .into_iter()
+                    .flatten(),
+            )
+            .chain(
+                schema::export_columns(&ctx.columns)
+                    .iter()
+                    .filter_map(Column::csv_field),
+            )
+            .chain(["error"])
+            .collect();
+        let header: Vec<&str> = match schema {
+            CsvSchema::V1 => V1_FIELDS.to_vec(),
+            CsvSchema::Latest => csv_fields.clone(),
+        };
+        if is_empty {
+            writer.write_record(&header).map_err(|e| error(&e))?;
+        } else {
+            // Rows under another header would end up in the wrong columns
+            let existing = existing_header(&path, args.delimiter as u8).map_err(|e| error(&e))?;
+            if existing != header {
+                return Err(format!(
+                    "Cannot append to {}: its columns ({}) differ from this run's ({}); \
+                     write to a new file or keep --columns, --members and --org-profile as before",
+                    path.display(),
+                    existing.join(","),
+                    header.join(",")
+                ));
+            }
+        }
+        Ok(Self {
+            writer,
+            width: csv_fields.len(),
+            schema,
+            now,
+            raw_style: Style::raw(now, args.units()),
+        })
+    }
+
+    fn write_row(
+        &mut self,
+        ctx: &Context,
+        name: &str,
+        created_year: Option<i32>,
+        members: [String; 3],
+        profile: [String; 5],
+        stats: &SumStats,
+        totals: &[Metric],
+    ) -> Result<(), String> {
+        let args = &ctx.args;
+        if self.schema == CsvSchema::V1 {
+            return self.write_record(&[
+                name.to_string(),
+                created_year.map(|y| y.to_string()).unwrap_or_default(),
+                stats.stars.to_string(),
+                stats.forks.to_string(),
+                stats.followers.to_string(),
+                format::timestamp(stats.updated_at),
+                format::timestamp(stats.pushed_at),
+                stats.open_issues_count.to_string(),
+                stats.size.to_string(),
+            ]);
+        }
+        let csv_row: Vec<String> = [SCHEMA_VERSION.to_string()]
+            .into_iter()
+            .chain(args.append.then(|| self.now.to_rfc3339()))
+            .chain([
+                name.to_string(),
+                created_year.map(|y| y.to_string()).unwrap_or_default(),
+            ])
+            .chain(args.members.then_some(members).into_iter().flatten())
+            .chain(args.org_profile.then_some(profile).into_iter().flatten())
+            .chain(
+                totals
+                    .iter()
+                    .filter(|total| total.column.csv_field().is_some())
+                    .map(|total| total.raw.clone()),
+            )
+            .chain([String::new()])
+            .collect();
+        self.write_record(&csv_row)
+    }
+
+    fn write_record(&mut self, record: &[String]) -> Result<(), String> {
+        self.writer
+            .write_record(record)
+            .map_err(|e| format!("Cannot write CSV row: {e}"))?;
+        self.writer
+            .flush()
+            .map_err(|e| format!("Cannot write CSV row: {e}"))
+    }
+}
+
+impl Sink for CsvSink {
+    fn org(&mut self, ctx: &Context, report: &OrgReport) -> Result<(), String> {
+        let anonymized = anonymize::report(ctx, report);
+        let report = anonymized.as_ref().unwrap_or(report);
+        let columns = schema::export_columns(&ctx.columns);
+        // Organization totals follow --agg like the table
+        let metrics = OrgMetrics::new(ctx, report, &columns, &self.raw_style);
+        if let Some(dir) = &ctx.args.out_dir {
+            out_dir::write_csv(
+                &out_dir::org_path(dir, &report.org, "csv"),
+                &metrics.repos,
+                &columns,
+                &ctx.providers
+                    .iter()
+                    .map(|p| p.name().to_string())
+                    .collect::<Vec<_>>(),
+                ctx.args.delimiter as u8,
+                ctx.args.bom,
+            )?;
+        }
+        let members = match &report.members {
+            Some(m) => [
+                m.members.to_string(),
+                m.public_members.to_string(),
+                m.teams.map(|t| t.to_string()).unwrap_or_default(),
+            ],
+            None => Default::default(),
+        };
+        let profile = report
+            .profile
+            .as_ref()
+            .map(OrgProfile::csv_values)
+            .unwrap_or_default();
+        self.write_row(
+            ctx,
+            &report.name,
+            Some(report.created_year),
+            members,
+            profile,
+            &report.stats,
+            &metrics.totals,
+        )
+    }
+
+    // Same width as an organization row, with only the name and the error filled in
+    fn failed(&mut self, ctx: &Context, failure: &Failure) -> Result<(), String> {
+        if self.schema == CsvSchema::V1 {
+            return Ok(());
+        }
+        let (org, error) = failure_fields(ctx, failure);
+        let mut record = vec![String::new(); self.width];
+        record[0] = SCHEMA_VERSION.to_string();
+        if ctx.args.append {
+            record[1] = self.now.to_rfc3339();
+        }
+        record[1 + ctx.args.append as usize] = org;
+        record[self.width - 1] = error;
+        self.write_record(&record)
+    }
+
+    fn total(
+        &mut self,
+        ctx: &Context,
+        _orgs: u32,
+        _repos: u32,
+        stats: &SumStats,
+    ) -> Result<(), String> {
+        let anonymized = anonymize::totals(ctx, stats);
+        let stats = anonymized.as_ref().unwrap_or(stats);
+        self.write_row(
+            ctx,
+            TOTAL_ROW,
+            None,
+            Default::default(),
+            Default::default(),
+            stats,
+            // The total row only has the sums, so aggregations apply to organization rows
+            &metrics::totals(
+                stats,
+                &schema::export_columns(&ctx.columns),
+                &self.raw_style,
+            ),
+        )
+    }
+
+    fn finish(&mut self, _run: &RunSummary) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+struct XlsxSink {
+    export: XlsxExport,
+    out_dir: Option<PathBuf>,
+    columns: Vec<Column>,
+    brand_color: Option<u32>,
+    // Names of the metric providers
+    custom: Vec<String>,
+    raw_style: Style,
+}
+
+impl XlsxSink {
+    fn new(ctx: &Context, now: DateTime<Utc>) -> Result<Self, String> {
+        let brand_color = ctx
+            .args
+            .brand_color
+            .as_deref()
+            .map(xlsx::parse_color)
+            .transpose()?;
+        Ok(Self {
+            export: XlsxExport::new(&ctx.columns, brand_color)
+                .map_err(|e| format!("Cannot create xlsx workbook: {e}"))?,
+            out_dir: ctx.args.out_dir.clone(),
+            columns: ctx.columns.clone(),
+            brand_color,
+            custom: ctx.providers.iter().map(|p| p.name().to_string()).collect(),
+            raw_style: Style::raw(now, ctx.args.units()),
+        })
+    }
+}
+
+impl Sink for XlsxSink {
+    fn org(&mut self, ctx: &Context, report: &OrgReport) -> Result<(), String> {
+        let anonymized = anonymize::report(ctx, report);
+        let report = anonymized.as_ref().unwrap_or(report);
+        let error = |e: rust_xlsxwriter::XlsxError| format!("Cannot write xlsx sheet: {e}");
+        let metrics = OrgMetrics::new(ctx, report, &self.columns, &self.raw_style);
+        match &self.out_dir {
+            Some(dir) => {
+                let mut org_xlsx =
+                    XlsxExport::new(&self.columns, self.brand_color).map_err(error)?;
+                org_xlsx
+                    .add_org(&metrics, &self.columns, &self.custom)
+                    .map_err(error)?;
+                org_xlsx
+                    .save(&out_dir::org_path(dir, &report.org, "xlsx"))
+                    .map_err(error)?;
+                self.export
+                    .add_summary(&report.org, Some(report.created_year), &metrics.totals)
+                    .map_err(error)
+            }
+            None => self
+                .export
+                .add_org(&metrics, &self.columns, &self.custom)
+                .map_err(error),
+        }
+    }
+
+    fn failed(&mut self, ctx: &Context, failure: &Failure) -> Result<(), String> {
+        let (org, error) = failure_fields(ctx, failure);
+        self.export
+            .add_failure(&org, &error, &self.columns)
+            .map_err(|e| format!("Cannot write xlsx sheet: {e}"))
+    }
+
+    fn total(
+        &mut self,
+        ctx: &Context,
+        _orgs: u32,
+        _repos: u32,
+        stats: &SumStats,
+    ) -> Result<(), String> {
+        let anonymized = anonymize::totals(ctx, stats);
+        let stats = anonymized.as_ref().unwrap_or(stats);
+        self.export
+            .add_summary(
+                TOTAL_ROW,
+                None,
+                &metrics::totals(stats, &self.columns, &self.raw_style),
+            )
+            .map_err(|e| format!("Cannot write xlsx sheet: {e}"))
+    }
+
+    fn finish(&mut self, run: &RunSummary) -> Result<(), String> {
+        let path = self
+            .out_dir
+            .as_ref()
+            .map_or(PathBuf::from(XLSX_PATH), |d| d.join("index.xlsx"));
+        self.export
+            .add_run_summary(run)
+            .and_then(|_| self.export.save(&path))
+            .map_err(|e| format!("Cannot write {}: {e}", path.display()))
+    }
+}
+
+struct NdjsonSink {
+    raw_style: Style,
+}
+
+impl Sink for NdjsonSink {
+    fn org(&mut self, ctx: &Context, report: &OrgReport) -> Result<(), String> {
+        let anonymized = anonymize::report(ctx, report);
+        let report = anonymized.as_ref().unwrap_or(report);
+        let metrics = OrgMetrics::new(ctx, report, &ctx.columns, &self.raw_style);
+        ndjson::org(report, &metrics.totals);
+        Ok(())
+    }
+
+    fn failed(&mut self, ctx: &Context, failure: &Failure) -> Result<(), String> {
+        let (org, error) = failure_fields(ctx, failure);
+        ndjson::failure(&org, failure.reason, &error);
+        Ok(())
+    }
+
+    fn total(
+        &mut self,
+        ctx: &Context,
+        orgs: u32,
+        repos: u32,
+        stats: &SumStats,
+    ) -> Result<(), String> {
+        let anonymized = anonymize::totals(ctx, stats);
+        let stats = anonymized.as_ref().unwrap_or(stats);
+        ndjson::total(
+            orgs,
+            repos,
+            stats,
+            &metrics::totals(stats, &ctx.columns, &self.raw_style),
+        );
+        Ok(())
+    }
+
+    fn finish(&mut self, run: &RunSummary) -> Result<(), String> {
+        ndjson::run(run);
+        Ok(())
+    }
+}
+
+// A few lines per organization for a chat channel or mail instead of the tables
+struct BriefSink {
+    flavor: Flavor,
+    // The latest snapshot of each organization before this run, for the changes
+    previous: HashMap<String, Snapshot>,
+    now: DateTime<Utc>,
+}
+
+impl BriefSink {
+    fn new(now: DateTime<Utc>, history: &[Snapshot], flavor: Flavor) -> Self {
+        let mut previous: HashMap<String, Snapshot> = HashMap::new();
+        for snapshot in history.iter().filter(|s| s.collected_at < now) {
+            let org = snapshot.org.to_lowercase();
+            if previous
+                .get(&org)
+                .is_none_or(|p| p.collected_at < snapshot.collected_at)
+            {
+                previous.insert(org, snapshot.clone());
+            }
+        }
+        Self {
+            flavor,
+            previous,
+            now,
+        }
+    }
+}
+
+impl Sink for BriefSink {
+    fn org(&mut self, ctx: &Context, report: &OrgReport) -> Result<(), String> {
+        let previous = self.previous.get(&report.org.to_lowercase());
+        let anonymized = anonymize::report(ctx, report);
+        let report = anonymized.as_ref().unwrap_or(report);
+        // The stale threshold of --status, 180 days by default
+        let stale_days = ctx.status_days[1];
+        println!(
+            "{}",
+            brief::org(report, previous, self.flavor, self.now, stale_days)
+        );
+        Ok(())
+    }
+
+    fn failed(&mut self, ctx: &Context, failure: &Failure) -> Result<(), String> {
+        let (org, error) = failure_fields(ctx, failure);
+        println!("{}", brief::failure(&org, &error, self.flavor));
+        Ok(())
+    }
+
+    fn total(
+        &mut self,
+        _ctx: &Context,
+        orgs: u32,
+        repos: u32,
+        stats: &SumStats,
+    ) -> Result<(), String> {
+        println!("{}", brief::total(orgs, repos, stats, self.flavor));
+        Ok(())
+    }
+
+    fn finish(&mut self, _run: &RunSummary) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+struct TemplateSink {
+    template: Template,
+    out: Option<PathBuf>,
+    // Formats the metric texts like the tables
+    style: Style,
+    orgs: Vec<Value>,
+    failed: Vec<Value>,
+    total: Option<Value>,
+}
+
+impl TemplateSink {
+    fn open(path: &Path, out: Option<PathBuf>, style: Style) -> Result<Self, String> {
+        Ok(Self {
+            template: Template::load(path)?,
+            out,
+            style,
+            orgs: vec![],
+            failed: vec![],
+            total: None,
+        })
+    }
+}
+
+impl Sink for TemplateSink {
+    fn org(&mut self, ctx: &Context, report: &OrgReport) -> Result<(), String> {
+        let anonymized = anonymize::report(ctx, report);
+        let report = anonymized.as_ref().unwrap_or(report);
+        let metrics = OrgMetrics::new(ctx, report, &ctx.columns, &self.style);
+        self.orgs.push(json!({
+            "org": report.org,
+            "name": report.name,
+            "created_year": report.created_year,
+            "members": report.members,
+            "profile": report.profile,
+            "stats": report.stats,
+            "repos": repos(report),
+            "metrics": metrics,
+            "unavailable": report
+                .unavailable
+                .iter()
+                .map(|(name, reason)| json!({ "name": name, "reason": reason }))
+                .collect::<Vec<_>>(),
+        }));
+        Ok(())
+    }
+
+    fn failed(&mut self, ctx: &Context, failure: &Failure) -> Result<(), String> {
+        let (org, error) = failure_fields(ctx, failure);
+        self.failed.push(json!({
+            "org": org,
+            "reason": failure.reason,
+            "error": error,
+        }));
+        Ok(())
+    }
+
+    fn total(
+        &mut self,
+        ctx: &Context,
+        orgs: u32,
+        repos: u32,
+        stats: &SumStats,
+    ) -> Result<(), String> {
+        let anonymized = anonymize::totals(ctx, stats);
+        let stats = anonymized.as_ref().unwrap_or(stats);
+        self.total = Some(json!({
+            "orgs": orgs,
+            "repos": repos,
+            "stats": stats,
+            "totals": metrics::totals(stats, &ctx.columns, &self.style),
+        }));
+        Ok(())
+    }
+
+    fn finish(&mut self, run: &RunSummary) -> Result<(), String> {
+        let output = self.template.render(&json!({
+            "generated_at": self.style.now,
+            "orgs": self.orgs,
+            "failed": self.failed,
+            "total": self.total,
+            "run": run,
+        }))?;
+        match &self.out {
+            Some(path) => std::fs::write(path, output)
+                .map_err(|e| format!("Cannot write {}: {e}", path.display())),
+            None => {
+                print!("{output}");
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use comfy_table::{Cell, Color, Table};
+
+use crate::format::Style;
+use crate::render;
+use crate::scan::OrgReport;
+use crate::stats::RepoStats;
+
+const LARGEST: usize = 3;
+
+pub fn table(report: &OrgReport, style: &Style, threshold_mb: Option<u32>) -> Table {
+    let mut repos: Vec<&RepoStats> = report.rows.iter().map(|(repo, _)| repo).collect();
+    repos.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    let title = match threshold_mb {
+        Some(mb) => {
+            // Sizes from the API are in KiB
+            repos.retain(|repo| repo.size >= mb.saturating_mul(1024));
+            format!("Repositories over {mb} MB")
+        }
+        None => {
+            repos.truncate(LARGEST);
+            "Largest repositories".to_string()
+        }
+    };
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new(title).fg(Color::Green),
+        Cell::new("Size").fg(Color::Green),
+        Cell::new("Share of organization").fg(Color::Green),
+    ]);
+    let total = report.stats.size.max(1) as f64;
+    for repo in repos {
+        table.add_row(vec![
+            Cell::new(&repo.name),
+            Cell::new(style.size(repo.size as u64)),
+            Cell::new(format!(
+                "{}%",
+                style.units.ratio(repo.size as f64 / total * 100.0)
+            )),
+        ]);
+    }
+    table
+}
@@ -0,0 +1,145 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::history::{self, Snapshot};
+
+const MANIFEST: &str = "manifest.json";
+const HISTORY: &str = "history.ndjson";
+const DUMP: &str = "dump.json";
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    created_at: DateTime<Utc>,
+    snapshots: usize,
+    orgs: Vec<String>,
+    dump: bool,
+}
+
+pub struct Bundle {
+    pub snapshots: Vec<Snapshot>,
+    pub dump: Option<Vec<u8>>,
+}
+
+fn append<W: Write>(
+    builder: &mut tar::Builder<W>,
+    name: &str,
+    data: &[u8],
+    now: DateTime<Utc>,
+) -> Result<(), String> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(now.timestamp().max(0) as u64);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, name, data)
+        .map_err(|e| format!("Cannot add {name} to bundle: {e}"))
+}
+
+pub fn export(
+    path: &Path,
+    snapshots: &[Snapshot],
+    dump: Option<&Path>,
+    now: DateTime<Utc>,
+) -> Result<(), String> {
+    let dump = match dump {
+        Some(dump) => Some(
+            std::fs::read(dump).map_err(|e| format!("Cannot read dump {}: {e}", dump.display()))?,
+        ),
+        None => None,
+    };
+    let mut orgs: Vec<String> = snapshots.iter().map(|s| s.org.clone()).collect();
+    orgs.sort();
+    orgs.dedup();
+    let manifest = Manifest {
+        created_at: now,
+        snapshots: snapshots.len(),
+        orgs,
+        dump: dump.is_some(),
+    };
+
+    let file = File::create(path).map_err(|e| format!("Cannot create {}: {e}", path.display()))?;
+    let encoder = zstd::Encoder::new(file, 0)
+        .map_err(|e| format!("Cannot compress {}: {e}", path.display()))?;
+    let mut builder = tar::Builder::new(encoder);
+    append(
+        &mut builder,
+        MANIFEST,
+        serde_json::to_string_pretty(&manifest)
+            .expect("serialize manifest ok")
+            .as_bytes(),
+        now,
+    )?;
+    append(
+        &mut builder,
+        HISTORY,
+        history::to_lines(snapshots).as_bytes(),
+        now,
+    )?;
+    if let Some(dump) = &dump {
+        append(&mut builder, DUMP, dump, now)?;
+    }
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| format!("Cannot write {}: {e}", path.display()))?;
+    Ok(())
+}
+
+pub fn import(path: &Path) -> Result<Bundle, String> {
+    let file = File::open(path).map_err(|e| format!("Cannot open {}: {e}", path.display()))?;
+    let decoder = zstd::Decoder::new(file)
+        .map_err(|e| format!("Cannot decompress {}: {e}", path.display()))?;
+    let mut archive = tar::Archive::new(decoder);
+    let invalid = |e: std::io::Error| format!("Invalid bundle {}: {e}", path.display());
+    let mut bundle = Bundle {
+        snapshots: vec![],
+        dump: None,
+    };
+    let mut manifest = false;
+    for entry in archive.entries().map_err(invalid)? {
+        let mut entry = entry.map_err(invalid)?;
+        let name = entry.path().map_err(invalid)?.to_string_lossy().to_string();
+        let mut data = vec![];
+        entry.read_to_end(&mut data).map_err(invalid)?;
+        match name.as_str() {
+            MANIFEST => manifest = true,
+            HISTORY => {
+                bundle.snapshots = String::from_utf8_lossy(&data)
+                    .lines()
+                    .filter_map(|line| serde_json::from_str(line).ok())
+                    .collect()
+            }
+            DUMP => bundle.dump = Some(data),
+            _ => {}
+        }
+    }
+    if !manifest {
+        return Err(format!(
+            "{} is not a snapshot bundle: {MANIFEST} missing",
+            path.display()
+        ));
+    }
+    Ok(bundle)
+}
+
+// Snapshots already in the store, e.g. from an earlier import, are not duplicated
+pub fn merge(existing: Vec<Snapshot>, imported: Vec<Snapshot>) -> (Vec<Snapshot>, usize) {
+    let mut merged = existing;
+    let mut added = 0;
+    for snapshot in imported {
+        if !merged
+            .iter()
+            .any(|s| s.org == snapshot.org && s.collected_at == snapshot.collected_at)
+        {
+            merged.push(snapshot);
+            added += 1;
+        }
+    }
+    merged.sort_by_key(|s| s.collected_at);
+    (merged, added)
+}
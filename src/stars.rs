@@ -0,0 +1,51 @@
+use chrono::{DateTime, Datelike, Months, TimeZone, Utc};
+use comfy_table::{Cell, Color, Table};
+
+use crate::render;
+
+pub const MONTHS: usize = 12;
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+fn month_number(at: DateTime<Utc>) -> i32 {
+    at.year() * 12 + at.month0() as i32
+}
+
+pub fn since(now: DateTime<Utc>) -> DateTime<Utc> {
+    let start = Utc
+        .with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+        .unwrap();
+    start - Months::new(MONTHS as u32 - 1)
+}
+
+// Oldest month first, the current month last
+pub fn month_index(at: DateTime<Utc>, now: DateTime<Utc>) -> Option<usize> {
+    let months_ago = month_number(now) - month_number(at);
+    (0..MONTHS as i32)
+        .contains(&months_ago)
+        .then(|| MONTHS - 1 - months_ago as usize)
+}
+
+pub fn sparkline(counts: &[u32]) -> String {
+    let max = counts.iter().copied().max().unwrap_or(0);
+    counts
+        .iter()
+        .map(|&count| match max {
+            0 => BARS[0],
+            max => BARS[(count as usize * (BARS.len() - 1) + max as usize / 2) / max as usize],
+        })
+        .collect()
+}
+
+pub fn table(counts: &[u32], now: DateTime<Utc>) -> Table {
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Month").fg(Color::Green),
+        Cell::new("New stars").fg(Color::Green),
+    ]);
+    let start = since(now);
+    for (i, count) in counts.iter().enumerate() {
+        let month = start + Months::new(i as u32);
+        table.add_row(vec![month.format("%Y-%m").to_string(), count.to_string()]);
+    }
+    table
+}
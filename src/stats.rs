@@ -0,0 +1,1026 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Utc};
+use octocrab::models::Repository;
+use serde::{Deserialize, Serialize};
+
+use crate::licenses::NO_LICENSE;
+use crate::status::Status;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RepoStats {
+    pub name: String,
+    pub description: Option<String>,
+    pub homepage: Option<String>,
+    pub topics: Vec<String>,
+    pub stars: u32,
+    pub forks: u32,
+    pub license: Option<String>,
+    pub followers: u32,
+    pub updated_at: DateTime<Utc>,
+    // None for empty repositories nobody has pushed to
+    pub pushed_at: Option<DateTime<Utc>>,
+    pub open_issues_count: u32,
+    pub size: u32,
+    pub created_at: DateTime<Utc>,
+    pub open_prs: Option<u32>,
+    pub traffic: Option<Traffic>,
+    pub can_push: bool,
+    pub releases_last_year: Option<u32>,
+    pub community: Option<Community>,
+    pub health: Option<u8>,
+    pub pushes: Option<PushActivity>,
+    pub devcontainer: Option<bool>,
+    pub merge: Option<MergeSettings>,
+    pub actions: Option<ActionsPolicy>,
+    pub deployments: Option<Deployments>,
+    pub traceability: Option<Traceability>,
+    pub security: Option<SecurityAlerts>,
+    pub default_branch: Option<String>,
+    pub ci: Option<String>,
+    pub star_history: Option<Vec<u32>>,
+    pub dependency_updates: Option<DependencyUpdates>,
+    pub advisories: Option<Vec<Advisory>>,
+    pub branch_protected: Option<bool>,
+    pub issue_ages: Option<Vec<u32>>,
+    #[serde(default)]
+    pub html_url: Option<String>,
+    #[serde(default)]
+    pub ssh_url: Option<String>,
+    #[serde(default)]
+    pub clone_url: Option<String>,
+    #[serde(default)]
+    pub status: Option<Status>,
+    #[serde(default)]
+    pub milestones: Option<Vec<Milestone>>,
+    #[serde(default)]
+    pub deep: Option<DeepStats>,
+    // Set for repositories of a virtual organization, whose owners differ from its name
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub codeowners: Option<Vec<String>>,
+    #[serde(default)]
+    pub branch_count: Option<u32>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub review_latency: Option<ReviewLatency>,
+    #[serde(default)]
+    pub visibility: Option<String>,
+    #[serde(default)]
+    pub star_velocity: Option<f64>,
+    #[serde(default)]
+    pub dependencies: Option<Dependencies>,
+    #[serde(default)]
+    pub features: Option<Features>,
+    // Open issues per label
+    #[serde(default)]
+    pub labels: Option<BTreeMap<String, u32>>,
+    #[serde(default)]
+    pub downloads: Option<Downloads>,
+    #[serde(default)]
+    pub docs: Option<Docs>,
+    #[serde(default)]
+    pub growth: Option<Growth>,
+    // Commits per day over the last year, days without commits left out
+    #[serde(default)]
+    pub commit_days: Option<BTreeMap<NaiveDate, u32>>,
+    // Values of the registered metric providers by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom: BTreeMap<String, String>,
+    // Set when a single page of releases or advisories was full, so there may be more
+    #[serde(default)]
+    pub releases_truncated: bool,
+    #[serde(default)]
+    pub advisories_truncated: bool,
+    // Set when open issues filled every page fetched for their ages
+    #[serde(default)]
+    pub issue_ages_truncated: bool,
+    // Largest languages first; only listed through GraphQL
+    #[serde(default)]
+    pub languages: Option<Vec<String>>,
+    // GraphQL has no contributor list, so this counts the users who can be mentioned:
+    // contributors and collaborators
+    #[serde(default)]
+    pub contributors: Option<u32>,
+}
+
+pub const URL_FIELDS: [&str; 3] = ["html_url", "ssh_url", "clone_url"];
+pub const APPROXIMATE_FIELD: &str = "approximate";
+
+pub fn median(values: &[u32]) -> Option<u32> {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    match sorted.len() {
+        0 => None,
+        n if n % 2 == 0 => Some((sorted[n / 2 - 1] + sorted[n / 2]) / 2),
+        n => Some(sorted[n / 2]),
+    }
+}
+
+pub fn is_failing_ci(conclusion: &str) -> bool {
+    matches!(conclusion, "failure" | "timed_out" | "startup_failure")
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SecurityAlerts {
+    pub dependabot: Option<u32>,
+    pub code_scanning: Option<u32>,
+    #[serde(default)]
+    pub private_reporting: Option<bool>,
+    // Set when a single page of alerts was full, so there may be more
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+impl SecurityAlerts {
+    pub fn total(&self) -> u32 {
+        self.dependabot.unwrap_or(0) + self.code_scanning.unwrap_or(0)
+    }
+
+    pub fn describe(&self) -> String {
+        let count = |n: Option<u32>| n.map(|n| n.to_string()).unwrap_or("n/a".to_string());
+        let private_reporting = match self.private_reporting {
+            Some(true) => ", private reporting on",
+            Some(false) => ", private reporting off",
+            None => "",
+        };
+        format!(
+            "{} dependabot / {} code scanning{private_reporting}",
+            count(self.dependabot),
+            count(self.code_scanning)
+        )
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Traceability {
+    pub releases: u32,
+    pub undeployed: Vec<String>,
+    pub unreleased: Vec<String>,
+}
+
+impl Traceability {
+    pub fn anomalies(&self) -> u32 {
+        (self.undeployed.len() + self.unreleased.len()) as u32
+    }
+
+    pub fn describe(&self) -> String {
+        if self.anomalies() == 0 {
+            return "ok".to_string();
+        }
+        let mut parts = vec![];
+        if !self.undeployed.is_empty() {
+            parts.push(format!("{} undeployed", self.undeployed.len()));
+        }
+        if !self.unreleased.is_empty() {
+            parts.push(format!("{} unreleased", self.unreleased.len()));
+        }
+        parts.join(", ")
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Deployments {
+    pub environments: Vec<String>,
+    pub last_30d: u32,
+    // Only the first page was read, so the counts are lower bounds
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+impl Deployments {
+    pub fn frequency(&self) -> &'static str {
+        match self.last_30d {
+            20.. => "daily",
+            4..=19 => "weekly",
+            1..=3 => "monthly",
+            0 => "rarely",
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Advisory {
+    pub ghsa_id: String,
+    pub severity: String,
+    pub published_at: DateTime<Utc>,
+    pub summary: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Milestone {
+    pub title: String,
+    pub open_issues: u32,
+    pub closed_issues: u32,
+    pub due_on: Option<DateTime<Utc>>,
+}
+
+impl Milestone {
+    pub fn completion(&self) -> Option<f64> {
+        let total = self.open_issues + self.closed_issues;
+        (total > 0).then(|| self.closed_issues as f64 * 100.0 / total as f64)
+    }
+}
+
+// Hours per sampled merged pull request; PRs merged without a review have no first review
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct ReviewLatency {
+    pub first_review_hours: Vec<u32>,
+    pub merge_hours: Vec<u32>,
+}
+
+// No manifests means the dependency graph is disabled or found nothing to parse
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Dependencies {
+    pub manifests: u32,
+    pub direct: u32,
+}
+
+// Asset download counts summed over the repository's releases
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Downloads {
+    pub releases: u32,
+    pub total: u64,
+    // Downloads of the newest release, the current version's adoption
+    pub latest: Option<u64>,
+    // Only the first page was read, so the counts are lower bounds
+    pub truncated: bool,
+}
+
+// README and description presence with the detected languages
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Docs {
+    pub has_readme: bool,
+    pub readme_language: Option<String>,
+    pub has_description: bool,
+    pub description_language: Option<String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct DocsCounts {
+    pub checked: u32,
+    pub without_readme: u32,
+    pub without_description: u32,
+    // Repositories per detected README language
+    pub languages: BTreeMap<String, u32>,
+}
+
+// Stars and forks gained since the organization's previous snapshot
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Growth {
+    pub since: DateTime<Utc>,
+    pub stars: u32,
+    pub forks: u32,
+    // The newest stars or forks ran past what is listed, so the counts are lower bounds
+    pub truncated: bool,
+}
+
+// Community features switched on in the repository settings
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Features {
+    pub has_issues: Option<bool>,
+    pub has_wiki: Option<bool>,
+    pub has_pages: Option<bool>,
+    pub has_discussions: Option<bool>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct FeatureCounts {
+    pub checked: u32,
+    pub issues: u32,
+    pub wiki: u32,
+    pub pages: u32,
+    pub discussions: u32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DeepStats {
+    pub code_lines: u64,
+    pub files: u32,
+    pub largest_files: Vec<(String, u64)>,
+    #[serde(default)]
+    pub churn: Option<Churn>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Churn {
+    pub commits: u32,
+    pub added: u64,
+    pub deleted: u64,
+    // Lowercased author emails, compared across repositories for contributor overlap
+    pub authors: Vec<String>,
+    pub hotspots: Vec<(String, u64)>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AuditEvent {
+    pub action: String,
+    pub actor: Option<String>,
+    pub subject: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Members {
+    pub members: u32,
+    pub public_members: u32,
+    pub teams: Option<u32>,
+}
+
+impl Members {
+    pub fn describe(&self) -> String {
+        let teams = match self.teams {
+            Some(teams) => format!(", {teams} teams"),
+            None => "".to_string(),
+        };
+        format!(
+            "{} members ({} public){teams}",
+            self.members, self.public_members
+        )
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct OrgProfile {
+    pub description: Option<String>,
+    pub blog: Option<String>,
+    pub location: Option<String>,
+    pub public_repos: u32,
+    pub followers: u32,
+}
+
+impl OrgProfile {
+    pub const CSV_FIELDS: [&'static str; 5] = [
+        "description",
+        "blog",
+        "location",
+        "public_repos",
+        "org_followers",
+    ];
+
+    pub fn csv_values(&self) -> [String; 5] {
+        [
+            self.description.clone().unwrap_or_default(),
+            self.blog.clone().unwrap_or_default(),
+            self.location.clone().unwrap_or_default(),
+            self.public_repos.to_string(),
+            self.followers.to_string(),
+        ]
+    }
+
+    pub fn describe(&self) -> String {
+        let mut lines: Vec<String> = [&self.description, &self.blog, &self.location]
+            .into_iter()
+            .flatten()
+            .filter(|line| !line.is_empty())
+            .cloned()
+            .collect();
+        lines.push(format!(
+            "{} public repositories, {} followers",
+            self.public_repos, self.followers
+        ));
+        lines.join("\n")
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DependencyUpdates {
+    pub opened: u32,
+    pub merged: u32,
+    pub closed_unmerged: u32,
+    // Only the first page was read, so the counts are lower bounds
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+impl DependencyUpdates {
+    pub fn describe(&self) -> String {
+        format!(
+            "{} opened, {} merged, {} closed",
+            self.opened, self.merged, self.closed_unmerged
+        )
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ActionsPolicy {
+    pub enabled: bool,
+    pub allowed_actions: Option<String>,
+    pub default_workflow_permissions: String,
+}
+
+impl ActionsPolicy {
+    pub fn is_compliant(&self) -> bool {
+        !self.enabled
+            || (self.allowed_actions.as_deref() != Some("all")
+                && self.default_workflow_permissions == "read")
+    }
+
+    pub fn describe(&self) -> String {
+        if !self.enabled {
+            return "disabled".to_string();
+        }
+        format!(
+            "{} / token {}",
+            self.allowed_actions.as_deref().unwrap_or("unknown"),
+            self.default_workflow_permissions
+        )
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MergeSettings {
+    pub allow_merge_commit: Option<bool>,
+    pub allow_squash_merge: Option<bool>,
+    pub allow_rebase_merge: Option<bool>,
+    pub allow_auto_merge: Option<bool>,
+    pub delete_branch_on_merge: Option<bool>,
+}
+
+impl MergeSettings {
+    pub fn methods(&self) -> String {
+        let methods: Vec<&str> = [
+            (self.allow_merge_commit, "merge"),
+            (self.allow_squash_merge, "squash"),
+            (self.allow_rebase_merge, "rebase"),
+        ]
+        .into_iter()
+        .filter(|(allowed, _)| allowed.unwrap_or(false))
+        .map(|(_, name)| name)
+        .collect();
+        methods.join(",")
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "{} / auto-merge {} / delete branch {}",
+            self.methods(),
+            if self.allow_auto_merge.unwrap_or(false) {
+                "on"
+            } else {
+                "off"
+            },
+            if self.delete_branch_on_merge.unwrap_or(false) {
+                "on"
+            } else {
+                "off"
+            },
+        )
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PushActivity {
+    pub total: u32,
+    pub by_actor: Vec<(String, u32)>,
+    // Only the first page was read, so the counts are lower bounds
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Community {
+    pub health_percentage: u32,
+    pub has_readme: bool,
+    pub has_contributing: bool,
+    pub has_issue_template: bool,
+    pub has_pr_template: bool,
+    pub has_issue_forms: Option<bool>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct CommunityCounts {
+    pub checked: u32,
+    pub readme: u32,
+    pub contributing: u32,
+    pub issue_templates: u32,
+    pub issue_forms: u32,
+    pub pr_templates: u32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Traffic {
+    pub views: u32,
+    pub unique_views: u32,
+    pub clones: u32,
+    pub unique_clones: u32,
+}
+
+impl RepoStats {
+    pub fn is_empty(&self) -> bool {
+        self.pushed_at.is_none()
+    }
+
+    // An empty repository has been idle since it was created
+    pub fn last_push(&self) -> DateTime<Utc> {
+        self.pushed_at.unwrap_or(self.created_at)
+    }
+
+    pub fn is_unchanged(&self, cached: &RepoStats) -> bool {
+        self.updated_at == cached.updated_at && self.pushed_at == cached.pushed_at
+    }
+
+    // Only what changes with a push or a settings edit is reused. Counts, alerts and rolling
+    // windows change without either and are fetched again, like enrichments the cache lacks
+    pub fn reuse_enrichments(&mut self, cached: &RepoStats) {
+        self.community = cached.community.clone();
+        self.devcontainer = cached.devcontainer;
+        self.merge = cached.merge.clone();
+        self.codeowners = cached.codeowners.clone();
+        self.branch_count = cached.branch_count;
+        self.dependencies = cached.dependencies.clone();
+        // Listing flags are fresh, only discussions come from the cache
+        if let (Some(features), Some(cached)) = (self.features.as_mut(), &cached.features) {
+            features.has_discussions = cached.has_discussions;
+        } else {
+            self.features = cached.features.clone();
+        }
+        self.docs = cached.docs.clone();
+        // Churn covers a window that moves with every run
+        self.deep = cached.deep.clone().filter(|deep| deep.churn.is_none());
+    }
+
+    pub fn from_repo(repo: Repository) -> Result<Self, String> {
+        let missing = |field: &str| format!("missing {field}");
+        Ok(Self {
+            stars: repo
+                .stargazers_count
+                .ok_or_else(|| missing("stargazers_count"))?,
+            forks: repo.forks_count.ok_or_else(|| missing("forks_count"))?,
+            license: repo.license.map(|l| l.name),
+            followers: repo
+                .watchers_count
+                .ok_or_else(|| missing("watchers_count"))?,
+            updated_at: repo.updated_at.ok_or_else(|| missing("updated_at"))?,
+            pushed_at: repo.pushed_at,
+            open_issues_count: repo
+                .open_issues_count
+                .ok_or_else(|| missing("open_issues_count"))?,
+            size: repo.size.ok_or_else(|| missing("size"))?,
+            created_at: repo.created_at.ok_or_else(|| missing("created_at"))?,
+            open_prs: None,
+            traffic: None,
+            can_push: repo.permissions.as_ref().map(|p| p.push).unwrap_or(false),
+            releases_last_year: None,
+            community: None,
+            health: None,
+            pushes: None,
+            devcontainer: None,
+            merge: None,
+            actions: None,
+            deployments: None,
+            traceability: None,
+            security: None,
+            default_branch: repo.default_branch,
+            ci: None,
+            star_history: None,
+            dependency_updates: None,
+            advisories: None,
+            branch_protected: None,
+            issue_ages: None,
+            status: None,
+            milestones: None,
+            deep: None,
+            owner: None,
+            codeowners: None,
+            branch_count: None,
+            review_latency: None,
+            star_velocity: None,
+            dependencies: None,
+            features: Some(Features {
+                has_issues: repo.has_issues,
+                has_wiki: repo.has_wiki,
+                has_pages: repo.has_pages,
+                has_discussions: None,
+            }),
+            labels: None,
+            downloads: None,
+            docs: None,
+            growth: None,
+            commit_days: None,
+            custom: BTreeMap::new(),
+            releases_truncated: false,
+            advisories_truncated: false,
+            issue_ages_truncated: false,
+            languages: None,
+            contributors: None,
+            language: repo
+                .language
+                .as_ref()
+                .and_then(|l| l.as_str())
+                .map(str::to_string),
+            visibility: repo.visibility.clone().or_else(|| {
+                repo.private
+                    .map(|private| if private { "private" } else { "public" }.to_string())
+            }),
+            html_url: repo.html_url.map(|u| u.to_string()),
+            ssh_url: repo.ssh_url,
+            clone_url: repo.clone_url.map(|u| u.to_string()),
+            name: repo.name,
+            description: repo.description,
+            homepage: repo.homepage,
+            topics: repo.topics.unwrap_or_default(),
+        })
+    }
+
+    pub fn urls(&self) -> [&str; 3] {
+        [&self.html_url, &self.ssh_url, &self.clone_url].map(|url| url.as_deref().unwrap_or(""))
+    }
+
+    pub fn open_issues_only(&self) -> Option<u32> {
+        self.open_prs
+            .map(|prs| self.open_issues_count.saturating_sub(prs))
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SumStats {
+    pub stars: u32,
+    pub forks: u32,
+    pub followers: u32,
+    pub updated_at: DateTime<Utc>,
+    pub pushed_at: DateTime<Utc>,
+    pub open_issues_count: u32,
+    // KiB like the API reports per repository; an organization's sum outgrows u32
+    pub size: u64,
+    #[serde(default)]
+    pub open_prs: u32,
+    #[serde(default)]
+    pub views: u32,
+    #[serde(default)]
+    pub clones: u32,
+    #[serde(default)]
+    pub health_sum: u32,
+    #[serde(default)]
+    pub health_count: u32,
+    #[serde(default)]
+    pub pushes: u32,
+    #[serde(default)]
+    pub devcontainer_count: u32,
+    #[serde(default)]
+    pub devcontainer_checked: u32,
+    #[serde(default)]
+    pub community: CommunityCounts,
+    #[serde(default)]
+    pub merge_configs: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub actions_checked: u32,
+    #[serde(default)]
+    pub actions_compliant: u32,
+    #[serde(default)]
+    pub deployments_30d: u32,
+    #[serde(default)]
+    pub traceability_checked: u32,
+    #[serde(default)]
+    pub traceability_anomalies: u32,
+    #[serde(default)]
+    pub dependabot_alerts: u32,
+    #[serde(default)]
+    pub code_scanning_alerts: u32,
+    #[serde(default)]
+    pub ci_checked: u32,
+    #[serde(default)]
+    pub ci_failing: u32,
+    #[serde(default)]
+    pub licenses: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub star_history: Vec<u32>,
+    #[serde(default)]
+    pub dependency_prs_opened: u32,
+    #[serde(default)]
+    pub dependency_prs_merged: u32,
+    #[serde(default)]
+    pub dependency_prs_closed: u32,
+    #[serde(default)]
+    pub advisories: BTreeMap<String, BTreeMap<String, u32>>,
+    #[serde(default)]
+    pub private_reporting_checked: u32,
+    #[serde(default)]
+    pub private_reporting_enabled: u32,
+    #[serde(default)]
+    pub protection_checked: u32,
+    #[serde(default)]
+    pub unprotected_branches: u32,
+    #[serde(default)]
+    pub statuses: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub topics: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub milestones_open: u32,
+    #[serde(default)]
+    pub milestone_issues_open: u32,
+    #[serde(default)]
+    pub milestone_issues_closed: u32,
+    #[serde(default)]
+    pub code_lines: u64,
+    #[serde(default)]
+    pub files: u64,
+    #[serde(default)]
+    pub churn_commits: u64,
+    #[serde(default)]
+    pub churn_lines: u64,
+    #[serde(default)]
+    pub codeowners_checked: u32,
+    #[serde(default)]
+    pub codeowners_present: u32,
+    #[serde(default)]
+    pub code_owners: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub branches: u32,
+    #[serde(default)]
+    pub languages: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub visibilities: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub star_velocity: f64,
+    #[serde(default)]
+    pub direct_dependencies: u32,
+    #[serde(default)]
+    pub dependency_graphs_checked: u32,
+    #[serde(default)]
+    pub dependency_graphs_missing: u32,
+    #[serde(default)]
+    pub features: FeatureCounts,
+    #[serde(default)]
+    pub labels: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub release_downloads: u64,
+    #[serde(default)]
+    pub docs: DocsCounts,
+    #[serde(default)]
+    pub new_stars: u32,
+    #[serde(default)]
+    pub new_forks: u32,
+    #[serde(default)]
+    pub commits_12_months: u32,
+    #[serde(default)]
+    pub review_latency: ReviewLatency,
+    // Ages in days of every open issue, too large to keep in the history
+    #[serde(skip)]
+    pub issue_ages: Vec<u32>,
+    // Commits per day across the repositories, only kept for the heatmap
+    #[serde(skip)]
+    pub commit_days: BTreeMap<NaiveDate, u32>,
+}
+
+impl Default for SumStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SumStats {
+    pub fn new() -> Self {
+        Self {
+            stars: 0,
+            forks: 0,
+            followers: 0,
+            updated_at: DateTime::<Utc>::from_utc(NaiveDateTime::MIN, Utc),
+            pushed_at: DateTime::<Utc>::from_utc(NaiveDateTime::MIN, Utc),
+            open_issues_count: 0,
+            size: 0,
+            open_prs: 0,
+            views: 0,
+            clones: 0,
+            health_sum: 0,
+            health_count: 0,
+            pushes: 0,
+            devcontainer_count: 0,
+            devcontainer_checked: 0,
+            community: CommunityCounts::default(),
+            merge_configs: BTreeMap::new(),
+            actions_checked: 0,
+            actions_compliant: 0,
+            deployments_30d: 0,
+            traceability_checked: 0,
+            traceability_anomalies: 0,
+            dependabot_alerts: 0,
+            code_scanning_alerts: 0,
+            ci_checked: 0,
+            ci_failing: 0,
+            licenses: BTreeMap::new(),
+            star_history: vec![],
+            dependency_prs_opened: 0,
+            dependency_prs_merged: 0,
+            dependency_prs_closed: 0,
+            advisories: BTreeMap::new(),
+            private_reporting_checked: 0,
+            private_reporting_enabled: 0,
+            protection_checked: 0,
+            unprotected_branches: 0,
+            statuses: BTreeMap::new(),
+            topics: BTreeMap::new(),
+            milestones_open: 0,
+            milestone_issues_open: 0,
+            milestone_issues_closed: 0,
+            code_lines: 0,
+            files: 0,
+            churn_commits: 0,
+            churn_lines: 0,
+            codeowners_checked: 0,
+            codeowners_present: 0,
+            code_owners: BTreeMap::new(),
+            branches: 0,
+            languages: BTreeMap::new(),
+            visibilities: BTreeMap::new(),
+            star_velocity: 0.0,
+            direct_dependencies: 0,
+            dependency_graphs_checked: 0,
+            dependency_graphs_missing: 0,
+            features: FeatureCounts::default(),
+            labels: BTreeMap::new(),
+            release_downloads: 0,
+            docs: DocsCounts::default(),
+            new_stars: 0,
+            new_forks: 0,
+            commits_12_months: 0,
+            review_latency: ReviewLatency::default(),
+            issue_ages: vec![],
+            commit_days: BTreeMap::new(),
+        }
+    }
+
+    pub fn update(&mut self, repo: &RepoStats) {
+        self.stars += repo.stars;
+        self.forks += repo.forks;
+        self.followers += repo.followers;
+        self.updated_at = self.updated_at.max(repo.updated_at);
+        if let Some(pushed_at) = repo.pushed_at {
+            self.pushed_at = self.pushed_at.max(pushed_at);
+        }
+        self.open_issues_count += repo.open_issues_count;
+        self.size += repo.size as u64;
+        *self
+            .licenses
+            .entry(repo.license.clone().unwrap_or(NO_LICENSE.to_string()))
+            .or_insert(0) += 1;
+        for topic in &repo.topics {
+            *self.topics.entry(topic.clone()).or_insert(0) += 1;
+        }
+        self.open_prs += repo.open_prs.unwrap_or(0);
+        if let Some(traffic) = &repo.traffic {
+            self.views += traffic.views;
+            self.clones += traffic.clones;
+        }
+        if let Some(pushes) = &repo.pushes {
+            self.pushes += pushes.total;
+        }
+        if let Some(devcontainer) = repo.devcontainer {
+            self.devcontainer_checked += 1;
+            self.devcontainer_count += devcontainer as u32;
+        }
+        if let Some(community) = &repo.community {
+            self.community.checked += 1;
+            self.community.readme += community.has_readme as u32;
+            self.community.contributing += community.has_contributing as u32;
+            self.community.issue_templates +=
+                (community.has_issue_template || community.has_issue_forms == Some(true)) as u32;
+            self.community.issue_forms += (community.has_issue_forms == Some(true)) as u32;
+            self.community.pr_templates += community.has_pr_template as u32;
+        }
+        if let Some(merge) = &repo.merge {
+            *self.merge_configs.entry(merge.describe()).or_insert(0) += 1;
+        }
+        if let Some(actions) = &repo.actions {
+            self.actions_checked += 1;
+            self.actions_compliant += actions.is_compliant() as u32;
+        }
+        if let Some(deployments) = &repo.deployments {
+            self.deployments_30d += deployments.last_30d;
+        }
+        if let Some(traceability) = &repo.traceability {
+            self.traceability_checked += 1;
+            self.traceability_anomalies += traceability.anomalies();
+        }
+        if let Some(security) = &repo.security {
+            self.dependabot_alerts += security.dependabot.unwrap_or(0);
+            self.code_scanning_alerts += security.code_scanning.unwrap_or(0);
+            if let Some(enabled) = security.private_reporting {
+                self.private_reporting_checked += 1;
+                self.private_reporting_enabled += enabled as u32;
+            }
+        }
+        if let Some(ci) = &repo.ci {
+            self.ci_checked += 1;
+            self.ci_failing += is_failing_ci(ci) as u32;
+        }
+        if let Some(history) = &repo.star_history {
+            self.star_history
+                .resize(history.len().max(self.star_history.len()), 0);
+            for (sum, count) in self.star_history.iter_mut().zip(history) {
+                *sum += count;
+            }
+        }
+        if let Some(updates) = &repo.dependency_updates {
+            self.dependency_prs_opened += updates.opened;
+            self.dependency_prs_merged += updates.merged;
+            self.dependency_prs_closed += updates.closed_unmerged;
+        }
+        for advisory in repo.advisories.iter().flatten() {
+            *self
+                .advisories
+                .entry(advisory.published_at.year().to_string())
+                .or_default()
+                .entry(advisory.severity.clone())
+                .or_insert(0) += 1;
+        }
+        if let Some(protected) = repo.branch_protected {
+            self.protection_checked += 1;
+            self.unprotected_branches += !protected as u32;
+        }
+        for milestone in repo.milestones.iter().flatten() {
+            self.milestones_open += 1;
+            self.milestone_issues_open += milestone.open_issues;
+            self.milestone_issues_closed += milestone.closed_issues;
+        }
+        if let Some(deep) = &repo.deep {
+            self.code_lines += deep.code_lines;
+            self.files += deep.files as u64;
+            if let Some(churn) = &deep.churn {
+                self.churn_commits += churn.commits as u64;
+                self.churn_lines += churn.added + churn.deleted;
+            }
+        }
+        self.branches += repo.branch_count.unwrap_or(0);
+        if let Some(latency) = &repo.review_latency {
+            self.review_latency
+                .first_review_hours
+                .extend(&latency.first_review_hours);
+            self.review_latency.merge_hours.extend(&latency.merge_hours);
+        }
+        if let Some(language) = &repo.language {
+            *self.languages.entry(language.clone()).or_insert(0) += 1;
+        }
+        self.star_velocity += repo.star_velocity.unwrap_or(0.0);
+        if let Some(dependencies) = &repo.dependencies {
+            self.dependency_graphs_checked += 1;
+            self.dependency_graphs_missing += (dependencies.manifests == 0) as u32;
+            self.direct_dependencies += dependencies.direct;
+        }
+        if let Some(features) = &repo.features {
+            self.features.checked += 1;
+            self.features.issues += features.has_issues.unwrap_or(false) as u32;
+            self.features.wiki += features.has_wiki.unwrap_or(false) as u32;
+            self.features.pages += features.has_pages.unwrap_or(false) as u32;
+            self.features.discussions += features.has_discussions.unwrap_or(false) as u32;
+        }
+        if let Some(downloads) = &repo.downloads {
+            self.release_downloads += downloads.total;
+        }
+        if let Some(docs) = &repo.docs {
+            self.docs.checked += 1;
+            self.docs.without_readme += !docs.has_readme as u32;
+            self.docs.without_description += !docs.has_description as u32;
+            if let Some(language) = &docs.readme_language {
+                *self.docs.languages.entry(language.clone()).or_insert(0) += 1;
+            }
+        }
+        if let Some(growth) = &repo.growth {
+            self.new_stars += growth.stars;
+            self.new_forks += growth.forks;
+        }
+        for (day, count) in repo.commit_days.iter().flatten() {
+            self.commits_12_months += count;
+            *self.commit_days.entry(*day).or_insert(0) += count;
+        }
+        for (label, count) in repo.labels.iter().flatten() {
+            *self.labels.entry(label.clone()).or_insert(0) += count;
+        }
+        if let Some(visibility) = &repo.visibility {
+            *self.visibilities.entry(visibility.clone()).or_insert(0) += 1;
+        }
+        if let Some(owners) = &repo.codeowners {
+            self.codeowners_checked += 1;
+            self.codeowners_present += !owners.is_empty() as u32;
+            for owner in owners {
+                *self.code_owners.entry(owner.clone()).or_insert(0) += 1;
+            }
+        }
+        if let Some(status) = repo.status {
+            *self.statuses.entry(status.label().to_string()).or_insert(0) += 1;
+        }
+        if let Some(ages) = &repo.issue_ages {
+            self.issue_ages.extend(ages);
+        }
+        if let Some(health) = repo.health {
+            self.health_sum += health as u32;
+            self.health_count += 1;
+        }
+    }
+
+    pub fn health_avg(&self) -> Option<f64> {
+        if self.health_count == 0 {
+            return None;
+        }
+        Some(self.health_sum as f64 / self.health_count as f64)
+    }
+
+    pub fn devcontainer_pct(&self) -> Option<f64> {
+        if self.devcontainer_checked == 0 {
+            return None;
+        }
+        Some(self.devcontainer_count as f64 * 100.0 / self.devcontainer_checked as f64)
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use comfy_table::Color;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_DAYS: [i64; 3] = [90, 180, 365];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Status {
+    Active,
+    Slowing,
+    Stale,
+    Dead,
+}
+
+pub const ALL: [Status; 4] = [Status::Active, Status::Slowing, Status::Stale, Status::Dead];
+
+impl Status {
+    pub fn classify(pushed_at: DateTime<Utc>, now: DateTime<Utc>, days: &[i64; 3]) -> Self {
+        let idle = (now - pushed_at).num_days();
+        if idle < days[0] {
+            Status::Active
+        } else if idle < days[1] {
+            Status::Slowing
+        } else if idle < days[2] {
+            Status::Stale
+        } else {
+            Status::Dead
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Status::Active => "Active",
+            Status::Slowing => "Slowing",
+            Status::Stale => "Stale",
+            Status::Dead => "Dead",
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            Status::Active => Color::Green,
+            Status::Slowing => Color::Yellow,
+            Status::Stale => Color::DarkYellow,
+            Status::Dead => Color::Red,
+        }
+    }
+}
+
+pub fn parse_days(days: &[i64]) -> Result<[i64; 3], String> {
+    match days {
+        [] => Ok(DEFAULT_DAYS),
+        [active, slowing, stale] if 0 < *active && active < slowing && slowing < stale => {
+            Ok([*active, *slowing, *stale])
+        }
+        _ => Err("--status-days needs three increasing day counts, e.g. 90,180,365".to_string()),
+    }
+}
@@ -0,0 +1,60 @@
+use comfy_table::{Cell, Color, Table};
+use serde::Serialize;
+
+use crate::render;
+
+pub const SUMMARY_PATH: &str = "out/run_summary.json";
+
+#[derive(Default, Serialize)]
+pub struct RunSummary {
+    pub orgs: u32,
+    pub failed_orgs: u32,
+    pub repos: u32,
+    pub metrics_skipped: u32,
+    pub api_calls: u32,
+    pub warnings: u32,
+    pub quality: u32,
+}
+
+impl RunSummary {
+    // Share of organizations scanned, scaled down by the share of per-repository metrics that could not be collected
+    pub fn finish(&mut self, metrics_per_repo: u32) {
+        let total_orgs = self.orgs + self.failed_orgs;
+        if total_orgs == 0 {
+            return;
+        }
+        let expected = (self.repos * metrics_per_repo.max(1)).max(1) as f64;
+        let collected = 1.0 - (self.metrics_skipped as f64 / expected).min(1.0);
+        self.quality = (self.orgs as f64 / total_orgs as f64 * collected * 100.0).round() as u32;
+    }
+
+    pub fn rows(&self) -> [(&'static str, u32); 7] {
+        [
+            ("Organizations processed", self.orgs),
+            ("Organizations failed", self.failed_orgs),
+            ("Repositories counted", self.repos),
+            ("Metrics skipped", self.metrics_skipped),
+            ("API calls", self.api_calls),
+            ("Warnings", self.warnings),
+            ("Quality score (%)", self.quality),
+        ]
+    }
+
+    pub fn table(&self) -> Table {
+        let mut table = render::new_table();
+        table.set_header(vec![
+            Cell::new("Run summary").fg(Color::Green),
+            Cell::new("").fg(Color::Green),
+        ]);
+        for (name, value) in self.rows() {
+            table.add_row(vec![name.to_string(), value.to_string()]);
+        }
+        table
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self).expect("serialize run summary ok");
+        std::fs::write(SUMMARY_PATH, content)
+            .map_err(|e| format!("Cannot write {SUMMARY_PATH}: {e}"))
+    }
+}
@@ -0,0 +1,33 @@
+use std::path::Path;
+
+use serde_json::Value;
+use tera::Tera;
+
+const NAME: &str = "report";
+
+pub struct Template {
+    tera: Tera,
+}
+
+impl Template {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let mut tera = Tera::default();
+        tera.add_template_file(path, Some(NAME))
+            .map_err(|e| format!("Cannot load template {}: {e}", path.display()))?;
+        Ok(Self { tera })
+    }
+
+    pub fn render(&self, data: &Value) -> Result<String, String> {
+        let context = tera::Context::from_value(data.clone()).map_err(|e| e.to_string())?;
+        self.tera.render(NAME, &context).map_err(|e| {
+            // Tera keeps the useful part of the message in the error's source
+            let mut message = e.to_string();
+            let mut source = std::error::Error::source(&e);
+            while let Some(cause) = source {
+                message = format!("{message}: {cause}");
+                source = cause.source();
+            }
+            format!("Cannot render template: {message}")
+        })
+    }
+}
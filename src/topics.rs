@@ -0,0 +1,152 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::Utc;
+use comfy_table::{Cell, Color};
+
+use crate::diagnostics::Kind;
+use crate::fetch;
+use crate::render;
+use crate::scan::{self, Context};
+use crate::stats::RepoStats;
+
+const MANIFESTS: [(&str, &str); 12] = [
+    ("Cargo.toml", "rust"),
+    ("package.json", "nodejs"),
+    ("pyproject.toml", "python"),
+    ("requirements.txt", "python"),
+    ("go.mod", "golang"),
+    ("pom.xml", "maven"),
+    ("build.gradle", "gradle"),
+    ("Gemfile", "ruby"),
+    ("composer.json", "php"),
+    ("Dockerfile", "docker"),
+    ("Chart.yaml", "helm"),
+    ("main.tf", "terraform"),
+];
+
+const KEYWORDS: [&str; 16] = [
+    "api",
+    "cli",
+    "library",
+    "sdk",
+    "framework",
+    "plugin",
+    "kubernetes",
+    "docker",
+    "terraform",
+    "graphql",
+    "database",
+    "parser",
+    "compiler",
+    "documentation",
+    "template",
+    "github-action",
+];
+
+fn topic(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .trim_matches('-')
+        .to_string()
+}
+
+pub fn suggest(repo: &RepoStats, languages: &[String], files: &[String]) -> Vec<String> {
+    let mut suggested = BTreeSet::new();
+    if let Some(language) = languages.first() {
+        suggested.insert(topic(language));
+    }
+    for (manifest, name) in MANIFESTS {
+        if files.iter().any(|f| f == manifest) {
+            suggested.insert(name.to_string());
+        }
+    }
+    let description = repo
+        .description
+        .as_deref()
+        .unwrap_or_default()
+        .to_lowercase();
+    let words: Vec<&str> = description
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+        .collect();
+    for keyword in KEYWORDS {
+        if words.contains(&keyword) {
+            suggested.insert(keyword.to_string());
+        }
+    }
+    suggested
+        .into_iter()
+        .filter(|t| !t.is_empty() && !repo.topics.contains(t))
+        .collect()
+}
+
+pub fn summary(topics: &BTreeMap<String, u32>) -> String {
+    if topics.is_empty() {
+        return "Topics: none".to_string();
+    }
+    let mut topics: Vec<(&String, &u32)> = topics.iter().collect();
+    topics.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    let counts: Vec<String> = topics
+        .into_iter()
+        .map(|(topic, count)| match count {
+            1 => format!("{topic}: 1 repo"),
+            n => format!("{topic}: {n} repos"),
+        })
+        .collect();
+    format!("Topics: {}", counts.join(", "))
+}
+
+pub async fn run(ctx: &Context, orgs: &[String], apply: bool) -> Result<(), String> {
+    let now = Utc::now();
+    for org_name in orgs {
+        let report = match scan::scan_org(ctx, org_name, now).await {
+            Ok(report) => report,
+            Err(e) => {
+                ctx.diagnostics
+                    .emit(Kind::OrgNotFound, Some(org_name), None, &e.to_string());
+                continue;
+            }
+        };
+        let mut table = render::new_table();
+        table.set_header(vec![
+            Cell::new(&report.name).fg(Color::Green),
+            Cell::new("Topics").fg(Color::Green),
+            Cell::new("Suggested").fg(Color::Green),
+        ]);
+        for (repo, _) in &report.rows {
+            let (languages, files) =
+                match fetch::topic_signals(&ctx.github, org_name, &repo.name).await {
+                    Ok(signals) => signals,
+                    Err(e) => {
+                        ctx.diagnostics.emit(
+                            Kind::DegradedMetric,
+                            Some(org_name),
+                            Some(&repo.name),
+                            &format!("topic signals unavailable: {e}"),
+                        );
+                        continue;
+                    }
+                };
+            let suggested = suggest(repo, &languages, &files);
+            if suggested.is_empty() {
+                continue;
+            }
+            let mut status = Cell::new(suggested.join(", "));
+            if apply {
+                let topics: Vec<String> = repo.topics.iter().chain(&suggested).cloned().collect();
+                status = match fetch::set_topics(&ctx.github, org_name, &repo.name, &topics).await {
+                    Ok(()) => status.fg(Color::Green),
+                    Err(e) => Cell::new(format!("{}: {e}", suggested.join(", "))).fg(Color::Red),
+                };
+            }
+            table.add_row(vec![
+                Cell::new(&repo.name),
+                Cell::new(repo.topics.join(", ")),
+                status,
+            ]);
+        }
+        println!("{table}");
+    }
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use comfy_table::{Cell, Color, Table};
+
+use crate::render;
+use crate::scan::OrgReport;
+
+pub fn table(report: &OrgReport) -> Table {
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Repository").fg(Color::Green),
+        Cell::new("Traceability anomaly").fg(Color::Green),
+        Cell::new("Ref").fg(Color::Green),
+    ]);
+    for (repo, _) in &report.rows {
+        let Some(traceability) = &repo.traceability else {
+            continue;
+        };
+        for tag in &traceability.undeployed {
+            table.add_row(vec![
+                Cell::new(&repo.name),
+                Cell::new("released but not deployed").fg(Color::Yellow),
+                Cell::new(tag),
+            ]);
+        }
+        for label in &traceability.unreleased {
+            table.add_row(vec![
+                Cell::new(&repo.name),
+                Cell::new("deployed but not released").fg(Color::Yellow),
+                Cell::new(label),
+            ]);
+        }
+    }
+    table
+}
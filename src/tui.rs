@@ -0,0 +1,566 @@
+use std::collections::HashMap;
+use std::io::{self, Stdout};
+use std::time::Duration;
+
+use chrono::Utc;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{
+    Block, Borders, Cell, List, ListItem, ListState, Paragraph, Row, Table, TableState, Wrap,
+};
+use ratatui::{Frame, Terminal};
+
+use crate::diagnostics::Kind;
+use crate::fetch::{self, RepoDetail};
+use crate::filter::Filter;
+use crate::format::Style as ValueStyle;
+use crate::history::{RepoSnapshot, Snapshot};
+use crate::scan::{self, Context, OrgReport};
+use crate::stats::RepoStats;
+
+#[derive(PartialEq, Eq)]
+enum Focus {
+    Orgs,
+    Repos,
+}
+
+struct App {
+    reports: Vec<OrgReport>,
+    org_state: ListState,
+    repo_state: TableState,
+    focus: Focus,
+    sort: Option<usize>,
+    filter: String,
+    expression: Option<Result<Filter, String>>,
+    editing_filter: bool,
+    details: HashMap<(String, String), Result<RepoDetail, String>>,
+    history: Vec<Snapshot>,
+    diff: bool,
+    // Positions counted back from the organization's newest snapshot
+    base: usize,
+    target: usize,
+}
+
+struct DiffRow<'a> {
+    name: &'a str,
+    before: Option<&'a RepoSnapshot>,
+    after: Option<&'a RepoSnapshot>,
+}
+
+impl App {
+    fn report(&self) -> Option<&OrgReport> {
+        self.org_state.selected().and_then(|i| self.reports.get(i))
+    }
+
+    fn snapshots(&self) -> Vec<&Snapshot> {
+        let Some(report) = self.report() else {
+            return vec![];
+        };
+        let mut snapshots: Vec<&Snapshot> = self
+            .history
+            .iter()
+            .filter(|s| s.org.eq_ignore_ascii_case(&report.org))
+            .collect();
+        snapshots.sort_by_key(|s| std::cmp::Reverse(s.collected_at));
+        snapshots
+    }
+
+    fn compared(&self) -> Option<(&Snapshot, &Snapshot)> {
+        let snapshots = self.snapshots();
+        Some((*snapshots.get(self.base)?, *snapshots.get(self.target)?))
+    }
+
+    fn diff_rows(&self) -> Vec<DiffRow> {
+        let Some((base, target)) = self.compared() else {
+            return vec![];
+        };
+        let filter = self.filter.to_lowercase();
+        let mut names: Vec<&str> = base
+            .repos
+            .iter()
+            .chain(&target.repos)
+            .map(|r| r.name.as_str())
+            .filter(|name| name.to_lowercase().contains(&filter))
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+            .into_iter()
+            .map(|name| DiffRow {
+                name,
+                before: base.repos.iter().find(|r| r.name == name),
+                after: target.repos.iter().find(|r| r.name == name),
+            })
+            .collect()
+    }
+
+    fn step_snapshot(&mut self, target: bool, delta: i64) {
+        let len = self.snapshots().len();
+        let position = if target {
+            &mut self.target
+        } else {
+            &mut self.base
+        };
+        *position = (*position as i64 + delta).clamp(0, len.saturating_sub(1) as i64) as usize;
+        self.repo_state.select(Some(0));
+    }
+
+    fn visible_rows(&self, ctx: &Context) -> Vec<&RepoStats> {
+        let Some(report) = self.report() else {
+            return vec![];
+        };
+        let filter = self.filter.to_lowercase();
+        let mut rows: Vec<&RepoStats> = report
+            .rows
+            .iter()
+            .map(|(repo, _)| repo)
+            .filter(|repo| match &self.expression {
+                Some(Ok(expression)) => expression.matches(repo),
+                Some(Err(_)) => true,
+                None => {
+                    filter.is_empty()
+                        || repo.name.to_lowercase().contains(&filter)
+                        || repo
+                            .description
+                            .as_deref()
+                            .map(|d| d.to_lowercase().contains(&filter))
+                            .unwrap_or(false)
+                        || repo.topics.iter().any(|t| t.contains(&filter))
+                }
+            })
+            .collect();
+        if let Some(column) = self.sort.and_then(|i| ctx.columns.get(i)) {
+            rows.sort_by_key(|repo| std::cmp::Reverse(column.sort_key(repo)));
+        }
+        rows
+    }
+
+    fn selected(&self, ctx: &Context) -> Option<(String, String)> {
+        if self.diff {
+            return None;
+        }
+        let report = self.report()?;
+        let rows = self.visible_rows(ctx);
+        let repo = rows.get(self.repo_state.selected()?)?;
+        Some((report.org.clone(), repo.name.clone()))
+    }
+
+    fn move_selection(&mut self, ctx: &Context, delta: i64) {
+        match self.focus {
+            Focus::Orgs => {
+                let len = self.reports.len() as i64;
+                if len == 0 {
+                    return;
+                }
+                let current = self.org_state.selected().unwrap_or(0) as i64;
+                self.org_state
+                    .select(Some((current + delta).rem_euclid(len) as usize));
+                self.repo_state.select(Some(0));
+            }
+            Focus::Repos => {
+                let len = if self.diff {
+                    self.diff_rows().len()
+                } else {
+                    self.visible_rows(ctx).len()
+                } as i64;
+                if len == 0 {
+                    self.repo_state.select(None);
+                    return;
+                }
+                let current = self.repo_state.selected().unwrap_or(0) as i64;
+                self.repo_state
+                    .select(Some((current + delta).rem_euclid(len) as usize));
+            }
+        }
+    }
+}
+
+fn highlight(active: bool) -> Style {
+    let style = Style::default().add_modifier(Modifier::REVERSED);
+    if active {
+        style.fg(Color::Green)
+    } else {
+        style
+    }
+}
+
+fn changed(before: Option<u32>, after: Option<u32>) -> Cell<'static> {
+    let (Some(before), Some(after)) = (before, after) else {
+        return Cell::from(after.map(|v| v.to_string()).unwrap_or("-".to_string()));
+    };
+    let delta = after as i64 - before as i64;
+    let cell = Cell::from(if delta == 0 {
+        after.to_string()
+    } else {
+        format!("{after} ({delta:+})")
+    });
+    match delta.signum() {
+        1 => cell.style(Style::default().fg(Color::Green)),
+        -1 => cell.style(Style::default().fg(Color::Red)),
+        _ => cell,
+    }
+}
+
+fn draw_diff<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect, detail_area: Rect) {
+    let rows: Vec<Row> = app
+        .diff_rows()
+        .iter()
+        .map(|row| {
+            let name = Cell::from(row.name.to_string());
+            let name = match (row.before, row.after) {
+                (None, _) => name.style(Style::default().fg(Color::Green)),
+                (_, None) => name.style(Style::default().fg(Color::Red)),
+                _ => name,
+            };
+            Row::new(vec![
+                name,
+                Cell::from(
+                    row.before
+                        .map(|r| r.stars.to_string())
+                        .unwrap_or("-".to_string()),
+                ),
+                changed(row.before.map(|r| r.stars), row.after.map(|r| r.stars)),
+                Cell::from(
+                    row.before
+                        .map(|r| r.open_issues.to_string())
+                        .unwrap_or("-".to_string()),
+                ),
+                changed(
+                    row.before.map(|r| r.open_issues),
+                    row.after.map(|r| r.open_issues),
+                ),
+                Cell::from(
+                    row.after
+                        .map(|r| r.pushed_at.format("%Y-%m-%d").to_string())
+                        .unwrap_or("-".to_string()),
+                ),
+            ])
+        })
+        .collect();
+    let (title, summary) = match app.compared() {
+        Some((base, target)) => {
+            let date = |s: &Snapshot| s.collected_at.format("%Y-%m-%d %H:%M").to_string();
+            let stars = target.stats.stars as i64 - base.stats.stars as i64;
+            let issues =
+                target.stats.open_issues_count as i64 - base.stats.open_issues_count as i64;
+            let repos = target.repos.len() as i64 - base.repos.len() as i64;
+            (
+                format!("{} → {}", date(base), date(target)),
+                format!(
+                    "Base: {} ({} repositories)\nTarget: {} ({} repositories)\nRepositories {repos:+}, stars {stars:+}, open issues {issues:+}",
+                    date(base),
+                    base.repos.len(),
+                    date(target),
+                    target.repos.len()
+                ),
+            )
+        }
+        None => (
+            "Comparison".to_string(),
+            "The history has fewer than two snapshots of this organization".to_string(),
+        ),
+    };
+    let header = Row::new(vec![
+        "Repository",
+        "Stars before",
+        "Stars after",
+        "Issues before",
+        "Issues after",
+        "Last push",
+    ])
+    .style(Style::default().fg(Color::Green));
+    let widths = [
+        Constraint::Length(28),
+        Constraint::Length(14),
+        Constraint::Length(16),
+        Constraint::Length(14),
+        Constraint::Length(16),
+        Constraint::Length(12),
+    ];
+    let table = Table::new(rows)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .widths(&widths)
+        .highlight_style(highlight(app.focus == Focus::Repos));
+    f.render_stateful_widget(table, area, &mut app.repo_state);
+    let summary = Paragraph::new(summary)
+        .block(Block::default().borders(Borders::ALL).title("Comparison"))
+        .wrap(Wrap { trim: true });
+    f.render_widget(summary, detail_area);
+}
+
+fn detail_text(repo: &RepoStats, detail: Option<&Result<RepoDetail, String>>) -> String {
+    let mut lines = vec![
+        repo.description
+            .clone()
+            .unwrap_or("(no description)".to_string()),
+        format!("Topics: {}", repo.topics.join(", ")),
+    ];
+    match detail {
+        Some(Ok(detail)) => {
+            lines.push(format!(
+                "Latest release: {}",
+                detail.latest_release.as_deref().unwrap_or("none")
+            ));
+            let total: u64 = detail.languages.iter().map(|(_, bytes)| bytes).sum();
+            let languages: Vec<String> = detail
+                .languages
+                .iter()
+                .map(|(language, bytes)| {
+                    format!(
+                        "{language} {:.0}%",
+                        *bytes as f64 / total.max(1) as f64 * 100.0
+                    )
+                })
+                .collect();
+            lines.push(format!("Languages: {}", languages.join(", ")));
+        }
+        Some(Err(e)) => lines.push(format!("Details unavailable: {e}")),
+        None => lines.push("Loading details...".to_string()),
+    }
+    lines.join("\n")
+}
+
+fn draw<B: Backend>(f: &mut Frame<B>, ctx: &Context, app: &mut App, style: &ValueStyle) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Min(5),
+                Constraint::Length(7),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(28), Constraint::Min(20)].as_ref())
+        .split(chunks[0]);
+
+    let orgs: Vec<ListItem> = app
+        .reports
+        .iter()
+        .map(|report| ListItem::new(format!("{} ({})", report.org, report.rows.len())))
+        .collect();
+    let orgs = List::new(orgs)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Organizations"),
+        )
+        .highlight_style(highlight(app.focus == Focus::Orgs));
+    f.render_stateful_widget(orgs, top[0], &mut app.org_state);
+
+    if app.diff {
+        draw_diff(f, app, top[1], chunks[1]);
+        let help = format!(
+            "q quit | d table view | [/] base snapshot | {{/}} target snapshot | / filter{}",
+            if app.filter.is_empty() {
+                "".to_string()
+            } else {
+                format!(" [{}]", app.filter)
+            }
+        );
+        f.render_widget(Paragraph::new(help), chunks[2]);
+        return;
+    }
+
+    let visible = app.visible_rows(ctx);
+    let rows: Vec<Row> = visible
+        .iter()
+        .map(|repo| {
+            let mut cells = vec![repo.name.clone()];
+            cells.extend(ctx.columns.iter().map(|column| column.value(repo, style)));
+            Row::new(cells)
+        })
+        .collect();
+    let selected_repo = app
+        .repo_state
+        .selected()
+        .and_then(|i| visible.get(i))
+        .map(|repo| (*repo).clone());
+    let mut header = vec!["Repository".to_string()];
+    header.extend(ctx.columns.iter().enumerate().map(|(i, column)| {
+        if app.sort == Some(i) {
+            format!("{} ▼", column.title())
+        } else {
+            column.title().to_string()
+        }
+    }));
+    let mut widths = vec![Constraint::Length(28)];
+    widths.extend(ctx.columns.iter().map(|_| Constraint::Length(14)));
+    let title = match app.report() {
+        Some(report) => format!("{} [{}]", report.name, report.created_year),
+        None => "Repositories".to_string(),
+    };
+    let table = Table::new(rows)
+        .header(Row::new(header).style(Style::default().fg(Color::Green)))
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .widths(&widths)
+        .highlight_style(highlight(app.focus == Focus::Repos));
+    f.render_stateful_widget(table, top[1], &mut app.repo_state);
+
+    let detail = match &selected_repo {
+        Some(repo) => {
+            let key = (
+                app.report().map(|r| r.org.clone()).unwrap_or_default(),
+                repo.name.clone(),
+            );
+            detail_text(repo, app.details.get(&key))
+        }
+        None => "".to_string(),
+    };
+    let detail = Paragraph::new(detail)
+        .block(Block::default().borders(Borders::ALL).title("Details"))
+        .wrap(Wrap { trim: true });
+    f.render_widget(detail, chunks[1]);
+
+    let error = match &app.expression {
+        Some(Err(e)) => format!("  {e}"),
+        _ => "".to_string(),
+    };
+    let help = if app.editing_filter {
+        format!(
+            "Filter: {}_  (Enter to apply, Esc to clear){error}",
+            app.filter
+        )
+    } else {
+        format!(
+            "q quit | Tab switch pane | ↑/↓ move | s sort | / filter | d compare snapshots{}{error}",
+            if app.filter.is_empty() {
+                "".to_string()
+            } else {
+                format!(" [{}]", app.filter)
+            }
+        )
+    };
+    f.render_widget(Paragraph::new(help), chunks[2]);
+}
+
+async fn event_loop(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    ctx: &Context,
+    app: &mut App,
+) -> io::Result<()> {
+    let style = ValueStyle::from_args(&ctx.args, Utc::now());
+    loop {
+        if let Some(key) = app.selected(ctx) {
+            if !app.details.contains_key(&key) {
+                terminal.draw(|f| draw(f, ctx, app, &style))?;
+                let detail = fetch::repo_detail(&ctx.github, &key.0, &key.1)
+                    .await
+                    .map_err(|e| e.to_string());
+                app.details.insert(key, detail);
+            }
+        }
+        terminal.draw(|f| draw(f, ctx, app, &style))?;
+
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        if app.editing_filter {
+            match key.code {
+                KeyCode::Enter => app.editing_filter = false,
+                KeyCode::Esc => {
+                    app.filter.clear();
+                    app.editing_filter = false;
+                }
+                KeyCode::Backspace => {
+                    app.filter.pop();
+                }
+                KeyCode::Char(c) => app.filter.push(c),
+                _ => {}
+            }
+            app.expression =
+                Filter::is_expression(&app.filter).then(|| Filter::parse(&app.filter, style.now));
+            app.repo_state.select(Some(0));
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Tab | KeyCode::Left | KeyCode::Right => {
+                app.focus = match app.focus {
+                    Focus::Orgs => Focus::Repos,
+                    Focus::Repos => Focus::Orgs,
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => app.move_selection(ctx, -1),
+            KeyCode::Down | KeyCode::Char('j') => app.move_selection(ctx, 1),
+            KeyCode::Char('s') => {
+                app.sort = match app.sort {
+                    None if !ctx.columns.is_empty() => Some(0),
+                    Some(i) if i + 1 < ctx.columns.len() => Some(i + 1),
+                    _ => None,
+                };
+                app.repo_state.select(Some(0));
+            }
+            KeyCode::Char('/') => app.editing_filter = true,
+            KeyCode::Char('d') => {
+                app.diff = !app.diff;
+                app.repo_state.select(Some(0));
+            }
+            KeyCode::Char('[') if app.diff => app.step_snapshot(false, 1),
+            KeyCode::Char(']') if app.diff => app.step_snapshot(false, -1),
+            KeyCode::Char('{') if app.diff => app.step_snapshot(true, 1),
+            KeyCode::Char('}') if app.diff => app.step_snapshot(true, -1),
+            _ => {}
+        }
+    }
+}
+
+pub async fn run(ctx: &Context, orgs: &[String], history: Vec<Snapshot>) -> Result<(), String> {
+    let now = Utc::now();
+    let mut reports = vec![];
+    for org_name in orgs {
+        eprintln!("Loading {org_name}...");
+        match scan::scan_org(ctx, org_name, now).await {
+            Ok(report) => reports.push(report),
+            Err(e) => {
+                ctx.diagnostics
+                    .emit(Kind::OrgNotFound, Some(org_name), None, &e.to_string());
+            }
+        }
+    }
+    let mut app = App {
+        reports,
+        org_state: ListState::default(),
+        repo_state: TableState::default(),
+        focus: Focus::Repos,
+        sort: None,
+        filter: String::new(),
+        expression: None,
+        editing_filter: false,
+        details: HashMap::new(),
+        history,
+        diff: false,
+        base: 1,
+        target: 0,
+    };
+    app.org_state.select(Some(0));
+    app.repo_state.select(Some(0));
+
+    enable_raw_mode().map_err(|e| e.to_string())?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen).map_err(|e| e.to_string())?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout)).map_err(|e| e.to_string())?;
+
+    let result = event_loop(&mut terminal, ctx, &mut app).await;
+
+    disable_raw_mode().map_err(|e| e.to_string())?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen).map_err(|e| e.to_string())?;
+    terminal.show_cursor().map_err(|e| e.to_string())?;
+    result.map_err(|e| e.to_string())
+}
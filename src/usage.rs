@@ -0,0 +1,92 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+
+use comfy_table::{Cell, Color, Table};
+
+use crate::client::TokenUsage;
+use crate::render;
+
+#[derive(Clone, Copy, Default)]
+pub struct GraphqlUsage {
+    pub queries: u32,
+    pub estimated: u32,
+    pub consumed: u32,
+    pub remaining: Option<u32>,
+}
+
+#[derive(Default)]
+pub struct Usage {
+    graphql: Mutex<GraphqlUsage>,
+    cache_hits: AtomicU32,
+    cache_misses: AtomicU32,
+}
+
+impl Usage {
+    pub fn record_graphql(&self, estimated: u32, consumed: u32, remaining: u32) {
+        let mut usage = self.graphql.lock().expect("usage lock ok");
+        usage.queries += 1;
+        usage.estimated += estimated;
+        usage.consumed += consumed;
+        usage.remaining = Some(remaining);
+    }
+
+    pub fn graphql(&self) -> GraphqlUsage {
+        *self.graphql.lock().expect("usage lock ok")
+    }
+
+    pub fn record_cache(&self, hit: bool) {
+        let counter = if hit {
+            &self.cache_hits
+        } else {
+            &self.cache_misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn cache(&self) -> (u32, u32) {
+        (
+            self.cache_hits.load(Ordering::Relaxed),
+            self.cache_misses.load(Ordering::Relaxed),
+        )
+    }
+
+    pub fn table(&self) -> Table {
+        let graphql = self.graphql();
+        let mut table = render::new_table();
+        table.set_header(vec![
+            Cell::new("API usage").fg(Color::Green),
+            Cell::new("Queries").fg(Color::Green),
+            Cell::new("Estimated points").fg(Color::Green),
+            Cell::new("Consumed points").fg(Color::Green),
+            Cell::new("Remaining").fg(Color::Green),
+        ]);
+        table.add_row(vec![
+            "GraphQL".to_string(),
+            graphql.queries.to_string(),
+            graphql.estimated.to_string(),
+            graphql.consumed.to_string(),
+            graphql.remaining.map(|r| r.to_string()).unwrap_or_default(),
+        ]);
+        table
+    }
+}
+
+pub fn token_table(tokens: &[TokenUsage]) -> Table {
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Token").fg(Color::Green),
+        Cell::new("Requests").fg(Color::Green),
+        Cell::new("Remaining").fg(Color::Green),
+    ]);
+    for (i, token) in tokens.iter().enumerate() {
+        table.add_row(vec![
+            format!("#{}", i + 1),
+            token.requests.to_string(),
+            token
+                .core
+                .map(|core| format!("{}/{}", core.remaining, core.limit))
+                .unwrap_or_default(),
+        ]);
+    }
+    table
+}
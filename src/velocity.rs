@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::history::Snapshot;
+use crate::stats::RepoStats;
+
+// Oldest recorded star count per (lowercase org, repository) within the window
+pub type Baselines = HashMap<(String, String), (DateTime<Utc>, u32)>;
+
+const DAYS_PER_MONTH: f64 = 30.44;
+const WINDOW_DAYS: i64 = 365;
+// Snapshots younger than this measure noise rather than growth
+const MIN_SPAN_DAYS: i64 = 7;
+
+pub fn baselines(history: &[Snapshot], now: DateTime<Utc>) -> Baselines {
+    let mut baselines = Baselines::new();
+    for snapshot in history.iter().filter(|s| {
+        now - s.collected_at <= Duration::days(WINDOW_DAYS)
+            && now - s.collected_at >= Duration::days(MIN_SPAN_DAYS)
+    }) {
+        for repo in &snapshot.repos {
+            let baseline = baselines
+                .entry((snapshot.org.to_lowercase(), repo.name.clone()))
+                .or_insert((snapshot.collected_at, repo.stars));
+            if snapshot.collected_at < baseline.0 {
+                *baseline = (snapshot.collected_at, repo.stars);
+            }
+        }
+    }
+    baselines
+}
+
+fn months(since: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+    (now - since).num_hours() as f64 / 24.0 / DAYS_PER_MONTH
+}
+
+// Exact growth since the oldest snapshot when there is one, the lifetime average otherwise
+pub fn stars_per_month(
+    repo: &RepoStats,
+    org: &str,
+    baselines: &Baselines,
+    now: DateTime<Utc>,
+) -> f64 {
+    match baselines.get(&(org.to_lowercase(), repo.name.clone())) {
+        // Unstarring outpacing new stars reads as no growth
+        Some((at, stars)) => ((repo.stars as f64 - *stars as f64) / months(*at, now)).max(0.0),
+        // Repositories younger than a month would otherwise project their first days
+        None => repo.stars as f64 / months(repo.created_at, now).max(1.0),
+    }
+}
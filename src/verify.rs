@@ -0,0 +1,137 @@
+use comfy_table::{Cell, Color, Table};
+
+use crate::diagnostics::Kind;
+use crate::graphql;
+use crate::render;
+use crate::scan::{self, Context, ListedRepo};
+use crate::stats::RepoStats;
+
+type Field = (&'static str, fn(&RepoStats) -> String);
+
+const FIELDS: [Field; 12] = [
+    ("description", |r| r.description.clone().unwrap_or_default()),
+    ("homepage", |r| r.homepage.clone().unwrap_or_default()),
+    ("topics", |r| {
+        let mut topics = r.topics.clone();
+        topics.sort();
+        topics.join(",")
+    }),
+    ("stars", |r| r.stars.to_string()),
+    ("forks", |r| r.forks.to_string()),
+    ("license", |r| r.license.clone().unwrap_or_default()),
+    ("open_issues", |r| r.open_issues_count.to_string()),
+    ("size", |r| r.size.to_string()),
+    ("created_at", |r| r.created_at.to_rfc3339()),
+    ("pushed_at", |r| {
+        r.pushed_at.map(|at| at.to_rfc3339()).unwrap_or_default()
+    }),
+    ("updated_at", |r| r.updated_at.to_rfc3339()),
+    ("default_branch", |r| {
+        r.default_branch.clone().unwrap_or_default()
+    }),
+];
+
+struct Mismatch {
+    org: String,
+    repo: String,
+    field: &'static str,
+    rest: String,
+    graphql: String,
+}
+
+fn compare(org: &str, rest: &ListedRepo, graphql: Option<&ListedRepo>) -> Vec<Mismatch> {
+    let name = rest.name.clone();
+    let mismatch = |field, rest_value, graphql_value| Mismatch {
+        org: org.to_string(),
+        repo: name.clone(),
+        field,
+        rest: rest_value,
+        graphql: graphql_value,
+    };
+    let Some(graphql) = graphql else {
+        return vec![mismatch("listed", "yes".to_string(), "no".to_string())];
+    };
+    let mut mismatches = vec![];
+    if rest.archived != graphql.archived {
+        mismatches.push(mismatch(
+            "archived",
+            rest.archived.to_string(),
+            graphql.archived.to_string(),
+        ));
+    }
+    let (Ok(a), Ok(b)) = (&rest.stats, &graphql.stats) else {
+        let error =
+            |stats: &Result<RepoStats, String>| stats.as_ref().err().cloned().unwrap_or_default();
+        mismatches.push(mismatch("stats", error(&rest.stats), error(&graphql.stats)));
+        return mismatches;
+    };
+    for (field, value) in FIELDS {
+        let (a, b) = (value(a), value(b));
+        if a != b {
+            mismatches.push(mismatch(field, a, b));
+        }
+    }
+    mismatches
+}
+
+fn table(mismatches: &[Mismatch]) -> Table {
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Organization").fg(Color::Green),
+        Cell::new("Repository").fg(Color::Green),
+        Cell::new("Field").fg(Color::Green),
+        Cell::new("REST").fg(Color::Green),
+        Cell::new("GraphQL").fg(Color::Green),
+    ]);
+    for m in mismatches {
+        table.add_row(vec![
+            Cell::new(&m.org),
+            Cell::new(&m.repo),
+            Cell::new(m.field),
+            Cell::new(&m.rest),
+            Cell::new(&m.graphql).fg(Color::Red),
+        ]);
+    }
+    table
+}
+
+// Both backends map into RepoStats, so any difference is drift in the API or in one of the mappings
+pub async fn run(ctx: &Context, orgs: &[String], sample: usize) -> Result<(), String> {
+    let mut mismatches = vec![];
+    let mut checked = 0;
+    for org_name in orgs {
+        let listings = futures::join!(
+            scan::rest_listing(ctx, org_name),
+            graphql::org_listing(
+                &ctx.github,
+                org_name,
+                ctx.args.graphql_max_cost,
+                ctx.args.visibility,
+                &ctx.usage
+            )
+        );
+        let (rest, graphql) = match listings {
+            (Ok(rest), Ok(graphql)) => (rest, graphql),
+            (Err(e), _) => {
+                ctx.diagnostics
+                    .emit(Kind::OrgNotFound, Some(org_name), None, &e.to_string());
+                continue;
+            }
+            (_, Err(e)) => return Err(format!("GraphQL listing of {org_name} failed: {e}")),
+        };
+        for repo in rest.repos.iter().take(sample) {
+            let other = graphql.repos.iter().find(|r| r.name == repo.name);
+            mismatches.extend(compare(org_name, repo, other));
+            checked += 1;
+        }
+    }
+    if mismatches.is_empty() {
+        println!("REST and GraphQL agree on {checked} repositories");
+        return Ok(());
+    }
+    println!("{}", table(&mismatches));
+    Err(format!(
+        "{} field mismatches between REST and GraphQL in {checked} repositories",
+        mismatches.len()
+    ))
+}
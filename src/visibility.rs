@@ -0,0 +1,57 @@
+use std::collections::BTreeMap;
+
+use clap::ValueEnum;
+use comfy_table::{Cell, Color, Table};
+use serde::Deserialize;
+
+use crate::render;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    All,
+    #[default]
+    Public,
+    Private,
+    Internal,
+}
+
+impl Visibility {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Visibility::All => "all",
+            Visibility::Public => "public",
+            Visibility::Private => "private",
+            Visibility::Internal => "internal",
+        }
+    }
+
+    // GraphQL only tells public from private; internal repositories are private there
+    pub fn privacy(&self) -> Option<&'static str> {
+        match self {
+            Visibility::All => None,
+            Visibility::Public => Some("PUBLIC"),
+            Visibility::Private | Visibility::Internal => Some("PRIVATE"),
+        }
+    }
+
+    // Dumps and caches from before visibility was recorded only held public repositories
+    pub fn matches(&self, visibility: Option<&str>) -> bool {
+        match self {
+            Visibility::All => true,
+            _ => visibility.unwrap_or("public") == self.name(),
+        }
+    }
+}
+
+pub fn table(visibilities: &BTreeMap<String, u32>) -> Table {
+    let mut table = render::new_table();
+    table.set_header(vec![
+        Cell::new("Visibility").fg(Color::Green),
+        Cell::new("Repositories").fg(Color::Green),
+    ]);
+    for (visibility, count) in visibilities {
+        table.add_row(vec![Cell::new(visibility), Cell::new(count)]);
+    }
+    table
+}
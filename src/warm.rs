@@ -0,0 +1,33 @@
+use std::path::Path;
+
+use chrono::Utc;
+
+use crate::diagnostics::Kind;
+use crate::dump;
+use crate::scan::{self, Context};
+
+pub async fn run(ctx: &Context, orgs: &[String], cache: &Path) -> Result<(), String> {
+    let now = Utc::now();
+    let mut repos = 0;
+    for org_name in orgs {
+        match scan::scan_org(ctx, org_name, now).await {
+            Ok(report) => repos += report.rows.len(),
+            Err(e) => {
+                ctx.diagnostics
+                    .emit(Kind::OrgNotFound, Some(org_name), None, &e.to_string());
+            }
+        }
+    }
+    let scanned = ctx.dump.as_ref().expect("warm collects a dump");
+    dump::update_cache(
+        cache,
+        &scanned.lock().expect("dump lock ok"),
+        ctx.cache.as_deref().unwrap_or_default(),
+    )?;
+    eprintln!(
+        "Warmed {} with {repos} repositories of {} organizations",
+        cache.display(),
+        orgs.len()
+    );
+    Ok(())
+}
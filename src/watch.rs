@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use chrono::Utc;
+
+use crate::diagnostics::Kind;
+use crate::format::Style;
+use crate::render::{self, Previous};
+use crate::scan::{self, Context};
+
+pub fn parse_interval(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let value: u64 = value
+        .parse()
+        .map_err(|_| format!("Invalid interval {s}, expected e.g. 30s, 5m, 1h or 7d"))?;
+    let seconds = match unit {
+        "" | "s" => value,
+        "m" => value * 60,
+        "h" => value * 3600,
+        "d" => value * 86400,
+        "w" => value * 604800,
+        _ => {
+            return Err(format!(
+                "Invalid interval unit {unit}, expected s, m, h, d or w"
+            ))
+        }
+    };
+    if seconds == 0 {
+        return Err("Interval must be greater than zero".to_string());
+    }
+    Ok(Duration::from_secs(seconds))
+}
+
+pub async fn run(ctx: &Context, orgs: &[String], interval: Duration) {
+    let mut previous: Option<Previous> = None;
+    // Changes are told apart on raw values relative to a fixed time
+    let started = Utc::now();
+    loop {
+        let now = Utc::now();
+        let style = Style::from_args(&ctx.args, now);
+        let mut reports = vec![];
+        let mut failures = vec![];
+        for org_name in orgs {
+            match scan::scan_org(ctx, org_name, now).await {
+                Ok(report) => reports.push(report),
+                Err(e) => {
+                    ctx.diagnostics
+                        .emit(Kind::OrgNotFound, Some(org_name), None, &e.to_string());
+                    failures.push(format!("Organization {org_name} not found {e}"));
+                }
+            }
+        }
+
+        print!("\x1B[2J\x1B[H");
+        println!(
+            "Refreshed {} (every {}s, Ctrl-C to stop)",
+            now.format("%Y-%m-%d %H:%M:%S UTC"),
+            interval.as_secs()
+        );
+        let mut current = Previous::new(started);
+        for report in &reports {
+            render::print_report(ctx, report, &style, previous.as_ref());
+            render::snapshot(ctx, report, &mut current);
+        }
+        for failure in failures {
+            println!("{failure}");
+        }
+        previous = Some(current);
+
+        tokio::time::sleep(interval).await;
+    }
+}
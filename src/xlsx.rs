@@ -0,0 +1,197 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use rust_xlsxwriter::{Color, Format, Workbook, Worksheet, XlsxError};
+
+use crate::columns::{CellValue, Column};
+use crate::format;
+use crate::metrics::{Metric, OrgMetrics};
+use crate::stats::{APPROXIMATE_FIELD, URL_FIELDS};
+use crate::summary::RunSummary;
+
+pub struct XlsxExport {
+    workbook: Workbook,
+    date_format: Format,
+    header_format: Format,
+    brand_color: Option<u32>,
+    summary_row: u32,
+}
+
+// Excel dates carry no zone, so they are written as wall-clock time in --timezone
+fn excel_date(at: DateTime<Utc>) -> f64 {
+    format::local(at).naive_local().timestamp() as f64 / 86400.0 + 25569.0
+}
+
+pub fn parse_color(hex: &str) -> Result<u32, String> {
+    let digits = hex.trim_start_matches('#');
+    match u32::from_str_radix(digits, 16) {
+        Ok(rgb) if digits.len() == 6 => Ok(rgb),
+        _ => Err(format!(
+            "Invalid brand color {hex}, expected six hex digits such as #0366d6"
+        )),
+    }
+}
+
+fn sheet_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '[' | ']' | ':' | '*' | '?' | '/' | '\\' => '_',
+            c => c,
+        })
+        .take(31)
+        .collect()
+}
+
+impl XlsxExport {
+    pub fn new(columns: &[Column], brand_color: Option<u32>) -> Result<Self, XlsxError> {
+        let header_format = match brand_color {
+            Some(rgb) => Format::new()
+                .set_bold()
+                .set_background_color(rgb)
+                .set_font_color(Color::White),
+            None => Format::new().set_bold(),
+        };
+        let mut export = Self {
+            workbook: Workbook::new(),
+            date_format: Format::new().set_num_format("yyyy-mm-dd hh:mm"),
+            header_format,
+            brand_color,
+            summary_row: 1,
+        };
+        let header_format = export.header_format.clone();
+        let summary = export.workbook.add_worksheet();
+        summary.set_name("Summary")?;
+        if let Some(rgb) = brand_color {
+            summary.set_tab_color(rgb);
+        }
+        summary.write_with_format(0, 0, "org", &header_format)?;
+        summary.write_with_format(0, 1, "org_created_at", &header_format)?;
+        for (i, field) in columns.iter().filter_map(Column::csv_field).enumerate() {
+            summary.write_with_format(0, i as u16 + 2, field, &header_format)?;
+        }
+        summary.write_with_format(0, Self::error_column(columns), "error", &header_format)?;
+        Ok(export)
+    }
+
+    fn write_value(
+        sheet: &mut Worksheet,
+        row: u32,
+        col: u16,
+        value: &CellValue,
+        date_format: &Format,
+    ) -> Result<(), XlsxError> {
+        match value {
+            CellValue::Number(number) => sheet.write(row, col, *number)?,
+            CellValue::Date(at) => {
+                sheet.write_with_format(row, col, excel_date(*at), date_format)?
+            }
+            CellValue::Text(text) => sheet.write(row, col, text.as_str())?,
+        };
+        Ok(())
+    }
+
+    pub fn add_org(
+        &mut self,
+        org: &OrgMetrics,
+        columns: &[Column],
+        custom: &[String],
+    ) -> Result<(), XlsxError> {
+        let sheet = self.workbook.add_worksheet();
+        sheet.set_name(sheet_name(&org.org))?;
+        if let Some(rgb) = self.brand_color {
+            sheet.set_tab_color(rgb);
+        }
+        sheet.write_with_format(0, 0, "repository", &self.header_format)?;
+        for (i, column) in columns.iter().enumerate() {
+            let name = column.csv_field().unwrap_or(column.title());
+            sheet.write_with_format(0, i as u16 + 1, name, &self.header_format)?;
+        }
+        let custom_col = columns.len() as u16 + 1;
+        for (i, name) in custom.iter().enumerate() {
+            sheet.write_with_format(0, custom_col + i as u16, name, &self.header_format)?;
+        }
+        let url_col = custom_col + custom.len() as u16;
+        for (i, field) in URL_FIELDS.iter().enumerate() {
+            sheet.write_with_format(0, url_col + i as u16, *field, &self.header_format)?;
+        }
+        let approximate_col = url_col + URL_FIELDS.len() as u16;
+        sheet.write_with_format(0, approximate_col, APPROXIMATE_FIELD, &self.header_format)?;
+        for (r, repo) in org.repos.iter().enumerate() {
+            let row = r as u32 + 1;
+            sheet.write(row, 0, repo.stats.name.as_str())?;
+            for (i, metric) in repo.metrics.iter().enumerate() {
+                Self::write_value(sheet, row, i as u16 + 1, &metric.value, &self.date_format)?;
+            }
+            for (i, name) in custom.iter().enumerate() {
+                if let Some(value) = repo.stats.custom.get(name) {
+                    sheet.write(row, custom_col + i as u16, value.as_str())?;
+                }
+            }
+            for (i, url) in repo.urls.iter().enumerate() {
+                sheet.write(row, url_col + i as u16, url.as_str())?;
+            }
+            sheet.write(row, approximate_col, repo.approximate_fields().as_str())?;
+        }
+        self.add_summary(&org.org, Some(org.created_year), &org.totals)
+    }
+
+    pub fn add_summary(
+        &mut self,
+        org: &str,
+        org_created_at: Option<i32>,
+        totals: &[Metric],
+    ) -> Result<(), XlsxError> {
+        let summary = self.workbook.worksheet_from_index(0)?;
+        summary.write(self.summary_row, 0, org)?;
+        if let Some(org_created_at) = org_created_at {
+            summary.write(self.summary_row, 1, org_created_at)?;
+        }
+        for (i, total) in totals
+            .iter()
+            .filter(|total| total.column.csv_field().is_some())
+            .enumerate()
+        {
+            Self::write_value(
+                summary,
+                self.summary_row,
+                i as u16 + 2,
+                &total.value,
+                &self.date_format,
+            )?;
+        }
+        self.summary_row += 1;
+        Ok(())
+    }
+
+    fn error_column(columns: &[Column]) -> u16 {
+        columns.iter().filter_map(Column::csv_field).count() as u16 + 2
+    }
+
+    // A placeholder row so a failed organization is not silently missing from the summary
+    pub fn add_failure(
+        &mut self,
+        org: &str,
+        error: &str,
+        columns: &[Column],
+    ) -> Result<(), XlsxError> {
+        let summary = self.workbook.worksheet_from_index(0)?;
+        summary.write(self.summary_row, 0, org)?;
+        summary.write(self.summary_row, Self::error_column(columns), error)?;
+        self.summary_row += 1;
+        Ok(())
+    }
+
+    pub fn add_run_summary(&mut self, run: &RunSummary) -> Result<(), XlsxError> {
+        let sheet = self.workbook.add_worksheet();
+        sheet.set_name("Run")?;
+        for (row, (name, value)) in run.rows().into_iter().enumerate() {
+            sheet.write(row as u32, 0, name)?;
+            sheet.write(row as u32, 1, value as f64)?;
+        }
+        Ok(())
+    }
+
+    pub fn save(&mut self, path: &Path) -> Result<(), XlsxError> {
+        self.workbook.save(path)
+    }
+}
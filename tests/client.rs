@@ -0,0 +1,125 @@
+mod common;
+
+use github_star_crawler::fetch;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn error(status: u16, message: &str) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_raw(
+        format!(r#"{{"message":"{message}","documentation_url":"https://docs.github.com/rest"}}"#),
+        "application/json",
+    )
+}
+
+#[tokio::test]
+async fn transient_errors_are_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/repos/acme/anvil/releases"))
+        .respond_with(error(502, "Server Error"))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    common::mock_get(
+        &server,
+        "/repos/acme/anvil/releases",
+        common::fixture("releases"),
+    )
+    .await;
+    let github = common::github(&server);
+
+    let downloads = fetch::downloads(&github, "acme", "anvil").await.unwrap();
+
+    assert_eq!(downloads.total, 350);
+    assert_eq!(github.requests(), 2);
+}
+
+// Gateways answer with an HTML page GitHub's error format cannot be read from
+#[tokio::test]
+async fn server_errors_without_a_json_body_are_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/repos/acme/anvil/releases"))
+        .respond_with(
+            ResponseTemplate::new(502)
+                .set_body_raw("<html><body>502 Bad Gateway</body></html>", "text/html"),
+        )
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    common::mock_get(
+        &server,
+        "/repos/acme/anvil/releases",
+        common::fixture("releases"),
+    )
+    .await;
+    let github = common::github(&server);
+
+    let downloads = fetch::downloads(&github, "acme", "anvil").await.unwrap();
+
+    assert_eq!(downloads.total, 350);
+    assert_eq!(github.requests(), 2);
+}
+
+#[tokio::test]
+async fn secondary_rate_limits_wait_for_retry_after() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/repos/acme/anvil/releases"))
+        .respond_with(
+            error(403, "You have exceeded a secondary rate limit")
+                .insert_header("retry-after", "1"),
+        )
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    common::mock_get(
+        &server,
+        "/repos/acme/anvil/releases",
+        common::fixture("releases"),
+    )
+    .await;
+    let github = common::github(&server);
+    let started = std::time::Instant::now();
+
+    let downloads = fetch::downloads(&github, "acme", "anvil").await.unwrap();
+
+    assert_eq!(downloads.total, 350);
+    assert!(started.elapsed().as_secs() >= 1);
+}
+
+#[tokio::test]
+async fn missing_repositories_are_not_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/repos/acme/gone/releases"))
+        .respond_with(error(404, "Not Found"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let github = common::github(&server);
+
+    assert!(fetch::downloads(&github, "acme", "gone").await.is_err());
+}
+
+// A write answered with a server error may still have been applied
+#[tokio::test]
+async fn failed_writes_are_not_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("PATCH"))
+        .and(path("/repos/acme/anvil"))
+        .respond_with(error(502, "Server Error"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let github = common::github(&server);
+
+    let result = github
+        .patch::<serde_json::Value, _, _>(
+            "repos/acme/anvil",
+            Some(&serde_json::json!({"description": "Forged tools"})),
+        )
+        .await;
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,48 @@
+// Each test crate uses only some of the helpers
+#![allow(dead_code)]
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use clap::Parser;
+use github_star_crawler::cli::Cli;
+use github_star_crawler::client::Github;
+use github_star_crawler::scan::Context;
+use octocrab::Octocrab;
+use serde_json::Value;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+pub fn github(server: &MockServer) -> Github {
+    let url = format!("{}/", server.uri());
+    let client = Octocrab::builder()
+        .base_url(url.as_str())
+        .and_then(|b| b.build())
+        .expect("mock client ok");
+    Github::new(vec![client], 2, Duration::from_millis(1), 1)
+}
+
+pub fn context(server: &MockServer, args: &[&str]) -> Context {
+    let args =
+        Cli::try_parse_from(std::iter::once("github_star_crawler").chain(args.iter().copied()))
+            .expect("test arguments ok");
+    Context::new(Arc::new(github(server)), args).expect("test context ok")
+}
+
+pub fn fixture(name: &str) -> ResponseTemplate {
+    let body = std::fs::read_to_string(format!("tests/fixtures/{name}.json")).expect("fixture ok");
+    ResponseTemplate::new(200).set_body_raw(body, "application/json")
+}
+
+pub fn fixture_json(name: &str) -> Value {
+    let body = std::fs::read_to_string(format!("tests/fixtures/{name}.json")).expect("fixture ok");
+    serde_json::from_str(&body).expect("fixture is JSON")
+}
+
+pub async fn mock_get(server: &MockServer, route: &str, response: ResponseTemplate) {
+    Mock::given(method("GET"))
+        .and(path(route))
+        .respond_with(response)
+        .mount(server)
+        .await;
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use github_star_crawler::{fetch, graphql};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn downloads_sum_every_release() {
+    let server = MockServer::start().await;
+    common::mock_get(
+        &server,
+        "/repos/acme/anvil/releases",
+        common::fixture("releases"),
+    )
+    .await;
+    let github = common::github(&server);
+
+    let downloads = fetch::downloads(&github, "acme", "anvil").await.unwrap();
+
+    assert_eq!(downloads.releases, 2);
+    assert_eq!(downloads.total, 350);
+    assert_eq!(downloads.latest, Some(50));
+    assert!(!downloads.truncated);
+}
+
+#[tokio::test]
+async fn commit_days_spread_weeks_over_their_days() {
+    let server = MockServer::start().await;
+    common::mock_get(
+        &server,
+        "/repos/acme/anvil/stats/commit_activity",
+        common::fixture("commit_activity"),
+    )
+    .await;
+    let github = common::github(&server);
+
+    let days = fetch::commit_days(&github, "acme", "anvil").await.unwrap();
+
+    let day = |d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+    assert_eq!(days.len(), 5);
+    assert_eq!(days.get(&day(5)), Some(&3));
+    assert_eq!(days.get(&day(15)), Some(&5));
+    assert_eq!(days.values().sum::<u32>(), 12);
+}
+
+#[tokio::test]
+async fn commit_days_fail_while_github_computes_them() {
+    let server = MockServer::start().await;
+    common::mock_get(
+        &server,
+        "/repos/acme/anvil/stats/commit_activity",
+        ResponseTemplate::new(202).set_body_raw("{}", "application/json"),
+    )
+    .await;
+    let github = common::github(&server);
+
+    assert!(fetch::commit_days(&github, "acme", "anvil").await.is_err());
+}
+
+#[tokio::test]
+async fn forks_since_counts_only_newer_forks() {
+    let server = MockServer::start().await;
+    common::mock_get(&server, "/repos/acme/anvil/forks", common::fixture("forks")).await;
+    let github = common::github(&server);
+    let since = Utc.with_ymd_and_hms(2026, 10, 5, 0, 0, 0).unwrap();
+
+    let (forks, truncated) = fetch::forks_since(&github, "acme", "anvil", since)
+        .await
+        .unwrap();
+
+    assert_eq!(forks, 2);
+    assert!(!truncated);
+}
+
+#[tokio::test]
+async fn stars_since_stops_at_the_first_older_star() {
+    let server = MockServer::start().await;
+    // A second page would be requested if the walk did not stop
+    Mock::given(method("POST"))
+        .and(path("/graphql"))
+        .respond_with(common::fixture("stargazers"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let github = common::github(&server);
+    let since = Utc.with_ymd_and_hms(2026, 10, 5, 0, 0, 0).unwrap();
+
+    let (stars, truncated) = graphql::stars_since(&github, "acme", "anvil", since)
+        .await
+        .unwrap();
+
+    assert_eq!(stars, 2);
+    assert!(!truncated);
+}
+
+#[tokio::test]
+async fn open_issue_once_skips_a_title_already_open() {
+    let server = MockServer::start().await;
+    common::mock_get(
+        &server,
+        "/search/issues",
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "items": [
+                { "number": 7, "title": "Archive candidate: no activity since 2024" },
+                { "number": 9, "title": "Archive candidate: no activity" },
+            ]
+        })),
+    )
+    .await;
+    Mock::given(method("POST"))
+        .and(path("/repos/acme/anvil/issues"))
+        .respond_with(ResponseTemplate::new(201))
+        .expect(0)
+        .mount(&server)
+        .await;
+    let github = common::github(&server);
+
+    let opened = fetch::open_issue_once(
+        &github,
+        "acme",
+        "anvil",
+        "Archive candidate: no activity",
+        "",
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(opened, None);
+}
+
+#[tokio::test]
+async fn security_alerts_fail_on_errors_other_than_a_disabled_feature() {
+    let server = MockServer::start().await;
+    let error = |status, message| {
+        ResponseTemplate::new(status).set_body_json(serde_json::json!({
+            "message": message,
+            "documentation_url": "https://docs.github.com/rest",
+        }))
+    };
+    common::mock_get(
+        &server,
+        "/repos/acme/anvil/dependabot/alerts",
+        error(403, "Dependabot alerts are disabled for this repository."),
+    )
+    .await;
+    common::mock_get(
+        &server,
+        "/repos/acme/anvil/code-scanning/alerts",
+        error(401, "Bad credentials"),
+    )
+    .await;
+    let github = common::github(&server);
+
+    assert!(fetch::security_alerts(&github, "acme", "anvil")
+        .await
+        .is_err());
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+use github_star_crawler::columns::Column;
+use github_star_crawler::format::Style;
+use github_star_crawler::scan;
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn scan_org_sums_the_listed_repositories() {
+    let server = MockServer::start().await;
+    common::mock_get(&server, "/orgs/acme", common::fixture("org")).await;
+    common::mock_get(&server, "/orgs/acme/repos", common::fixture("repos")).await;
+    let ctx = common::context(&server, &["--columns", "stars,forks,open_issues", "acme"]);
+    let now = Utc.with_ymd_and_hms(2026, 10, 16, 0, 0, 0).unwrap();
+
+    let report = scan::scan_org(&ctx, "acme", now).await.unwrap();
+
+    assert_eq!(report.name, "Acme Corporation");
+    assert_eq!(report.created_year, 2015);
+    // Most recently pushed first
+    let names: Vec<&str> = report.rows.iter().map(|(r, _)| r.name.as_str()).collect();
+    assert_eq!(names, ["anvil", "rocket-skates"]);
+    assert_eq!(report.stats.stars, 150);
+    assert_eq!(report.stats.forks, 16);
+    let style = Style::raw(now, ctx.args.units());
+    assert_eq!(Column::Stars.value(&report.rows[0].0, &style), "120");
+}
+
+#[tokio::test]
+async fn rest_listing_follows_every_page() {
+    let server = MockServer::start().await;
+    common::mock_get(&server, "/orgs/acme", common::fixture("org")).await;
+    // A full first page of copies of the first fixture repository, then the fixture itself
+    let template = common::fixture_json("repos")[0].clone();
+    let first_page: Vec<Value> = (0..100)
+        .map(|i| {
+            let mut repo = template.clone();
+            repo["id"] = json!(5000 + i);
+            repo["name"] = json!(format!("anvil-{i}"));
+            repo["full_name"] = json!(format!("acme/anvil-{i}"));
+            repo
+        })
+        .collect();
+    Mock::given(method("GET"))
+        .and(path("/orgs/acme/repos"))
+        .and(query_param("page", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(first_page))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/orgs/acme/repos"))
+        .and(query_param("page", "2"))
+        .respond_with(common::fixture("repos"))
+        .mount(&server)
+        .await;
+    let ctx = common::context(&server, &["acme"]);
+
+    let listing = scan::rest_listing(&ctx, "acme").await.unwrap();
+
+    assert_eq!(listing.repos.len(), 102);
+    assert!(listing
+        .repos
+        .iter()
+        .any(|repo| repo.name == "rocket-skates"));
+}
+
+#[tokio::test]
+async fn growth_counts_new_stars_and_forks_per_repository() {
+    let server = MockServer::start().await;
+    common::mock_get(&server, "/orgs/acme", common::fixture("org")).await;
+    common::mock_get(&server, "/orgs/acme/repos", common::fixture("repos")).await;
+    common::mock_get(&server, "/repos/acme/anvil/forks", common::fixture("forks")).await;
+    common::mock_get(
+        &server,
+        "/repos/acme/rocket-skates/forks",
+        common::fixture("forks"),
+    )
+    .await;
+    Mock::given(method("POST"))
+        .and(path("/graphql"))
+        .respond_with(common::fixture("stargazers"))
+        .mount(&server)
+        .await;
+    let mut ctx = common::context(&server, &["--columns", "stars,new_stars,new_forks", "acme"]);
+    ctx.last_runs.insert(
+        "acme".to_string(),
+        Utc.with_ymd_and_hms(2026, 10, 5, 0, 0, 0).unwrap(),
+    );
+    let now = Utc.with_ymd_and_hms(2026, 10, 16, 0, 0, 0).unwrap();
+
+    let report = scan::scan_org(&ctx, "acme", now).await.unwrap();
+
+    assert_eq!(report.stats.new_stars, 4);
+    assert_eq!(report.stats.new_forks, 4);
+}
+
+#[tokio::test]
+async fn graphql_query_errors_do_not_fall_back_to_rest() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/graphql"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": { "organization": null },
+            "errors": [{
+                "type": "NOT_FOUND",
+                "message": "Could not resolve to an Organization with the login of 'acme'.",
+            }],
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/orgs/acme/repos"))
+        .respond_with(common::fixture("repos"))
+        .expect(0)
+        .mount(&server)
+        .await;
+    let ctx = common::context(&server, &["--graphql", "acme"]);
+    let now = Utc.with_ymd_and_hms(2026, 10, 16, 0, 0, 0).unwrap();
+
+    let error = scan::scan_org(&ctx, "acme", now).await.err().unwrap();
+
+    assert!(error.contains("NOT_FOUND"));
+}
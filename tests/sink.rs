@@ -0,0 +1,85 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+use github_star_crawler::scan;
+use github_star_crawler::sink;
+use wiremock::MockServer;
+
+#[tokio::test]
+async fn csv_sink_writes_organization_and_repository_rows() {
+    let server = MockServer::start().await;
+    common::mock_get(&server, "/orgs/acme", common::fixture("org")).await;
+    common::mock_get(&server, "/orgs/acme/repos", common::fixture("repos")).await;
+    let dir = std::env::temp_dir().join(format!("repo-info-csv-{}", std::process::id()));
+    let out_dir = dir.to_str().unwrap();
+    let ctx = common::context(
+        &server,
+        &[
+            "--quiet",
+            "--format",
+            "csv",
+            "--out-dir",
+            out_dir,
+            "--columns",
+            "stars,forks,open_issues",
+            "acme",
+        ],
+    );
+    let now = Utc.with_ymd_and_hms(2026, 10, 16, 0, 0, 0).unwrap();
+    let report = scan::scan_org(&ctx, "acme", now).await.unwrap();
+
+    for mut sink in sink::open(&ctx, now, &[]).unwrap() {
+        sink.org(&ctx, &report).unwrap();
+    }
+
+    let index = std::fs::read_to_string(dir.join("index.csv")).unwrap();
+    let index: Vec<&str> = index.lines().collect();
+    assert_eq!(
+        index,
+        [
+            "schema_version,real_org_name,org_created_at,stars,forks,open_issues_count,error",
+            "3,Acme Corporation,2015,150,16,8,",
+        ]
+    );
+    let repos = std::fs::read_to_string(dir.join("acme.csv")).unwrap();
+    let repos: Vec<&str> = repos.lines().collect();
+    assert_eq!(
+        repos[0],
+        "repository,stars,forks,open_issues_count,html_url,ssh_url,clone_url,approximate"
+    );
+    assert_eq!(
+        repos[1],
+        "anvil,120,14,7,https://github.com/acme/anvil,git@github.com:acme/anvil.git,https://github.com/acme/anvil.git,"
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn csv_sink_refuses_to_append_under_another_header() {
+    let server = MockServer::start().await;
+    let dir = std::env::temp_dir().join(format!("repo-info-append-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("index.csv"),
+        "schema_version,collected_at,real_org_name,org_created_at,stars,error\n",
+    )
+    .unwrap();
+    let ctx = common::context(
+        &server,
+        &[
+            "--quiet",
+            "--format",
+            "csv",
+            "--append",
+            "--out-dir",
+            dir.to_str().unwrap(),
+            "--columns",
+            "stars,forks",
+            "acme",
+        ],
+    );
+    let now = Utc.with_ymd_and_hms(2026, 10, 16, 0, 0, 0).unwrap();
+
+    assert!(sink::open(&ctx, now, &[]).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}